@@ -1,6 +1,7 @@
 use wasm_bindgen::prelude::*;
 use math_universe_zigen::Dual;
 use math_universe_ryoshi::QuantumState;
+use math_universe_shinen::MultiVector;
 
 #[wasm_bindgen]
 pub fn setup() {
@@ -75,3 +76,50 @@ impl WasmQuantumState {
         p
     }
 }
+
+// --- Shinen Wrapper ---
+
+#[wasm_bindgen]
+pub struct WasmMultiVector {
+    inner: MultiVector,
+}
+
+#[wasm_bindgen]
+impl WasmMultiVector {
+    #[wasm_bindgen(constructor)]
+    pub fn new(coeffs: &[f64]) -> Self {
+        let mut c = [0.0; 8];
+        for (i, v) in coeffs.iter().enumerate().take(8) {
+            c[i] = *v;
+        }
+        Self { inner: MultiVector::new(c) }
+    }
+
+    /// Parses blade notation such as `"1 + 2e1 - 0.5e12"`.
+    pub fn parse(s: &str) -> Result<WasmMultiVector, JsValue> {
+        s.parse()
+            .map(|inner| WasmMultiVector { inner })
+            .map_err(|e: math_universe_shinen::ParseMultiVectorError| JsValue::from_str(&e.to_string()))
+    }
+
+    pub fn coeffs(&self) -> Vec<f64> {
+        self.inner.coeffs.to_vec()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.inner.to_string()
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.inner.latex().to_string()
+    }
+
+    pub fn add(&self, other: &WasmMultiVector) -> WasmMultiVector {
+        WasmMultiVector { inner: self.inner + other.inner }
+    }
+
+    pub fn mul(&self, other: &WasmMultiVector) -> WasmMultiVector {
+        WasmMultiVector { inner: self.inner * other.inner }
+    }
+}
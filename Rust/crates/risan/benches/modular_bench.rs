@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use math_universe_risan::{DynModular, Modular, ModulusContext, Reduction};
+use rand::prelude::*;
+
+const P: i64 = 4_611_686_018_427_388_039; // prime just above 2^62
+
+fn benchmark_products(c: &mut Criterion) {
+    // Multiply-accumulate over 10k random pairs
+    let mut rng = rand::thread_rng();
+    let pairs: Vec<(i64, i64)> = (0..10000).map(|_| (rng.gen_range(0..P), rng.gen_range(0..P))).collect();
+
+    c.bench_function("10k products (Modular<M>, %)", |b| {
+        let xs: Vec<(Modular<P>, Modular<P>)> = pairs.iter().map(|&(x, y)| (Modular::new(x), Modular::new(y))).collect();
+        b.iter(|| {
+            let mut acc = Modular::<P>::new(0);
+            for &(x, y) in &xs {
+                acc += x * y;
+            }
+            black_box(acc)
+        })
+    });
+
+    for reduction in [Reduction::Plain, Reduction::Barrett, Reduction::Montgomery] {
+        let ctx = ModulusContext::with_reduction(P as u64, reduction);
+        let xs: Vec<(DynModular, DynModular)> = pairs.iter().map(|&(x, y)| (ctx.element(x), ctx.element(y))).collect();
+        c.bench_function(&format!("10k products (DynModular, {:?})", reduction), |b| {
+            b.iter(|| {
+                let mut acc = ctx.element(0);
+                for (x, y) in &xs {
+                    acc += &(x * y);
+                }
+                black_box(acc)
+            })
+        });
+    }
+}
+
+fn benchmark_pow(c: &mut Criterion) {
+    // Modular exponentiation with a full-width exponent
+    let exp = (P - 2) as u64;
+    c.bench_function("pow (Modular<M>, %)", |b| b.iter(|| black_box(Modular::<P>::new(3)).pow(exp)));
+    for reduction in [Reduction::Plain, Reduction::Barrett, Reduction::Montgomery] {
+        let ctx = ModulusContext::with_reduction(P as u64, reduction);
+        c.bench_function(&format!("pow (DynModular, {:?})", reduction), |b| b.iter(|| black_box(ctx.element(3)).pow(exp)));
+    }
+}
+
+criterion_group!(benches, benchmark_products, benchmark_pow);
+criterion_main!(benches);
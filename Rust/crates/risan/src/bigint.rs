@@ -0,0 +1,1225 @@
+//! Arbitrary-precision integers: `BigUint` (magnitude as little-endian `u64` limbs) and
+//! `BigInt` (sign and magnitude).
+//!
+//! Multiplication switches from schoolbook to Karatsuba above `KARATSUBA_THRESHOLD`
+//! limbs; division is Knuth's Algorithm D.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Shl, Shr, Sub, SubAssign};
+use std::str::FromStr;
+
+use num_traits::{FromPrimitive, Num, One, Pow, Signed, ToPrimitive, Unsigned, Zero};
+
+use crate::modular::Modular;
+
+/// Operand size (in limbs) below which schoolbook multiplication is faster.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Non-negative integer of unbounded size.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /// Little-endian limbs without trailing zeros; zero is the empty vector.
+    limbs: Vec<u64>,
+}
+
+/// Signed integer of unbounded size. Zero is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+/// Error from parsing a big integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,
+    InvalidDigit,
+    InvalidRadix(u32),
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "cannot parse integer from empty string"),
+            Self::InvalidDigit => write!(f, "invalid digit found in string"),
+            Self::InvalidRadix(r) => write!(f, "radix {} is not in 2..=36", r),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+// Limb-slice kernels. Inputs may carry trailing zeros; outputs are normalized by callers.
+
+fn normalize(v: &mut Vec<u64>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+fn trimmed(v: &[u64]) -> &[u64] {
+    let n = v.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+    &v[..n]
+}
+
+fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    let (a, b) = (trimmed(a), trimmed(b));
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// `acc[offset..] += b`, growing `acc` as needed.
+fn add_into(acc: &mut Vec<u64>, b: &[u64], offset: usize) {
+    if acc.len() < offset + b.len() {
+        acc.resize(offset + b.len(), 0);
+    }
+    let mut carry = 0u64;
+    let mut i = 0;
+    while i < b.len() || carry != 0 {
+        if offset + i == acc.len() {
+            acc.push(0);
+        }
+        let s = acc[offset + i] as u128 + b.get(i).copied().unwrap_or(0) as u128 + carry as u128;
+        acc[offset + i] = s as u64;
+        carry = (s >> 64) as u64;
+        i += 1;
+    }
+}
+
+/// `acc -= b`; requires `acc >= b`.
+fn sub_into(acc: &mut [u64], b: &[u64]) {
+    let mut borrow = 0u64;
+    let mut i = 0;
+    while i < b.len() || borrow != 0 {
+        let (d1, o1) = acc[i].overflowing_sub(b.get(i).copied().unwrap_or(0));
+        let (d2, o2) = d1.overflowing_sub(borrow);
+        acc[i] = d2;
+        borrow = (o1 || o2) as u64;
+        i += 1;
+    }
+}
+
+fn mul_schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+    out
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = (trimmed(a), trimmed(b));
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    let h = a.len().max(b.len()) / 2;
+    if a.len() <= h || b.len() <= h {
+        // unbalanced: cut the long operand into pieces the size of the short one
+        let (long, short) = if a.len() > b.len() { (a, b) } else { (b, a) };
+        let mut out = vec![0u64; a.len() + b.len()];
+        for (k, chunk) in long.chunks(short.len()).enumerate() {
+            add_into(&mut out, &mul_limbs(chunk, short), k * short.len());
+        }
+        return out;
+    }
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h);
+    let z0 = mul_limbs(a0, b0);
+    let z2 = mul_limbs(a1, b1);
+    let mut sa = a0.to_vec();
+    add_into(&mut sa, a1, 0);
+    let mut sb = b0.to_vec();
+    add_into(&mut sb, b1, 0);
+    // z1 = (a0 + a1)(b0 + b1) - z0 - z2
+    let mut z1 = mul_limbs(&sa, &sb);
+    sub_into(&mut z1, &z0);
+    sub_into(&mut z1, &z2);
+    let mut out = z0;
+    out.reserve(a.len() + b.len());
+    add_into(&mut out, trimmed(&z1), h);
+    add_into(&mut out, trimmed(&z2), 2 * h);
+    out
+}
+
+/// Divide by a single limb, returning the quotient limbs and the remainder.
+fn div_rem_limb(a: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut q = vec![0u64; a.len()];
+    let mut r = 0u128;
+    for i in (0..a.len()).rev() {
+        let cur = (r << 64) | a[i] as u128;
+        q[i] = (cur / d as u128) as u64;
+        r = cur % d as u128;
+    }
+    (q, r as u64)
+}
+
+fn shl_limbs(a: &[u64], bits: usize) -> Vec<u64> {
+    let (words, bits) = (bits / 64, bits % 64);
+    let mut out = vec![0u64; words];
+    if bits == 0 {
+        out.extend_from_slice(a);
+    } else {
+        let mut carry = 0u64;
+        for &x in a {
+            out.push((x << bits) | carry);
+            carry = x >> (64 - bits);
+        }
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_limbs(a: &[u64], bits: usize) -> Vec<u64> {
+    let (words, bits) = (bits / 64, bits % 64);
+    if words >= a.len() {
+        return Vec::new();
+    }
+    let a = &a[words..];
+    if bits == 0 {
+        return a.to_vec();
+    }
+    (0..a.len())
+        .map(|i| (a[i] >> bits) | a.get(i + 1).map_or(0, |&next| next << (64 - bits)))
+        .collect()
+}
+
+/// Knuth's Algorithm D. `b` must be non-zero; both inputs normalized.
+fn div_rem_limbs(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    if cmp_limbs(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let (q, r) = div_rem_limb(a, b[0]);
+        return (q, vec![r]);
+    }
+    // scale so the divisor's top limb has its high bit set
+    let shift = b[b.len() - 1].leading_zeros() as usize;
+    let v = shl_limbs(b, shift);
+    let v = trimmed(&v);
+    let mut u = shl_limbs(a, shift);
+    if u.len() == a.len() {
+        u.push(0);
+    }
+    let n = v.len();
+    let m = u.len() - n - 1;
+    let mut q = vec![0u64; m + 1];
+    let (v1, v2) = (v[n - 1] as u128, v[n - 2] as u128);
+    for j in (0..=m).rev() {
+        let num = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let mut qhat = num / v1;
+        let mut rhat = num % v1;
+        while qhat >> 64 != 0 || qhat * v2 > ((rhat << 64) | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += v1;
+            if rhat >> 64 != 0 {
+                break;
+            }
+        }
+        // u[j..=j+n] -= qhat * v
+        let mut borrow = 0i128;
+        let mut carry = 0u128;
+        for i in 0..n {
+            let p = qhat * v[i] as u128 + carry;
+            carry = p >> 64;
+            let t = u[i + j] as i128 - borrow - (p as u64) as i128;
+            u[i + j] = t as u64;
+            borrow = (t < 0) as i128;
+        }
+        let t = u[j + n] as i128 - borrow - carry as i128;
+        u[j + n] = t as u64;
+        if t < 0 {
+            // qhat was one too large: add v back
+            qhat -= 1;
+            let mut c = 0u128;
+            for i in 0..n {
+                let s = u[i + j] as u128 + v[i] as u128 + c;
+                u[i + j] = s as u64;
+                c = s >> 64;
+            }
+            u[j + n] = u[j + n].wrapping_add(c as u64);
+        }
+        q[j] = qhat as u64;
+    }
+    (q, shr_limbs(&u[..n], shift))
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        normalize(&mut limbs);
+        Self { limbs }
+    }
+
+    /// Little-endian base-2^64 digits.
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    /// Number of significant bits; 0 for zero.
+    pub fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |&top| self.limbs.len() as u64 * 64 - top.leading_zeros() as u64)
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|&x| x % 2 == 0)
+    }
+
+    /// Quotient and remainder. Panics on division by zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+        let (q, r) = div_rem_limbs(&self.limbs, &rhs.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    /// Remainder modulo a machine word, without allocating a quotient.
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        assert!(m != 0, "attempt to calculate the remainder with a divisor of zero");
+        self.limbs.iter().rev().fold(0u128, |r, &x| ((r << 64) | x as u128) % m as u128) as u64
+    }
+
+    fn mul_u64(&self, x: u64) -> Self {
+        Self::from_limbs(mul_schoolbook(&self.limbs, &[x]))
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = std::mem::replace(&mut b, r);
+        }
+        a
+    }
+
+    /// `self^exp mod modulus` by square-and-multiply. Panics if `modulus` is zero.
+    pub fn modpow(&self, exp: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "modpow with zero modulus");
+        let mut acc = Self::one() % modulus;
+        let base = self % modulus;
+        for i in (0..exp.bits()).rev() {
+            acc = &acc * &acc % modulus;
+            if exp.limbs[(i / 64) as usize] >> (i % 64) & 1 == 1 {
+                acc = &acc * &base % modulus;
+            }
+        }
+        acc
+    }
+
+    /// Integer square root `floor(sqrt(self))` (Newton's method).
+    pub fn sqrt(&self) -> Self {
+        if self.is_zero() {
+            return Self::zero();
+        }
+        let mut x = Self::one() << self.bits().div_ceil(2) as usize;
+        loop {
+            let y = (&x + &(self / &x)) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // peel off chunks of `per` digits with single-limb divisions by radix^per
+        let (chunk, per) = max_radix_power(radix);
+        let mut parts = Vec::new();
+        let mut n = self.limbs.clone();
+        while !n.is_empty() {
+            let (q, r) = div_rem_limb(&n, chunk);
+            parts.push(r);
+            n = q;
+            normalize(&mut n);
+        }
+        let mut s = String::new();
+        for (i, &part) in parts.iter().rev().enumerate() {
+            let digits = u64_to_radix(part, radix);
+            if i > 0 {
+                s.extend(std::iter::repeat_n('0', per - digits.len()));
+            }
+            s.push_str(&digits);
+        }
+        s
+    }
+
+    /// Parses digits in `radix` (2..=36), case-insensitive, with an optional leading `+`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        if !(2..=36).contains(&radix) {
+            return Err(ParseBigIntError::InvalidRadix(radix));
+        }
+        let s = s.strip_prefix('+').unwrap_or(s);
+        if s.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        let (chunk, per) = max_radix_power(radix);
+        let mut acc = Self::zero();
+        let bytes = s.as_bytes();
+        let first = bytes.len() % per;
+        let mut start = 0;
+        while start < bytes.len() {
+            let end = if start == 0 && first != 0 { first } else { start + per };
+            let mut word = 0u64;
+            for &c in &bytes[start..end] {
+                let d = (c as char).to_digit(radix).ok_or(ParseBigIntError::InvalidDigit)?;
+                word = word * radix as u64 + d as u64;
+            }
+            let scale = if end - start == per { chunk } else { (radix as u64).pow((end - start) as u32) };
+            acc = acc.mul_u64(scale) + BigUint::from(word);
+            start = end;
+        }
+        Ok(acc)
+    }
+
+    /// `n!`, multiplied as a balanced product tree so the large products go through Karatsuba.
+    pub fn factorial(n: u64) -> Self {
+        product_range(1, n + 1)
+    }
+
+    /// Binomial coefficient `C(n, k)`; zero when `k > n`.
+    pub fn binomial(n: u64, k: u64) -> Self {
+        if k > n {
+            return Self::zero();
+        }
+        let k = k.min(n - k);
+        // C(n, k) = (n-k+1 ... n) / k!
+        &product_range(n - k + 1, n + 1) / &product_range(1, k + 1)
+    }
+}
+
+/// Product of `lo..hi` as a balanced binary tree.
+fn product_range(lo: u64, hi: u64) -> BigUint {
+    if hi <= lo {
+        return BigUint::one();
+    }
+    if hi - lo <= 16 {
+        let mut acc = BigUint::one();
+        let mut word = 1u64;
+        for x in lo..hi {
+            match word.checked_mul(x) {
+                Some(w) => word = w,
+                None => {
+                    acc = acc.mul_u64(word);
+                    word = x;
+                }
+            }
+        }
+        return acc.mul_u64(word);
+    }
+    let mid = lo + (hi - lo) / 2;
+    &product_range(lo, mid) * &product_range(mid, hi)
+}
+
+/// Largest power of `radix` fitting in a `u64`, and its exponent.
+fn max_radix_power(radix: u32) -> (u64, usize) {
+    let (mut p, mut k) = (radix as u64, 1);
+    while let Some(next) = p.checked_mul(radix as u64) {
+        p = next;
+        k += 1;
+    }
+    (p, k)
+}
+
+fn u64_to_radix(mut x: u64, radix: u32) -> String {
+    if x == 0 {
+        return "0".to_string();
+    }
+    let mut digits = Vec::new();
+    while x > 0 {
+        digits.push(std::char::from_digit((x % radix as u64) as u32, radix).unwrap());
+        x /= radix as u64;
+    }
+    digits.iter().rev().collect()
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        Self::from_limbs(vec![x])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(x: u128) -> Self {
+        Self::from_limbs(vec![x as u64, (x >> 64) as u64])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut out = self.limbs.clone();
+        add_into(&mut out, &rhs.limbs, 0);
+        BigUint::from_limbs(out)
+    }
+}
+
+/// Panics if the result would be negative.
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        assert!(*self >= *rhs, "attempt to subtract with overflow");
+        let mut out = self.limbs.clone();
+        sub_into(&mut out, &rhs.limbs);
+        BigUint::from_limbs(out)
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        BigUint::from_limbs(shl_limbs(&self.limbs, bits))
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        BigUint::from_limbs(shr_limbs(&self.limbs, bits))
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+// Owned and mixed operands forward to the by-reference impls.
+macro_rules! forward_binops {
+    ($t:ty; $($op:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl $op<$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $op<&$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $op<$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $assign<$t> for $t {
+            fn $assign_method(&mut self, rhs: $t) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $assign<&$t> for $t {
+            fn $assign_method(&mut self, rhs: &$t) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    )*};
+}
+
+forward_binops!(BigUint;
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+impl Zero for BigUint {
+    fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+}
+
+impl One for BigUint {
+    fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+}
+
+impl Num for BigUint {
+    type FromStrRadixErr = ParseBigIntError;
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        BigUint::from_str_radix(s, radix)
+    }
+}
+
+impl Unsigned for BigUint {}
+
+impl Pow<u32> for &BigUint {
+    type Output = BigUint;
+    fn pow(self, mut exp: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut acc = BigUint::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = &acc * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        acc
+    }
+}
+
+impl Pow<u32> for BigUint {
+    type Output = BigUint;
+    fn pow(self, exp: u32) -> BigUint {
+        (&self).pow(exp)
+    }
+}
+
+impl ToPrimitive for BigUint {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_u64().and_then(|x| i64::try_from(x).ok())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        match self.limbs.len() {
+            0..=2 => Some(self.limbs.iter().rev().fold(0u128, |acc, &x| (acc << 64) | x as u128)),
+            _ => None,
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        // the top two limbs carry all 53 significant bits
+        let n = self.limbs.len();
+        Some(match n {
+            0 => 0.0,
+            1 => self.limbs[0] as f64,
+            _ => {
+                let top = ((self.limbs[n - 1] as u128) << 64 | self.limbs[n - 2] as u128) as f64;
+                top * 2f64.powi(64 * (n as i32 - 2))
+            }
+        })
+    }
+}
+
+impl FromPrimitive for BigUint {
+    fn from_i64(x: i64) -> Option<Self> {
+        u64::try_from(x).ok().map(Self::from)
+    }
+
+    fn from_u64(x: u64) -> Option<Self> {
+        Some(Self::from(x))
+    }
+
+    fn from_u128(x: u128) -> Option<Self> {
+        Some(Self::from(x))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl fmt::Binary for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &self.to_str_radix(2))
+    }
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        Self { negative: negative && !magnitude.is_zero(), magnitude }
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// The magnitude, if non-negative.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        (!self.negative).then(|| self.magnitude.clone())
+    }
+
+    /// Quotient truncated toward zero and remainder with the sign of `self`, like `i64`.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = self.magnitude.div_rem(&rhs.magnitude);
+        (Self::from_parts(self.negative != rhs.negative, q), Self::from_parts(self.negative, r))
+    }
+
+    /// Non-negative remainder `self mod modulus` for positive `modulus`.
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let r = &self.magnitude % modulus;
+        if self.negative && !r.is_zero() { modulus - &r } else { r }
+    }
+
+    pub fn gcd(&self, other: &Self) -> BigUint {
+        self.magnitude.gcd(&other.magnitude)
+    }
+
+    /// Extended Euclid: `(g, x, y)` with `self * x + other * y = g = gcd(self, other)`.
+    pub fn extended_gcd(&self, other: &Self) -> (BigUint, BigInt, BigInt) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (BigInt::one(), BigInt::zero());
+        let (mut t0, mut t1) = (BigInt::zero(), BigInt::one());
+        while !r1.is_zero() {
+            let q = &r0 / &r1;
+            let r2 = &r0 - &(&q * &r1);
+            (r0, r1) = (r1, r2);
+            let s2 = &s0 - &(&q * &s1);
+            (s0, s1) = (s1, s2);
+            let t2 = &t0 - &(&q * &t1);
+            (t0, t1) = (t1, t2);
+        }
+        if r0.negative {
+            (r0, s0, t0) = (-r0, -s0, -t0);
+        }
+        (r0.magnitude, s0, t0)
+    }
+
+    /// `self^exp mod modulus` in `0..modulus`.
+    pub fn modpow(&self, exp: &BigUint, modulus: &BigUint) -> BigUint {
+        self.rem_euclid(modulus).modpow(exp, modulus)
+    }
+
+    /// Multiplicative inverse modulo `modulus`, if it exists.
+    pub fn mod_inverse(&self, modulus: &BigUint) -> Option<BigUint> {
+        let m = BigInt::from(modulus.clone());
+        let (g, x, _) = self.extended_gcd(&m);
+        g.is_one().then(|| x.rem_euclid(modulus))
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.magnitude.to_str_radix(radix);
+        if self.negative { format!("-{}", digits) } else { digits }
+    }
+
+    /// Parses an optionally signed number in `radix` (2..=36).
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        if negative && digits.starts_with('+') {
+            return Err(ParseBigIntError::InvalidDigit);
+        }
+        Ok(Self::from_parts(negative, BigUint::from_str_radix(digits, radix)?))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self { negative: false, magnitude }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(x: i128) -> Self {
+        Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(x: u64) -> Self {
+        Self::from(BigUint::from(x))
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::from_parts(self.negative, &self.magnitude - &rhs.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != rhs.negative, &self.magnitude * &rhs.magnitude)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+forward_binops!(BigInt;
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+impl Zero for BigInt {
+    fn zero() -> Self {
+        Self { negative: false, magnitude: BigUint::zero() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+}
+
+impl One for BigInt {
+    fn one() -> Self {
+        Self { negative: false, magnitude: BigUint::one() }
+    }
+}
+
+impl Num for BigInt {
+    type FromStrRadixErr = ParseBigIntError;
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        BigInt::from_str_radix(s, radix)
+    }
+}
+
+impl Signed for BigInt {
+    fn abs(&self) -> Self {
+        Self::from(self.magnitude.clone())
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other { Self::zero() } else { self - other }
+    }
+
+    fn signum(&self) -> Self {
+        match (self.negative, self.is_zero()) {
+            (_, true) => Self::zero(),
+            (true, _) => -Self::one(),
+            _ => Self::one(),
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        !self.negative && !self.is_zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+impl Pow<u32> for &BigInt {
+    type Output = BigInt;
+    fn pow(self, exp: u32) -> BigInt {
+        BigInt::from_parts(self.negative && exp % 2 == 1, (&self.magnitude).pow(exp))
+    }
+}
+
+impl Pow<u32> for BigInt {
+    type Output = BigInt;
+    fn pow(self, exp: u32) -> BigInt {
+        (&self).pow(exp)
+    }
+}
+
+impl ToPrimitive for BigInt {
+    fn to_i64(&self) -> Option<i64> {
+        let m = self.magnitude.to_u64()?;
+        if self.negative {
+            0i64.checked_sub_unsigned(m)
+        } else {
+            i64::try_from(m).ok()
+        }
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        if self.negative { None } else { self.magnitude.to_u64() }
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        let m = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(m)
+        } else {
+            i128::try_from(m).ok()
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        let m = self.magnitude.to_f64()?;
+        Some(if self.negative { -m } else { m })
+    }
+}
+
+impl FromPrimitive for BigInt {
+    fn from_i64(x: i64) -> Option<Self> {
+        Some(Self::from(x))
+    }
+
+    fn from_u64(x: u64) -> Option<Self> {
+        Some(Self::from(x))
+    }
+
+    fn from_i128(x: i128) -> Option<Self> {
+        Some(Self::from(x))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_str_radix(10))
+    }
+}
+
+// Conversions to and from Modular.
+
+impl<const M: i64> From<&BigUint> for Modular<M> {
+    fn from(x: &BigUint) -> Self {
+        Modular::new(x.rem_u64(M as u64) as i64)
+    }
+}
+
+impl<const M: i64> From<&BigInt> for Modular<M> {
+    fn from(x: &BigInt) -> Self {
+        let r = Modular::new(x.magnitude.rem_u64(M as u64) as i64);
+        if x.negative { -r } else { r }
+    }
+}
+
+impl<const M: i64> From<Modular<M>> for BigUint {
+    fn from(x: Modular<M>) -> Self {
+        BigUint::from(x.value() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::number_theory::pow_mod;
+
+    fn random(rng: &mut StdRng, limbs: usize) -> BigUint {
+        BigUint::from_limbs((0..limbs).map(|_| rng.gen()).collect())
+    }
+
+    #[test]
+    fn agrees_with_u128() {
+        let mut rng = StdRng::seed_from_u64(39);
+        for _ in 0..2000 {
+            let (a, b): (u128, u128) = (rng.gen::<u128>() >> rng.gen_range(0..128), rng.gen::<u128>() >> 64);
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            match a.checked_add(b) {
+                Some(sum) => assert_eq!(&x + &y, BigUint::from(sum)),
+                None => assert_eq!((&x + &y).bits(), 129),
+            }
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            if a >= b {
+                assert_eq!(&x - &y, BigUint::from(a - b));
+            }
+            let (c, d) = (a as u64, b as u64);
+            assert_eq!(BigUint::from(c) * BigUint::from(d), BigUint::from(c as u128 * d as u128));
+            if let Some(quotient) = a.checked_div(b) {
+                assert_eq!(x.div_rem(&y), (BigUint::from(quotient), BigUint::from(a % b)));
+            }
+            if let Some(r) = a.checked_rem(d as u128) {
+                assert_eq!(x.rem_u64(d), r as u64);
+            }
+            assert_eq!(x.to_string(), a.to_string());
+            assert_eq!(x.to_u128(), Some(a));
+            assert_eq!(x.bits(), 128 - a.leading_zeros() as u64);
+        }
+    }
+
+    #[test]
+    fn signed_arithmetic_agrees_with_i128() {
+        let mut rng = StdRng::seed_from_u64(40);
+        for _ in 0..2000 {
+            let (a, b) = (rng.gen::<i64>() as i128 * rng.gen::<i32>() as i128, rng.gen::<i64>() as i128);
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(&x + &y, BigInt::from(a + b));
+            assert_eq!(&x - &y, BigInt::from(a - b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            assert_eq!(x.to_string(), a.to_string());
+            if b != 0 {
+                let (q, r) = x.div_rem(&y);
+                assert_eq!((q, r), (BigInt::from(a / b), BigInt::from(a % b)));
+                let m = b.unsigned_abs();
+                assert_eq!(x.rem_euclid(&BigUint::from(m)), BigUint::from(a.rem_euclid(m as i128) as u128));
+            }
+        }
+        assert!(!(-BigInt::zero()).is_negative());
+        assert_eq!(BigInt::from(-7i64).div_rem(&BigInt::from(2i64)), (BigInt::from(-3i64), BigInt::from(-1i64)));
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for (m, n) in [(31, 31), (32, 32), (33, 90), (64, 64), (100, 7), (150, 200), (257, 40)] {
+            let (a, b) = (random(&mut rng, m), random(&mut rng, n));
+            let expected = BigUint::from_limbs(mul_schoolbook(&a.limbs, &b.limbs));
+            assert_eq!(&a * &b, expected, "{}x{} limbs", m, n);
+        }
+    }
+
+    #[test]
+    fn division_identity_on_large_operands() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for (m, n) in [(1, 1), (5, 1), (8, 3), (40, 39), (70, 33), (200, 64)] {
+            for _ in 0..20 {
+                let (a, b) = (random(&mut rng, m), random(&mut rng, n));
+                if b.is_zero() {
+                    continue;
+                }
+                let (q, r) = a.div_rem(&b);
+                assert!(r < b);
+                assert_eq!(&(&q * &b) + &r, a);
+                assert_eq!(&(&a * &b) / &b, a);
+            }
+        }
+        // top limbs that force the Algorithm D correction step
+        let a = BigUint::from_limbs(vec![0, 0, 0x8000_0000_0000_0000, u64::MAX]);
+        let b = BigUint::from_limbs(vec![1, 0x8000_0000_0000_0000, u64::MAX]);
+        let (q, r) = a.div_rem(&b);
+        assert!(r < b);
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn division_by_zero_panics() {
+        BigUint::from(3u64).div_rem(&BigUint::zero());
+    }
+
+    #[test]
+    fn shifts_and_powers() {
+        let mut rng = StdRng::seed_from_u64(43);
+        let a = random(&mut rng, 9);
+        for bits in [0, 1, 63, 64, 65, 200] {
+            let shifted = &a << bits;
+            assert_eq!(shifted, &a * &BigUint::from(2u64).pow(bits as u32));
+            assert_eq!(&shifted >> bits, a);
+        }
+        assert_eq!(BigUint::from(3u64).pow(40), BigUint::from(3u128.pow(40)));
+        assert_eq!(BigInt::from(-2i64).pow(63), BigInt::from(i64::MIN));
+    }
+
+    #[test]
+    fn radix_round_trips_and_errors() {
+        let mut rng = StdRng::seed_from_u64(44);
+        for radix in 2..=36 {
+            for limbs in [0, 1, 2, 13] {
+                let a = random(&mut rng, limbs);
+                let s = a.to_str_radix(radix);
+                assert_eq!(BigUint::from_str_radix(&s, radix), Ok(a.clone()));
+                let negative = -BigInt::from(a);
+                assert_eq!(BigInt::from_str_radix(&negative.to_str_radix(radix), radix), Ok(negative));
+            }
+        }
+        let x = BigUint::from(0xdead_beefu64);
+        assert_eq!(format!("{:x}", x), "deadbeef");
+        assert_eq!(format!("{:b}", BigUint::from(5u64)), "101");
+        assert_eq!("000123".parse::<BigUint>(), Ok(BigUint::from(123u64)));
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!("-+1".parse::<BigInt>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!(BigUint::from_str_radix("1", 37), Err(ParseBigIntError::InvalidRadix(37)));
+        assert_eq!("-0".parse::<BigInt>(), Ok(BigInt::zero()));
+    }
+
+    #[test]
+    fn factorial_binomial_and_sqrt() {
+        assert_eq!(BigUint::factorial(0), BigUint::one());
+        assert_eq!(BigUint::factorial(25).to_string(), "15511210043330985984000000");
+        assert_eq!(BigUint::binomial(100, 50).to_string(), "100891344545564193334812497256");
+        assert_eq!(BigUint::binomial(5, 7), BigUint::zero());
+        let mut rng = StdRng::seed_from_u64(45);
+        for limbs in [0, 1, 2, 5, 12] {
+            let n = random(&mut rng, limbs);
+            let s = n.sqrt();
+            let next = &s + &BigUint::one();
+            assert!(&s * &s <= n && n < &next * &next);
+        }
+        assert_eq!(BigUint::from(1u128 << 100).sqrt(), BigUint::from(1u64 << 50));
+    }
+
+    #[test]
+    fn modular_helpers() {
+        let mut rng = StdRng::seed_from_u64(46);
+        for _ in 0..200 {
+            let (b, e, m) = (rng.gen::<u64>(), rng.gen::<u64>(), rng.gen_range(1..u64::MAX));
+            let got = BigUint::from(b).modpow(&BigUint::from(e), &BigUint::from(m));
+            assert_eq!(got, BigUint::from(pow_mod(b, e, m)));
+        }
+        // Fermat's little theorem for the Mersenne prime 2^127 - 1
+        let p = BigUint::from(u128::MAX >> 1);
+        let exponent = &p - &BigUint::one();
+        assert!(BigUint::from(3u64).modpow(&exponent, &p).is_one());
+        assert_eq!(BigInt::from(-1i64).modpow(&BigUint::from(3u64), &BigUint::from(7u64)), BigUint::from(6u64));
+
+        for _ in 0..200 {
+            let (a, b) = (BigInt::from(rng.gen::<i64>()), BigInt::from(rng.gen::<i64>()));
+            let (g, x, y) = a.extended_gcd(&b);
+            assert_eq!(&(&a * &x) + &(&b * &y), BigInt::from(g.clone()));
+            assert_eq!(g, a.gcd(&b));
+        }
+        let m = BigUint::from(1_000_000_007u64);
+        let inverse = BigInt::from(-12345i64).mod_inverse(&m).unwrap();
+        assert!((&(&BigUint::from(1_000_000_007u64 - 12345) * &inverse) % &m).is_one());
+        assert_eq!(BigInt::from(6i64).mod_inverse(&BigUint::from(9u64)), None);
+    }
+
+    #[test]
+    fn modular_conversions() {
+        let x = BigUint::from(u128::MAX);
+        assert_eq!(Modular::<1_000_000_007>::from(&x).value() as u128, u128::MAX % 1_000_000_007);
+        assert_eq!(Modular::<7>::from(&BigInt::from(-10i64)).value(), 4);
+        assert_eq!(BigUint::from(Modular::<7>::new(12)), BigUint::from(5u64));
+    }
+}
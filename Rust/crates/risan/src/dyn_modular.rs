@@ -0,0 +1,416 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::modular::Modular;
+
+/// How a `ModulusContext` reduces double-width products.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// Hardware `%` on the 128-bit product.
+    Plain,
+    /// Barrett reduction with a precomputed `floor(2^128 / m)`.
+    Barrett,
+    /// Montgomery multiplication with `R = 2^64`; odd moduli only. Elements are kept
+    /// in Montgomery form internally.
+    Montgomery,
+}
+
+/// A runtime modulus with its precomputed reduction constants, shared by all
+/// `DynModular` values built from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModulusContext {
+    modulus: u64,
+    reduction: Reduction,
+    /// `floor((2^128 - 1) / m)`
+    barrett_mu: u128,
+    /// `-m^-1 mod 2^64`
+    montgomery_inv: u64,
+    /// `2^128 mod m`, converts into Montgomery form.
+    montgomery_r2: u64,
+}
+
+impl ModulusContext {
+    /// Context for `modulus`, using Montgomery reduction when it is odd and Barrett otherwise.
+    ///
+    /// Panics unless `0 < modulus < 2^63`.
+    pub fn new(modulus: u64) -> Arc<Self> {
+        let reduction = if modulus % 2 == 1 && modulus > 1 { Reduction::Montgomery } else { Reduction::Barrett };
+        Self::with_reduction(modulus, reduction)
+    }
+
+    /// Context with an explicit reduction strategy. Panics unless `0 < modulus < 2^63`,
+    /// or if Montgomery reduction is requested for an even modulus.
+    pub fn with_reduction(modulus: u64, reduction: Reduction) -> Arc<Self> {
+        assert!(modulus > 0 && modulus < 1 << 63, "modulus must be in 1..2^63");
+        assert!(
+            reduction != Reduction::Montgomery || modulus % 2 == 1,
+            "Montgomery reduction needs an odd modulus"
+        );
+        // Newton iteration doubles the correct low bits of m^-1 mod 2^64 each step
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.wrapping_mul(inv)));
+        }
+        let m = modulus as u128;
+        Arc::new(Self {
+            modulus,
+            reduction,
+            barrett_mu: u128::MAX / m,
+            montgomery_inv: inv.wrapping_neg(),
+            montgomery_r2: ((u128::MAX % m + 1) % m) as u64,
+        })
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    pub fn reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    /// Element of this context, reduced from any `i64`.
+    pub fn element(self: &Arc<Self>, value: i64) -> DynModular {
+        DynModular::new(value, self)
+    }
+
+    /// `x mod m` for `x < m^2`.
+    fn reduce_wide(&self, x: u128) -> u64 {
+        let m = self.modulus as u128;
+        match self.reduction {
+            Reduction::Plain | Reduction::Montgomery => (x % m) as u64,
+            Reduction::Barrett => {
+                let q = mul_hi(x, self.barrett_mu);
+                let mut r = x - q * m;
+                while r >= m {
+                    r -= m;
+                }
+                r as u64
+            }
+        }
+    }
+
+    /// Montgomery reduction: `x * 2^-64 mod m` for `x < m * 2^64`.
+    fn redc(&self, x: u128) -> u64 {
+        let u = (x as u64).wrapping_mul(self.montgomery_inv);
+        let t = ((x + u as u128 * self.modulus as u128) >> 64) as u64;
+        if t >= self.modulus { t - self.modulus } else { t }
+    }
+
+    /// Internal representation of a canonical residue.
+    fn encode(&self, v: u64) -> u64 {
+        match self.reduction {
+            Reduction::Montgomery => self.redc(v as u128 * self.montgomery_r2 as u128),
+            _ => v,
+        }
+    }
+
+    fn decode(&self, v: u64) -> u64 {
+        match self.reduction {
+            Reduction::Montgomery => self.redc(v as u128),
+            _ => v,
+        }
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        let x = a as u128 * b as u128;
+        match self.reduction {
+            Reduction::Montgomery => self.redc(x),
+            _ => self.reduce_wide(x),
+        }
+    }
+}
+
+/// High 128 bits of the 256-bit product `a * b`.
+fn mul_hi(a: u128, b: u128) -> u128 {
+    let (a0, a1) = (a as u64 as u128, a >> 64);
+    let (b0, b1) = (b as u64 as u128, b >> 64);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 as u64 as u128) + (p10 as u64 as u128);
+    p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64)
+}
+
+/// Integer modulo a runtime modulus held in a shared `ModulusContext`.
+///
+/// Mixing elements of different moduli panics. Elements of the same modulus from contexts
+/// with different reductions can be mixed: the right operand is converted into the left
+/// one's representation, and the result belongs to the left operand's context.
+#[derive(Debug, Clone)]
+pub struct DynModular {
+    /// Residue in the context's internal representation.
+    repr: u64,
+    ctx: Arc<ModulusContext>,
+}
+
+impl DynModular {
+    pub fn new(val: i64, ctx: &Arc<ModulusContext>) -> Self {
+        let v = (val as i128).rem_euclid(ctx.modulus as i128) as u64;
+        Self { repr: ctx.encode(v), ctx: Arc::clone(ctx) }
+    }
+
+    /// Canonical representative in `0..m`.
+    pub fn value(&self) -> u64 {
+        self.ctx.decode(self.repr)
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.ctx.modulus
+    }
+
+    pub fn context(&self) -> &Arc<ModulusContext> {
+        &self.ctx
+    }
+
+    fn with_repr(&self, repr: u64) -> Self {
+        Self { repr, ctx: Arc::clone(&self.ctx) }
+    }
+
+    /// `rhs` in this element's representation. Panics if the moduli differ.
+    fn operand(&self, rhs: &Self) -> u64 {
+        assert_eq!(self.ctx.modulus, rhs.ctx.modulus, "DynModular operands have different moduli");
+        if self.ctx.reduction == rhs.ctx.reduction {
+            rhs.repr
+        } else {
+            self.ctx.encode(rhs.value())
+        }
+    }
+
+    /// `self^exp` by repeated squaring.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = self.repr;
+        let mut acc = self.ctx.encode(1 % self.ctx.modulus);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = self.ctx.mul(acc, base);
+            }
+            base = self.ctx.mul(base, base);
+            exp >>= 1;
+        }
+        self.with_repr(acc)
+    }
+
+    /// Multiplicative inverse via the extended Euclidean algorithm, or `None` when
+    /// `gcd(self, m) != 1`.
+    pub fn inverse(&self) -> Option<Self> {
+        let (mut r0, mut r1) = (self.ctx.modulus as i128, self.value() as i128);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        (r0 == 1).then(|| {
+            let v = t0.rem_euclid(self.ctx.modulus as i128) as u64;
+            self.with_repr(self.ctx.encode(v))
+        })
+    }
+}
+
+/// Equal when the moduli and canonical values agree, whatever the reduction.
+impl PartialEq for DynModular {
+    fn eq(&self, other: &Self) -> bool {
+        self.ctx.modulus == other.ctx.modulus && self.value() == other.value()
+    }
+}
+
+impl Eq for DynModular {}
+
+impl<const M: i64> From<Modular<M>> for DynModular {
+    fn from(x: Modular<M>) -> Self {
+        DynModular::new(x.value(), &ModulusContext::new(M as u64))
+    }
+}
+
+/// Fails (returning the value back) when the runtime modulus is not `M`.
+impl<const M: i64> TryFrom<DynModular> for Modular<M> {
+    type Error = DynModular;
+    fn try_from(x: DynModular) -> Result<Self, DynModular> {
+        if x.modulus() == M as u64 {
+            Ok(Modular::new(x.value() as i64))
+        } else {
+            Err(x)
+        }
+    }
+}
+
+impl Add<&DynModular> for &DynModular {
+    type Output = DynModular;
+    fn add(self, rhs: &DynModular) -> DynModular {
+        let mut out = self.clone();
+        out += rhs;
+        out
+    }
+}
+
+impl Sub<&DynModular> for &DynModular {
+    type Output = DynModular;
+    fn sub(self, rhs: &DynModular) -> DynModular {
+        let mut out = self.clone();
+        out -= rhs;
+        out
+    }
+}
+
+impl Mul<&DynModular> for &DynModular {
+    type Output = DynModular;
+    fn mul(self, rhs: &DynModular) -> DynModular {
+        let mut out = self.clone();
+        out *= rhs;
+        out
+    }
+}
+
+/// Multiplication by the inverse. Panics if `rhs` is not invertible.
+impl Div<&DynModular> for &DynModular {
+    type Output = DynModular;
+    fn div(self, rhs: &DynModular) -> DynModular {
+        let mut out = self.clone();
+        out /= rhs;
+        out
+    }
+}
+
+impl Neg for &DynModular {
+    type Output = DynModular;
+    fn neg(self) -> DynModular {
+        let repr = if self.repr == 0 { 0 } else { self.ctx.modulus - self.repr };
+        self.with_repr(repr)
+    }
+}
+
+// In-place ops work on the representation and never touch the shared context.
+impl AddAssign<&DynModular> for DynModular {
+    fn add_assign(&mut self, rhs: &DynModular) {
+        // both < m < 2^63, so the sum cannot overflow
+        let s = self.repr + self.operand(rhs);
+        self.repr = if s >= self.ctx.modulus { s - self.ctx.modulus } else { s };
+    }
+}
+
+impl SubAssign<&DynModular> for DynModular {
+    fn sub_assign(&mut self, rhs: &DynModular) {
+        let r = self.operand(rhs);
+        self.repr = if self.repr >= r { self.repr - r } else { self.repr + self.ctx.modulus - r };
+    }
+}
+
+impl MulAssign<&DynModular> for DynModular {
+    fn mul_assign(&mut self, rhs: &DynModular) {
+        self.repr = self.ctx.mul(self.repr, self.operand(rhs));
+    }
+}
+
+impl DivAssign<&DynModular> for DynModular {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn div_assign(&mut self, rhs: &DynModular) {
+        *self *= &rhs.inverse().expect("division by a non-invertible element");
+    }
+}
+
+// Owned operands forward to the by-reference impls.
+macro_rules! forward_owned {
+    ($($op:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl $op for DynModular {
+            type Output = DynModular;
+            fn $method(mut self, rhs: DynModular) -> DynModular {
+                self.$assign_method(&rhs);
+                self
+            }
+        }
+
+        impl $assign for DynModular {
+            fn $assign_method(&mut self, rhs: DynModular) {
+                self.$assign_method(&rhs);
+            }
+        }
+    )*};
+}
+
+forward_owned!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign
+);
+
+impl Neg for DynModular {
+    type Output = DynModular;
+    fn neg(self) -> DynModular {
+        -&self
+    }
+}
+
+impl fmt::Display for DynModular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REDUCTIONS: [Reduction; 3] = [Reduction::Plain, Reduction::Barrett, Reduction::Montgomery];
+
+    #[test]
+    fn reductions_agree_with_modular() {
+        const P: i64 = 9_223_372_036_854_775_783;
+        for reduction in REDUCTIONS {
+            let ctx = ModulusContext::with_reduction(P as u64, reduction);
+            for (a, b) in [(P - 1, P - 1), (123_456_789_012, -987_654_321), (0, 5), (1, P - 2)] {
+                let (x, y) = (ctx.element(a), ctx.element(b));
+                let (ma, mb) = (Modular::<P>::new(a), Modular::<P>::new(b));
+                assert_eq!((&x * &y).value(), (ma * mb).value() as u64, "{:?}", reduction);
+                assert_eq!((&x + &y).value(), (ma + mb).value() as u64);
+                assert_eq!((&x - &y).value(), (ma - mb).value() as u64);
+                assert_eq!((-&x).value(), (-ma).value() as u64);
+                assert_eq!(x.pow(12345).value(), ma.pow(12345).value() as u64);
+            }
+            let x = ctx.element(10);
+            assert_eq!((&x / &x).value(), 1);
+            assert_eq!(x.inverse().map(|i| (&i * &x).value()), Some(1));
+        }
+    }
+
+    #[test]
+    fn even_and_unit_moduli() {
+        let ctx = ModulusContext::new(1 << 40);
+        assert_eq!(ctx.reduction(), Reduction::Barrett);
+        assert_eq!(ctx.element(-1).pow(2).value(), 1);
+        assert_eq!(ctx.element(2).inverse(), None);
+        let one = ModulusContext::new(1);
+        assert_eq!(one.element(5).pow(3).value(), 0);
+    }
+
+    #[test]
+    fn mixed_reductions() {
+        for a in REDUCTIONS {
+            for b in REDUCTIONS {
+                let x = ModulusContext::with_reduction(7, a).element(3);
+                let y = ModulusContext::with_reduction(7, b).element(2);
+                assert_eq!((&x * &y).value(), 6, "{:?} * {:?}", a, b);
+                assert_eq!((&x + &y).value(), 5);
+                assert_eq!((&x - &y).value(), 1);
+                assert_eq!((&y - &x).value(), 6);
+                assert_eq!((&x / &y).value(), 5);
+                assert_eq!(x, ModulusContext::with_reduction(7, b).element(10));
+            }
+        }
+        let plain = ModulusContext::with_reduction(7, Reduction::Plain).element(3);
+        assert_eq!((plain * DynModular::from(Modular::<7>::new(2))).value(), 6);
+    }
+
+    #[test]
+    fn modular_conversions() {
+        let x = DynModular::from(Modular::<11>::new(4));
+        assert_eq!(Modular::<11>::try_from(x.clone()), Ok(Modular::new(4)));
+        assert_eq!(Modular::<13>::try_from(x.clone()), Err(x));
+    }
+
+    #[test]
+    #[should_panic(expected = "different moduli")]
+    fn different_moduli_panic() {
+        let _ = ModulusContext::new(7).element(1) + ModulusContext::new(11).element(1);
+    }
+}
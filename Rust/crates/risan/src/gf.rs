@@ -0,0 +1,313 @@
+//! Finite fields GF(P^N), represented as `Modular<P>[x]` modulo a fixed monic
+//! irreducible polynomial of degree `N`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::{OnceLock, RwLock};
+
+use num_traits::Pow;
+
+use crate::bigint::BigUint;
+use crate::modular::Modular;
+use crate::poly::Poly;
+
+/// Lower coefficients of the defining polynomial for each `(P, N)`, computed on first use.
+type ModulusCache = RwLock<HashMap<(i64, usize), &'static [i64]>>;
+
+static MODULI: OnceLock<ModulusCache> = OnceLock::new();
+
+fn lower_coeffs(p: i64, n: usize, find: impl FnOnce() -> Vec<i64>) -> &'static [i64] {
+    let cache = MODULI.get_or_init(Default::default);
+    if let Some(&c) = cache.read().unwrap().get(&(p, n)) {
+        return c;
+    }
+    cache.write().unwrap().entry((p, n)).or_insert_with(|| Box::leak(find().into_boxed_slice()))
+}
+
+/// Element of GF(P^N) for a prime `P`, as coefficients of a polynomial of degree `< N`
+/// (lowest first). The defining polynomial is `Poly::<P>::irreducible(N)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GF<const P: i64, const N: usize> {
+    coeffs: [Modular<P>; N],
+}
+
+impl<const P: i64, const N: usize> GF<P, N> {
+    const DEGREE_IS_POSITIVE: () = assert!(N > 0, "GF<P, N> requires N > 0");
+
+    pub fn new(coeffs: [i64; N]) -> Self {
+        Self { coeffs: coeffs.map(Modular::new) }
+    }
+
+    pub fn zero() -> Self {
+        Self { coeffs: [Modular(0); N] }
+    }
+
+    pub fn one() -> Self {
+        Self::from(Modular::new(1))
+    }
+
+    /// The class of `x`, a root of the defining polynomial.
+    pub fn x() -> Self {
+        Self::from_poly(&Poly::x())
+    }
+
+    /// Reduce an arbitrary polynomial modulo the defining polynomial.
+    pub fn from_poly(p: &Poly<P>) -> Self {
+        let r = p % &Self::modulus();
+        Self { coeffs: std::array::from_fn(|i| r.coeff(i)) }
+    }
+
+    pub fn to_poly(&self) -> Poly<P> {
+        Poly::new(self.coeffs.to_vec())
+    }
+
+    pub fn coeffs(&self) -> &[Modular<P>; N] {
+        &self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|c| c.0 == 0)
+    }
+
+    /// Lower coefficients `m_0 .. m_{N-1}` of the monic defining polynomial.
+    fn modulus_lower() -> &'static [i64] {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::DEGREE_IS_POSITIVE;
+        lower_coeffs(P, N, || {
+            let m = Poly::<P>::irreducible(N);
+            m.coeffs()[..N].iter().map(|c| c.0).collect()
+        })
+    }
+
+    /// The defining irreducible polynomial of degree `N`.
+    pub fn modulus() -> Poly<P> {
+        let mut c: Vec<i64> = Self::modulus_lower().to_vec();
+        c.push(1);
+        Poly::from_ints(&c)
+    }
+
+    /// Number of elements, `P^N`.
+    pub fn order() -> BigUint {
+        BigUint::from(P as u64).pow(N as u32)
+    }
+
+    /// `self^exp` by repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut acc = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    /// The Frobenius automorphism `a -> a^P`.
+    pub fn frobenius(self) -> Self {
+        self.pow(P as u64)
+    }
+
+    /// Multiplicative inverse via the extended Euclidean algorithm, or `None` for zero.
+    pub fn inverse(self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let (_, s, _) = self.to_poly().extended_gcd(&Self::modulus());
+        Some(Self::from_poly(&s))
+    }
+
+    /// Sum of the conjugates `a + a^P + ... + a^(P^(N-1))`, which lies in GF(P).
+    pub fn trace(self) -> Modular<P> {
+        let mut t = self;
+        let mut sum = self;
+        for _ in 1..N {
+            t = t.frobenius();
+            sum += t;
+        }
+        sum.coeffs.first().copied().unwrap_or(Modular(0))
+    }
+
+    /// Product of the conjugates, which lies in GF(P).
+    pub fn norm(self) -> Modular<P> {
+        let mut t = self;
+        let mut prod = self;
+        for _ in 1..N {
+            t = t.frobenius();
+            prod *= t;
+        }
+        prod.coeffs.first().copied().unwrap_or(Modular(0))
+    }
+}
+
+/// Embedding of the prime field.
+impl<const P: i64, const N: usize> From<Modular<P>> for GF<P, N> {
+    fn from(c: Modular<P>) -> Self {
+        let mut coeffs = [Modular(0); N];
+        coeffs[0] = c;
+        Self { coeffs }
+    }
+}
+
+impl<const P: i64, const N: usize> Add for GF<P, N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self { coeffs: std::array::from_fn(|i| self.coeffs[i] + rhs.coeffs[i]) }
+    }
+}
+
+impl<const P: i64, const N: usize> Sub for GF<P, N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self { coeffs: std::array::from_fn(|i| self.coeffs[i] - rhs.coeffs[i]) }
+    }
+}
+
+impl<const P: i64, const N: usize> Mul for GF<P, N> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut t = vec![Modular::<P>(0); 2 * N - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                t[i + j] += a * b;
+            }
+        }
+        // x^N = -(m_0 + m_1 x + ... + m_{N-1} x^{N-1})
+        let m = Self::modulus_lower();
+        for k in (N..2 * N - 1).rev() {
+            let c = t[k];
+            for (ti, &mi) in t[k - N..k].iter_mut().zip(m) {
+                *ti -= c * Modular::new(mi);
+            }
+        }
+        Self { coeffs: std::array::from_fn(|i| t[i]) }
+    }
+}
+
+/// Multiplication by the inverse. Panics if `rhs` is zero.
+impl<const P: i64, const N: usize> Div for GF<P, N> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse().expect("division by zero in GF(p^n)")
+    }
+}
+
+impl<const P: i64, const N: usize> Neg for GF<P, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { coeffs: self.coeffs.map(|c| -c) }
+    }
+}
+
+impl<const P: i64, const N: usize> AddAssign for GF<P, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const P: i64, const N: usize> SubAssign for GF<P, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const P: i64, const N: usize> MulAssign for GF<P, N> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const P: i64, const N: usize> DivAssign for GF<P, N> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const P: i64, const N: usize> fmt::Display for GF<P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_poly())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements<const P: i64, const N: usize>() -> impl Iterator<Item = GF<P, N>> {
+        (0..(P as u64).pow(N as u32)).map(|mut k| {
+            GF::new(std::array::from_fn(|_| {
+                let c = k % P as u64;
+                k /= P as u64;
+                c as i64
+            }))
+        })
+    }
+
+    #[test]
+    fn defining_polynomial() {
+        let m = GF::<3, 4>::modulus();
+        assert_eq!(m.degree(), Some(4));
+        assert!(m.is_irreducible());
+        assert_eq!(GF::<3, 4>::order(), BigUint::from(81u64));
+        // x is a root of the defining polynomial
+        let x = GF::<3, 4>::x();
+        let value = m.coeffs().iter().rev().fold(GF::zero(), |acc, &c| acc * x + GF::from(c));
+        assert!(value.is_zero());
+    }
+
+    #[test]
+    fn gf256_is_a_field_with_a_cyclic_group() {
+        let mut generators = 0;
+        for a in elements::<2, 8>().skip(1) {
+            assert_eq!(a * a.inverse().unwrap(), GF::one());
+            assert_eq!(a.pow(255), GF::one());
+            if [3, 5, 17].iter().all(|&q| a.pow(255 / q) != GF::one()) {
+                generators += 1;
+            }
+        }
+        // Euler's phi(255)
+        assert_eq!(generators, 128);
+        assert_eq!(GF::<2, 8>::zero().inverse(), None);
+    }
+
+    #[test]
+    fn multiplication_reduces_the_polynomial_product() {
+        let all: Vec<GF<5, 2>> = elements().collect();
+        for &a in &all {
+            for &b in &all {
+                assert_eq!(a * b, GF::from_poly(&(&a.to_poly() * &b.to_poly())));
+                if !b.is_zero() {
+                    assert_eq!((a / b) * b, a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn frobenius_trace_and_norm() {
+        let all: Vec<GF<7, 3>> = elements().collect();
+        let (a, b) = (all[100], all[257]);
+        assert_eq!((a + b).frobenius(), a.frobenius() + b.frobenius());
+        assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+        assert_eq!(a.frobenius().frobenius().frobenius(), a);
+        assert_eq!((a + b).trace(), a.trace() + b.trace());
+        assert_eq!((a * b).norm(), a.norm() * b.norm());
+        let c = GF::<7, 3>::from(Modular::new(3));
+        assert_eq!(c.trace(), Modular::new(9));
+        assert_eq!(c.norm(), Modular::new(27));
+        // the trace is onto GF(7), every value taken 7^2 times
+        let zeros = all.iter().filter(|x| x.trace().0 == 0).count();
+        assert_eq!(zeros, 49);
+        assert_eq!(GF::<7, 3>::x().to_string(), "x");
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn division_by_zero_panics() {
+        let _ = GF::<3, 2>::one() / GF::zero();
+    }
+}
@@ -1,13 +1,89 @@
-use std::collections::HashMap;
+use std::fmt;
+
+pub mod centrality;
+pub mod clustering;
+pub mod community;
+pub mod connectivity;
+pub mod csr;
+pub mod flow;
+pub mod io;
+pub mod matching;
+pub mod matrix;
+pub mod mst;
+pub mod shortest_path;
+pub mod spectral;
+pub mod traversal;
+pub mod union_find;
+pub mod views;
+pub mod visit;
+
+pub use centrality::{
+    betweenness_centrality, closeness_centrality, degree_centrality, eigenvector_centrality, katz_centrality, pagerank,
+};
+pub use clustering::{
+    average_clustering, core_numbers, global_clustering, k_core, local_clustering, triangle_count, triangles,
+};
+pub use community::{label_propagation, louvain, modularity, Communities};
+pub use connectivity::{
+    articulation_points, bipartition, bridges, is_bipartite, is_cyclic_undirected, kosaraju_scc,
+    strongly_connected_components, tarjan_scc, weakly_connected_components,
+};
+pub use csr::CsrGraph;
+pub use flow::{dinic, min_cost_max_flow, min_cut, push_relabel, MaxFlow, MinCostFlow, MinCut};
+pub use io::{
+    attribute, read_dot, read_edge_list, read_graphml, read_matrix_market, write_dot, write_edge_list, write_graphml,
+    write_matrix_market, Attributes, EdgeListFormat, MatrixField, ReadGraphError,
+};
+pub use matching::{
+    hopcroft_karp, hungarian, hungarian_partial, maximum_bipartite_matching, min_weight_assignment, Assignment, Matching,
+};
+pub use matrix::{
+    adjacency_matrix, degree_matrix, laplacian_matrix, normalized_laplacian_matrix, symmetric_eigen, transition_matrix,
+    SparseMatrix,
+};
+pub use mst::{kruskal, prim, SpanningForest};
+pub use shortest_path::{astar, bellman_ford, dijkstra, floyd_warshall, johnson, AllPairsPaths, NegativeCycle, ShortestPaths};
+pub use spectral::{algebraic_connectivity, fiedler_vector, spectral_clustering};
+pub use traversal::{
+    breadth_first_search, depth_first_search, find_cycle, is_cyclic_directed, topological_sort, Bfs, BfsEvent, Control,
+    Cycle, Dfs, DfsEvent,
+};
+pub use union_find::UnionFind;
+pub use views::{EdgeFiltered, ImplicitEdge, ImplicitGraph, NodeFiltered, OrientedEdge, Reversed, Undirected};
+pub use visit::{
+    Direction, EdgeRef, EdgeReference, GraphBase, GraphProp, IntoEdgeReferences, IntoEdges, IntoEdgesDirected,
+    IntoNeighbors, IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount, NodeIndexable,
+};
 
 /// Unique identifier for a Node in the Graph.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Ids are slot indices that are never reused, so an id stays valid until its node is
+/// removed and afterwards is reported as missing rather than aliasing a newer node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct NodeId(pub usize);
 
-/// Unique identifier for an Edge in the Graph.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Unique identifier for an Edge in the Graph. Never reused, like `NodeId`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct EdgeId(pub usize);
 
+/// Error from a graph operation given an id that does not refer to a live node or edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError {
+    NodeNotFound(NodeId),
+    EdgeNotFound(EdgeId),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NodeNotFound(id) => write!(f, "node {} does not exist or was removed", id.0),
+            Self::EdgeNotFound(id) => write!(f, "edge {} does not exist or was removed", id.0),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
 #[derive(Debug, Clone)]
 pub struct Node<N> {
     pub data: N,
@@ -22,10 +98,22 @@ pub struct Edge<E> {
     pub to: NodeId,
 }
 
-#[derive(Debug, Clone, Default)]
+/// Directed multigraph with node data `N` and edge data `E`.
+///
+/// Removal leaves a tombstone in the slot, so per-node arrays in algorithms are sized by
+/// `node_bound()` and iterate over `node_ids()`.
+#[derive(Debug, Clone)]
 pub struct Graph<N, E> {
-    pub nodes: Vec<Node<N>>,
-    pub edges: Vec<Edge<E>>,
+    nodes: Vec<Option<Node<N>>>,
+    edges: Vec<Option<Edge<E>>>,
+    node_count: usize,
+    edge_count: usize,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<N, E> Graph<N, E> {
@@ -33,55 +121,289 @@ impl<N, E> Graph<N, E> {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
+            node_count: 0,
+            edge_count: 0,
         }
     }
 
     pub fn add_node(&mut self, data: N) -> NodeId {
         let id = NodeId(self.nodes.len());
-        self.nodes.push(Node {
+        self.nodes.push(Some(Node {
             data,
             outgoing_edges: Vec::new(),
             incoming_edges: Vec::new(),
-        });
+        }));
+        self.node_count += 1;
         id
     }
 
-    pub fn add_edge(&mut self, from: NodeId, to: NodeId, data: E) -> Result<EdgeId, String> {
-        if from.0 >= self.nodes.len() || to.0 >= self.nodes.len() {
-            return Err("Node index out of bounds".to_string());
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, data: E) -> Result<EdgeId, GraphError> {
+        for id in [from, to] {
+            if !self.contains_node(id) {
+                return Err(GraphError::NodeNotFound(id));
+            }
         }
 
         let id = EdgeId(self.edges.len());
-        self.edges.push(Edge { data, from, to });
+        self.edges.push(Some(Edge { data, from, to }));
+        self.edge_count += 1;
 
         // Update connectivity
-        self.nodes[from.0].outgoing_edges.push(id);
-        self.nodes[to.0].incoming_edges.push(id);
+        self.node_slot_mut(from).outgoing_edges.push(id);
+        self.node_slot_mut(to).incoming_edges.push(id);
 
         Ok(id)
     }
 
+    fn node_slot_mut(&mut self, id: NodeId) -> &mut Node<N> {
+        self.nodes[id.0].as_mut().expect("live node")
+    }
+
+    /// Remove an edge, returning its data.
+    pub fn remove_edge(&mut self, id: EdgeId) -> Result<E, GraphError> {
+        let edge = self.edges.get_mut(id.0).and_then(Option::take).ok_or(GraphError::EdgeNotFound(id))?;
+        self.edge_count -= 1;
+        self.node_slot_mut(edge.from).outgoing_edges.retain(|&e| e != id);
+        self.node_slot_mut(edge.to).incoming_edges.retain(|&e| e != id);
+        Ok(edge.data)
+    }
+
+    /// Remove a node and every edge incident to it, returning the node's data.
+    pub fn remove_node(&mut self, id: NodeId) -> Result<N, GraphError> {
+        let node = self.nodes.get_mut(id.0).and_then(Option::take).ok_or(GraphError::NodeNotFound(id))?;
+        self.node_count -= 1;
+        for &eid in node.outgoing_edges.iter().chain(&node.incoming_edges) {
+            // self-loops are listed twice; the second visit finds the slot empty
+            if let Some(edge) = self.edges[eid.0].take() {
+                self.edge_count -= 1;
+                let other = if edge.from == id { edge.to } else { edge.from };
+                if let Some(n) = self.nodes[other.0].as_mut() {
+                    n.outgoing_edges.retain(|&e| e != eid);
+                    n.incoming_edges.retain(|&e| e != eid);
+                }
+            }
+        }
+        Ok(node.data)
+    }
+
+    /// Keep only the nodes for which `keep` returns true, removing the others with their edges.
+    pub fn retain_nodes(&mut self, mut keep: impl FnMut(NodeId, &N) -> bool) {
+        let doomed: Vec<NodeId> = self.node_ids().filter(|&id| !keep(id, &self.node(id).unwrap().data)).collect();
+        for id in doomed {
+            self.remove_node(id).unwrap();
+        }
+    }
+
+    /// Keep only the edges for which `keep` returns true.
+    pub fn retain_edges(&mut self, mut keep: impl FnMut(EdgeId, &E) -> bool) {
+        let doomed: Vec<EdgeId> = self.edge_ids().filter(|&id| !keep(id, &self.edge(id).unwrap().data)).collect();
+        for id in doomed {
+            self.remove_edge(id).unwrap();
+        }
+    }
+
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        matches!(self.nodes.get(id.0), Some(Some(_)))
+    }
+
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        matches!(self.edges.get(id.0), Some(Some(_)))
+    }
+
+    /// Number of live nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Number of live edges.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Upper bound on `NodeId` indices, for sizing per-node arrays.
+    pub fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Upper bound on `EdgeId` indices, for sizing per-edge arrays.
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Ids of live nodes in increasing order.
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().enumerate().filter(|(_, n)| n.is_some()).map(|(i, _)| NodeId(i))
+    }
+
+    /// Ids of live edges in increasing order.
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.edges.iter().enumerate().filter(|(_, e)| e.is_some()).map(|(i, _)| EdgeId(i))
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node<N>> {
+        self.nodes.get(id.0)?.as_ref()
+    }
+
+    pub fn edge(&self, id: EdgeId) -> Option<&Edge<E>> {
+        self.edges.get(id.0)?.as_ref()
+    }
+
+    /// Live edges with their ids.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, &Edge<E>)> + '_ {
+        self.edges.iter().enumerate().filter_map(|(i, e)| e.as_ref().map(|e| (EdgeId(i), e)))
+    }
+
+    /// Outgoing edge ids of a node; empty for missing nodes.
+    pub fn outgoing(&self, id: NodeId) -> &[EdgeId] {
+        self.node(id).map_or(&[], |n| &n.outgoing_edges)
+    }
+
+    /// Incoming edge ids of a node; empty for missing nodes.
+    pub fn incoming(&self, id: NodeId) -> &[EdgeId] {
+        self.node(id).map_or(&[], |n| &n.incoming_edges)
+    }
+
+    /// Source and target of an edge.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Option<(NodeId, NodeId)> {
+        self.edge(id).map(|e| (e.from, e.to))
+    }
+
+    /// Outgoing edges of a node with their ids; empty for missing nodes.
+    pub fn edges_from(&self, id: NodeId) -> impl Iterator<Item = (EdgeId, &Edge<E>)> + '_ {
+        self.outgoing(id).iter().map(move |&eid| (eid, self.edges[eid.0].as_ref().unwrap()))
+    }
+
+    /// Incoming edges of a node with their ids; empty for missing nodes.
+    pub fn edges_to(&self, id: NodeId) -> impl Iterator<Item = (EdgeId, &Edge<E>)> + '_ {
+        self.incoming(id).iter().map(move |&eid| (eid, self.edges[eid.0].as_ref().unwrap()))
+    }
+
+    /// All edges from `from` to `to`, in insertion order.
+    pub fn edges_between(&self, from: NodeId, to: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.edges_from(from).filter(move |(_, e)| e.to == to).map(|(id, _)| id)
+    }
+
+    /// The first edge from `from` to `to`, if any.
+    pub fn find_edge(&self, from: NodeId, to: NodeId) -> Option<EdgeId> {
+        self.edges_between(from, to).next()
+    }
+
     pub fn get_node(&self, id: NodeId) -> Option<&N> {
-        self.nodes.get(id.0).map(|n| &n.data)
+        self.node(id).map(|n| &n.data)
     }
-    
+
     pub fn get_node_mut(&mut self, id: NodeId) -> Option<&mut N> {
-        self.nodes.get_mut(id.0).map(|n| &mut n.data)
+        self.nodes.get_mut(id.0)?.as_mut().map(|n| &mut n.data)
     }
 
     pub fn get_edge(&self, id: EdgeId) -> Option<&E> {
-        self.edges.get(id.0).map(|e| &e.data)
+        self.edge(id).map(|e| &e.data)
+    }
+
+    pub fn get_edge_mut(&mut self, id: EdgeId) -> Option<&mut E> {
+        self.edges.get_mut(id.0)?.as_mut().map(|e| &mut e.data)
     }
 
     /// Iterator over neighbors (outgoing).
     pub fn neighbors(&self, id: NodeId) -> Option<impl Iterator<Item = NodeId> + '_> {
-        self.nodes.get(id.0).map(|node| {
-            node.outgoing_edges.iter().map(move |&eid| {
-                // We assume edges are valid if they exist in the list, but for safety we could check
-                // However, internal consistency should be maintained by add_edge.
-                // The edge stores 'to', so we return that.
-                self.edges[eid.0].to
-            })
-        })
+        self.node(id).map(|_| self.edges_from(id).map(|(_, e)| e.to))
+    }
+}
+
+/// Test fixture: nodes `0..n` with unit data and one edge per `(from, to, data)` triple,
+/// added in order so edge ids follow the slice.
+#[cfg(test)]
+pub(crate) fn graph_from_edges<E: Clone>(n: usize, edges: &[(usize, usize, E)]) -> Graph<(), E> {
+    let mut g = Graph::new();
+    let ids: Vec<NodeId> = (0..n).map(|_| g.add_node(())).collect();
+    for (u, v, e) in edges {
+        g.add_edge(ids[*u], ids[*v], e.clone()).unwrap();
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `a -> b -> c -> a`, a self-loop on `b` and a parallel `a -> b`.
+    fn triangle() -> (Graph<&'static str, u32>, [NodeId; 3]) {
+        let mut g = Graph::new();
+        let ids = ["a", "b", "c"].map(|name| g.add_node(name));
+        let [a, b, c] = ids;
+        for (i, (from, to)) in [(a, b), (b, c), (c, a), (b, b), (a, b)].into_iter().enumerate() {
+            g.add_edge(from, to, i as u32).unwrap();
+        }
+        (g, ids)
+    }
+
+    #[test]
+    fn lookup() {
+        let (mut g, [a, b, c]) = triangle();
+        assert_eq!((g.node_count(), g.edge_count()), (3, 5));
+        assert_eq!(g.edges_between(a, b).collect::<Vec<_>>(), [EdgeId(0), EdgeId(4)]);
+        assert_eq!(g.find_edge(b, a), None);
+        assert_eq!(g.find_edge(b, b), Some(EdgeId(3)));
+        assert_eq!(g.edge_endpoints(EdgeId(2)), Some((c, a)));
+        assert_eq!(g.neighbors(b).unwrap().collect::<Vec<_>>(), [c, b]);
+        assert_eq!(g.incoming(b), [EdgeId(0), EdgeId(3), EdgeId(4)]);
+        assert_eq!(g.get_node(c), Some(&"c"));
+        assert!(g.neighbors(NodeId(7)).is_none());
+        assert!(g.outgoing(NodeId(7)).is_empty());
+        assert_eq!(g.add_edge(a, NodeId(7), 9), Err(GraphError::NodeNotFound(NodeId(7))));
+    }
+
+    #[test]
+    fn removing_edges() {
+        let (mut g, [a, b, _]) = triangle();
+        assert_eq!(g.remove_edge(EdgeId(0)), Ok(0));
+        assert_eq!(g.remove_edge(EdgeId(0)), Err(GraphError::EdgeNotFound(EdgeId(0))));
+        assert_eq!(g.edge_count(), 4);
+        assert_eq!(g.edge_bound(), 5);
+        assert!(!g.contains_edge(EdgeId(0)));
+        assert_eq!(g.find_edge(a, b), Some(EdgeId(4)));
+        assert_eq!(g.incoming(b), [EdgeId(3), EdgeId(4)]);
+        assert_eq!(g.get_edge_mut(EdgeId(0)), None);
+        // ids are never reused
+        assert_eq!(g.add_edge(a, b, 7), Ok(EdgeId(5)));
+    }
+
+    #[test]
+    fn removing_nodes_drops_incident_edges() {
+        let (mut g, [a, b, c]) = triangle();
+        assert_eq!(g.remove_node(b), Ok("b"));
+        assert_eq!(g.remove_node(b), Err(GraphError::NodeNotFound(b)));
+        assert_eq!((g.node_count(), g.edge_count()), (2, 1));
+        assert_eq!(g.edge_ids().collect::<Vec<_>>(), [EdgeId(2)]);
+        assert_eq!(g.node_ids().collect::<Vec<_>>(), [a, c]);
+        assert_eq!(g.outgoing(a), []);
+        assert_eq!(g.incoming(c), []);
+        assert_eq!(g.add_edge(a, b, 0), Err(GraphError::NodeNotFound(b)));
+        assert_eq!(g.add_node("d"), NodeId(3));
+        assert_eq!(g.node_bound(), 4);
+        assert_eq!(GraphError::NodeNotFound(b).to_string(), "node 1 does not exist or was removed");
+    }
+
+    #[test]
+    fn retain() {
+        let (mut g, [a, _, c]) = triangle();
+        g.retain_edges(|_, &w| w % 2 == 0);
+        assert_eq!(g.edge_ids().collect::<Vec<_>>(), [EdgeId(0), EdgeId(2), EdgeId(4)]);
+        g.retain_nodes(|_, &name| name != "b");
+        assert_eq!(g.node_ids().collect::<Vec<_>>(), [a, c]);
+        assert_eq!(g.edges().map(|(id, e)| (id, e.from, e.to)).collect::<Vec<_>>(), [(EdgeId(2), c, a)]);
+    }
+
+    #[test]
+    fn algorithms_skip_tombstones() {
+        let mut g = graph_from_edges(5, &[(0, 1, 1u32), (1, 2, 1), (2, 3, 1), (3, 4, 1), (0, 4, 10)]);
+        let v: Vec<NodeId> = g.node_ids().collect();
+        g.remove_node(v[2]).unwrap();
+        let sp = dijkstra(&g, v[0], |&w| w);
+        assert_eq!(sp.dist, [Some(0), Some(1), None, None, Some(10)]);
+        let components = weakly_connected_components(&g);
+        assert_eq!(components.len(), 1);
+        assert!(!components[0].contains(&v[2]));
+        assert_eq!(topological_sort(&g).unwrap(), [v[3], v[0], v[4], v[1]]);
     }
 }
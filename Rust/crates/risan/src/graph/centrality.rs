@@ -0,0 +1,415 @@
+//! Node centrality: degree, closeness and betweenness from shortest paths, and PageRank,
+//! eigenvector and Katz centrality by power iteration on the sparse adjacency and
+//! transition matrices. Scores are indexed by node, with zero for removed nodes. The
+//! shortest-path measures run one search per source in parallel with rayon.
+
+use std::collections::BinaryHeap;
+
+use rayon::prelude::*;
+
+use super::matrix::{adjacency_matrix, by_node_id, matrix_index, transition_matrix};
+use super::shortest_path::{dijkstra, Entry};
+use super::visit::{
+    edges_once, EdgeRef, GraphProp, IntoEdgeReferences, IntoEdges, IntoNodeIdentifiers, NodeCount, NodeIndexable,
+};
+use super::NodeId;
+
+const TOLERANCE: f64 = 1e-12;
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Largest absolute componentwise difference.
+fn max_change(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+}
+
+/// Number of incident edges (in plus out, a self-loop counting twice) divided by `n - 1`,
+/// the most a node can have in a simple undirected graph.
+pub fn degree_centrality<G>(g: G) -> Vec<f64>
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable + NodeCount,
+{
+    let mut degree = vec![0.0; g.node_bound()];
+    for e in edges_once(g) {
+        degree[e.source().0] += 1.0;
+        degree[e.target().0] += 1.0;
+    }
+    let scale = if g.node_count() > 1 { 1.0 / (g.node_count() - 1) as f64 } else { 0.0 };
+    degree.iter_mut().for_each(|d| *d *= scale);
+    degree
+}
+
+/// Closeness centrality from the distances out of each node (use `Reversed` for distances
+/// into it). With `r` nodes reachable from `v`, counting `v`, the score is
+/// `(r - 1) / sum of distances`, scaled by `(r - 1) / (n - 1)` (Wasserman and Faust) so
+/// that nodes reaching little of a disconnected graph do not score highly. Weights must be
+/// non-negative.
+pub fn closeness_centrality<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64 + Sync) -> Vec<f64>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable + NodeCount + Sync,
+{
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    let n = nodes.len();
+    let scores: Vec<f64> = nodes
+        .par_iter()
+        .map(|&v| {
+            let sp = dijkstra(g, v, &weight);
+            let (reached, total) = sp.dist.iter().flatten().fold((0usize, 0.0), |(r, t), d| (r + 1, t + d));
+            if reached <= 1 || total == 0.0 {
+                return 0.0;
+            }
+            let r = (reached - 1) as f64;
+            r / total * r / (n - 1) as f64
+        })
+        .collect();
+    by_node_id(&nodes, g.node_bound(), &scores, 0.0)
+}
+
+/// Betweenness centrality by Brandes' algorithm: for each node, the sum over pairs `s, t`
+/// of the fraction of shortest `s`-`t` paths passing through it. One Dijkstra search per
+/// source, `O(V E log V)` in total; weights must be positive. Undirected graphs count each
+/// unordered pair once. Divide by `(n - 1)(n - 2)` (halved when undirected) to normalize.
+pub fn betweenness_centrality<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64 + Sync) -> Vec<f64>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable + GraphProp + Sync,
+{
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    let bound = g.node_bound();
+    let mut scores = nodes
+        .par_iter()
+        .fold(
+            || Brandes::new(bound),
+            |mut b, &s| {
+                b.add_source(g, s, &weight);
+                b
+            },
+        )
+        .map(|b| b.score)
+        .reduce(
+            || vec![0.0; bound],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                a
+            },
+        );
+    if !g.is_directed() {
+        scores.iter_mut().for_each(|x| *x /= 2.0);
+    }
+    scores
+}
+
+/// Per-thread state of Brandes' algorithm: the running scores and buffers reused from one
+/// source to the next (only entries of settled nodes are dirty).
+struct Brandes {
+    score: Vec<f64>,
+    dist: Vec<Option<f64>>,
+    sigma: Vec<f64>,
+    delta: Vec<f64>,
+    settled: Vec<NodeId>,
+}
+
+impl Brandes {
+    fn new(n: usize) -> Self {
+        Self { score: vec![0.0; n], dist: vec![None; n], sigma: vec![0.0; n], delta: vec![0.0; n], settled: Vec::new() }
+    }
+
+    /// Count shortest paths from `s` in order of distance, then walk back from the farthest
+    /// node adding each node's dependency on `s`. A node's successors on shortest paths are
+    /// found again from its edges, so no predecessor lists are kept.
+    fn add_source<G>(&mut self, g: G, s: NodeId, weight: impl Fn(&G::EdgeWeight) -> f64)
+    where
+        G: IntoEdges + NodeIndexable,
+    {
+        self.dist[s.0] = Some(0.0);
+        self.sigma[s.0] = 1.0;
+        let mut heap = BinaryHeap::from([Entry { cost: 0.0, node: s }]);
+        while let Some(Entry { cost, node: v }) = heap.pop() {
+            if self.dist[v.0] != Some(cost) || self.delta[v.0] < 0.0 {
+                continue;
+            }
+            // a negative delta marks a settled node until the backward pass resets it
+            self.delta[v.0] = -1.0;
+            self.settled.push(v);
+            for e in g.edges(v) {
+                let (w, d) = (e.target(), cost + weight(e.weight()));
+                if self.delta[w.0] < 0.0 {
+                    continue;
+                }
+                match self.dist[w.0] {
+                    Some(old) if d > old => {}
+                    Some(old) if d == old => self.sigma[w.0] += self.sigma[v.0],
+                    _ => {
+                        self.dist[w.0] = Some(d);
+                        self.sigma[w.0] = self.sigma[v.0];
+                        heap.push(Entry { cost: d, node: w });
+                    }
+                }
+            }
+        }
+        for &v in self.settled.iter().rev() {
+            let mut dependency = 0.0;
+            for e in g.edges(v) {
+                let w = e.target();
+                if w != v && self.dist[w.0] == Some(self.dist[v.0].unwrap() + weight(e.weight())) {
+                    dependency += self.sigma[v.0] / self.sigma[w.0] * (1.0 + self.delta[w.0]);
+                }
+            }
+            self.delta[v.0] = dependency;
+            if v != s {
+                self.score[v.0] += dependency;
+            }
+        }
+        for v in self.settled.drain(..) {
+            self.dist[v.0] = None;
+            self.sigma[v.0] = 0.0;
+            self.delta[v.0] = 0.0;
+        }
+    }
+}
+
+/// PageRank: the stationary distribution of a random surfer who follows an out-edge
+/// (chosen in proportion to its weight) with probability `damping` and otherwise jumps to
+/// a uniformly random node. Nodes without out-edges jump uniformly. Scores sum to one.
+pub fn pagerank<G>(g: G, damping: f64, weight: impl Fn(&G::EdgeWeight) -> f64) -> Vec<f64>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, _) = matrix_index(g);
+    let n = order.len();
+    if n == 0 {
+        return vec![0.0; g.node_bound()];
+    }
+    let p = transition_matrix(g, weight);
+    let dangling: Vec<usize> = (0..n).filter(|&i| p.row(i).next().is_none()).collect();
+    let mut x = vec![1.0 / n as f64; n];
+    for _ in 0..MAX_ITERATIONS {
+        let lost: f64 = dangling.iter().map(|&i| x[i]).sum();
+        let teleport = (1.0 - damping + damping * lost) / n as f64;
+        let next: Vec<f64> = p.transpose_mul_vec(&x).iter().map(|y| damping * y + teleport).collect();
+        let done = max_change(&next, &x) < TOLERANCE;
+        x = next;
+        if done {
+            break;
+        }
+    }
+    by_node_id(&order, g.node_bound(), &x, 0.0)
+}
+
+/// Eigenvector centrality: the Perron vector of the transposed adjacency matrix, so a node
+/// scores highly when high-scoring nodes point to it. Normalized to unit length. Iterates
+/// with `A^T + I`, which has the same eigenvectors and converges on bipartite graphs too.
+/// `None` if the iteration does not settle, as can happen when the graph has several
+/// components with the same spectral radius.
+pub fn eigenvector_centrality<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> Option<Vec<f64>>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, _) = matrix_index(g);
+    let n = order.len();
+    let a = adjacency_matrix(g, weight);
+    let mut x = vec![1.0 / (n as f64).sqrt(); n];
+    for _ in 0..MAX_ITERATIONS {
+        let mut next: Vec<f64> = a.transpose_mul_vec(&x).iter().zip(&x).map(|(y, xi)| y + xi).collect();
+        let norm = next.iter().map(|y| y * y).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return None;
+        }
+        next.iter_mut().for_each(|y| *y /= norm);
+        let done = max_change(&next, &x) < TOLERANCE;
+        x = next;
+        if done {
+            return Some(by_node_id(&order, g.node_bound(), &x, 0.0));
+        }
+    }
+    None
+}
+
+/// Katz centrality `x = alpha A^T x + beta`: every node gets `beta` plus `alpha` times the
+/// scores of the nodes pointing to it, so walks of length `k` count with weight `alpha^k`.
+/// Converges when `alpha` is below the reciprocal of the spectral radius (which is at most
+/// the largest weighted in-degree); `None` otherwise.
+pub fn katz_centrality<G>(g: G, alpha: f64, beta: f64, weight: impl Fn(&G::EdgeWeight) -> f64) -> Option<Vec<f64>>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, _) = matrix_index(g);
+    let a = adjacency_matrix(g, weight);
+    let mut x = vec![beta; order.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<f64> = a.transpose_mul_vec(&x).iter().map(|y| alpha * y + beta).collect();
+        if next.iter().any(|y| !y.is_finite()) {
+            return None;
+        }
+        let scale = next.iter().fold(1.0, |m: f64, y| m.max(y.abs()));
+        let done = max_change(&next, &x) < TOLERANCE * scale;
+        x = next;
+        if done {
+            return Some(by_node_id(&order, g.node_bound(), &x, 0.0));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, symmetric_eigen, Undirected};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_edges(rng: &mut StdRng, n: usize, m: usize) -> Vec<(usize, usize, f64)> {
+        (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(1..4) as f64)).collect()
+    }
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-8)
+    }
+
+    /// Every simple path from `s` as `(length, nodes)`, following each parallel edge.
+    fn simple_paths(adj: &[Vec<(usize, f64)>], s: usize) -> Vec<(f64, Vec<usize>)> {
+        fn walk(adj: &[Vec<(usize, f64)>], path: &mut Vec<usize>, len: f64, out: &mut Vec<(f64, Vec<usize>)>) {
+            out.push((len, path.clone()));
+            for &(w, x) in &adj[*path.last().unwrap()] {
+                if !path.contains(&w) {
+                    path.push(w);
+                    walk(adj, path, len + x, out);
+                    path.pop();
+                }
+            }
+        }
+        let mut out = Vec::new();
+        walk(adj, &mut vec![s], 0.0, &mut out);
+        out
+    }
+
+    /// Closeness and betweenness from all simple paths, over ordered pairs.
+    fn brute_force(n: usize, adj: &[Vec<(usize, f64)>]) -> (Vec<f64>, Vec<f64>) {
+        let mut closeness = vec![0.0; n];
+        let mut betweenness = vec![0.0; n];
+        for (s, score) in closeness.iter_mut().enumerate() {
+            let paths = simple_paths(adj, s);
+            let mut total = 0.0;
+            let mut reached = 0;
+            for t in 0..n {
+                let to_t: Vec<&(f64, Vec<usize>)> = paths.iter().filter(|(_, p)| *p.last().unwrap() == t).collect();
+                let Some(best) = to_t.iter().map(|(l, _)| *l).min_by(f64::total_cmp) else { continue };
+                reached += 1;
+                total += best;
+                if t == s {
+                    continue;
+                }
+                let shortest: Vec<&Vec<usize>> = to_t.iter().filter(|(l, _)| *l == best).map(|(_, p)| p).collect();
+                for p in &shortest {
+                    for &v in &p[1..p.len() - 1] {
+                        betweenness[v] += 1.0 / shortest.len() as f64;
+                    }
+                }
+            }
+            if reached > 1 && total > 0.0 {
+                let r = (reached - 1) as f64;
+                *score = r / total * r / (n - 1) as f64;
+            }
+        }
+        (closeness, betweenness)
+    }
+
+    #[test]
+    fn shortest_path_centralities_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(4949);
+        for _ in 0..30 {
+            let n = 6;
+            let edges = random_edges(&mut rng, n, 9);
+            let g = graph_from_edges(n, &edges);
+            let mut directed = vec![Vec::new(); n];
+            let mut undirected = vec![Vec::new(); n];
+            for &(u, v, w) in &edges {
+                directed[u].push((v, w));
+                undirected[u].push((v, w));
+                if u != v {
+                    undirected[v].push((u, w));
+                }
+            }
+            let (c, b) = brute_force(n, &directed);
+            assert!(close(&closeness_centrality(&g, |&w| w), &c));
+            assert!(close(&betweenness_centrality(&g, |&w| w), &b));
+            let (c, b) = brute_force(n, &undirected);
+            let halved: Vec<f64> = b.iter().map(|x| x / 2.0).collect();
+            assert!(close(&closeness_centrality(Undirected(&g), |&w| w), &c));
+            assert!(close(&betweenness_centrality(Undirected(&g), |&w| w), &halved));
+        }
+    }
+
+    #[test]
+    fn degree_centrality_of_a_star() {
+        let mut g = graph_from_edges(5, &[(0, 1, 1.0), (2, 0, 1.0), (0, 3, 1.0), (0, 4, 1.0), (4, 4, 1.0)]);
+        assert_eq!(degree_centrality(&g), [1.0, 0.25, 0.25, 0.25, 0.75]);
+        assert_eq!(degree_centrality(Undirected(&g)), [1.0, 0.25, 0.25, 0.25, 0.75]);
+        g.remove_node(NodeId(2)).unwrap();
+        assert_eq!(degree_centrality(&g), [1.0, 1.0 / 3.0, 0.0, 1.0 / 3.0, 1.0]);
+    }
+
+    #[test]
+    fn pagerank_is_a_stationary_distribution() {
+        let mut rng = StdRng::seed_from_u64(49490);
+        for _ in 0..20 {
+            let n = 7;
+            // node 6 may be left dangling
+            let edges: Vec<_> = random_edges(&mut rng, n, 12).into_iter().filter(|e| e.0 != 6).collect();
+            let g = graph_from_edges(n, &edges);
+            let d = 0.85;
+            let x = pagerank(&g, d, |&w| w);
+            assert!((x.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            let p = transition_matrix(&g, |&w| w);
+            let lost: f64 = (0..n).filter(|&i| p.row(i).next().is_none()).map(|i| x[i]).sum();
+            let expected: Vec<f64> =
+                p.transpose_mul_vec(&x).iter().map(|y| d * y + (1.0 - d + d * lost) / n as f64).collect();
+            assert!(close(&x, &expected));
+        }
+        let cycle = graph_from_edges(4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 0, 1.0)]);
+        assert!(close(&pagerank(&cycle, 0.85, |&w| w), &[0.25; 4]));
+        assert!(pagerank(&graph_from_edges(0, &[]), 0.85, |&w| w).is_empty());
+    }
+
+    #[test]
+    fn eigenvector_centrality_is_the_perron_vector() {
+        let star = graph_from_edges(5, &[(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0), (0, 4, 1.0)]);
+        let x = eigenvector_centrality(Undirected(&star), |&w| w).unwrap();
+        assert!((x[0] / x[1] - 2.0).abs() < 1e-9);
+        assert!(close(&x[1..], &[x[1]; 4]));
+
+        let mut rng = StdRng::seed_from_u64(494);
+        for _ in 0..20 {
+            let mut edges = random_edges(&mut rng, 6, 8);
+            // a spanning path keeps the graph connected
+            edges.extend((0..5).map(|i| (i, i + 1, 1.0)));
+            let g = graph_from_edges(6, &edges);
+            let u = Undirected(&g);
+            let x = eigenvector_centrality(u, |&w| w).unwrap();
+            let (values, _) = symmetric_eigen(&adjacency_matrix(u, |&w| w).to_dense());
+            let lambda = values[5];
+            let ax = adjacency_matrix(u, |&w| w).transpose_mul_vec(&x);
+            assert!(close(&ax, &x.iter().map(|xi| lambda * xi).collect::<Vec<_>>()));
+            assert!(x.iter().all(|&xi| xi > 0.0));
+        }
+        // without edges the iteration is the identity
+        assert!(close(&eigenvector_centrality(&graph_from_edges(4, &[]), |&w| w).unwrap(), &[0.5; 4]));
+        assert_eq!(eigenvector_centrality(&graph_from_edges(0, &[]), |&w| w), None);
+    }
+
+    #[test]
+    fn katz_centrality_solves_its_equation() {
+        let mut rng = StdRng::seed_from_u64(4940);
+        for _ in 0..20 {
+            let g = graph_from_edges(6, &random_edges(&mut rng, 6, 10));
+            let (alpha, beta) = (0.05, 1.0);
+            let x = katz_centrality(&g, alpha, beta, |&w| w).unwrap();
+            let a = adjacency_matrix(&g, |&w| w);
+            let expected: Vec<f64> = a.transpose_mul_vec(&x).iter().map(|y| alpha * y + beta).collect();
+            assert!(close(&x, &expected));
+        }
+        // a 2-cycle has spectral radius 1, so alpha = 1.5 diverges
+        let cycle = graph_from_edges(2, &[(0, 1, 1.0), (1, 0, 1.0)]);
+        assert_eq!(katz_centrality(&cycle, 1.5, 1.0, |&w| w), None);
+        let chain = graph_from_edges(3, &[(0, 1, 1.0), (1, 2, 1.0)]);
+        assert!(close(&katz_centrality(&chain, 0.5, 1.0, |&w| w).unwrap(), &[1.0, 1.5, 1.75]));
+    }
+}
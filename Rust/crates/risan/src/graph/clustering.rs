@@ -0,0 +1,259 @@
+//! Triangles, clustering coefficients and k-core decomposition. These see the graph as
+//! simple and undirected: directions, self-loops and parallel edges are ignored. Per-node
+//! triangle counts run in parallel with rayon.
+
+use rayon::prelude::*;
+
+use super::visit::{EdgeRef, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use super::NodeId;
+
+/// Sorted, de-duplicated neighbours of each node in either direction, without self-loops.
+pub(crate) fn simple_adjacency<G: IntoEdgeReferences + NodeIndexable>(g: G) -> Vec<Vec<NodeId>> {
+    let mut adj = vec![Vec::new(); g.node_bound()];
+    for e in g.edge_references() {
+        let (a, b) = (e.source(), e.target());
+        if a != b {
+            adj[a.0].push(b);
+            adj[b.0].push(a);
+        }
+    }
+    for list in &mut adj {
+        list.sort_unstable();
+        list.dedup();
+    }
+    adj
+}
+
+/// Size of the intersection of two sorted lists.
+fn common(a: &[NodeId], b: &[NodeId]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+fn triangles_of(adj: &[Vec<NodeId>], nodes: &[NodeId]) -> Vec<usize> {
+    let mut out = vec![0; adj.len()];
+    let counts: Vec<usize> =
+        nodes.par_iter().map(|v| adj[v.0].iter().map(|u| common(&adj[v.0], &adj[u.0])).sum::<usize>() / 2).collect();
+    for (v, t) in nodes.iter().zip(counts) {
+        out[v.0] = t;
+    }
+    out
+}
+
+/// Number of triangles through each node.
+pub fn triangles<G>(g: G) -> Vec<usize>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    triangles_of(&simple_adjacency(g), &nodes)
+}
+
+/// Number of triangles in the graph.
+pub fn triangle_count<G>(g: G) -> usize
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    triangles(g).iter().sum::<usize>() / 3
+}
+
+/// Local clustering coefficient of each node: the fraction of pairs of its neighbours that
+/// are adjacent, or zero for nodes with fewer than two neighbours.
+pub fn local_clustering<G>(g: G) -> Vec<f64>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    let adj = simple_adjacency(g);
+    let t = triangles_of(&adj, &nodes);
+    let mut out = vec![0.0; adj.len()];
+    for v in nodes {
+        let d = adj[v.0].len();
+        if d >= 2 {
+            out[v.0] = 2.0 * t[v.0] as f64 / (d * (d - 1)) as f64;
+        }
+    }
+    out
+}
+
+/// Mean of the local clustering coefficients over all nodes (Watts and Strogatz).
+pub fn average_clustering<G>(g: G) -> f64
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let local = local_clustering(g);
+    let (count, total) = g.node_identifiers().fold((0, 0.0), |(c, t), v| (c + 1, t + local[v.0]));
+    if count == 0 { 0.0 } else { total / count as f64 }
+}
+
+/// Global clustering coefficient (transitivity): three times the number of triangles over
+/// the number of connected triples, or zero if there are none.
+pub fn global_clustering<G>(g: G) -> f64
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    let adj = simple_adjacency(g);
+    let closed: usize = triangles_of(&adj, &nodes).iter().sum();
+    let triples: usize = nodes.iter().map(|v| adj[v.0].len()).map(|d| d * d.saturating_sub(1) / 2).sum();
+    if triples == 0 { 0.0 } else { closed as f64 / triples as f64 }
+}
+
+/// Core number of each node: the largest `k` such that the node belongs to a subgraph in
+/// which every node has degree at least `k`. Batagelj and Zaversnik's bucket algorithm,
+/// `O(V + E)`.
+pub fn core_numbers<G>(g: G) -> Vec<usize>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let adj = simple_adjacency(g);
+    let mut degree: Vec<usize> = adj.iter().map(Vec::len).collect();
+    let nodes: Vec<NodeId> = g.node_identifiers().collect();
+    let max_degree = nodes.iter().map(|v| degree[v.0]).max().unwrap_or(0);
+    // bin[d] is the position in `vert` of the first node of degree d
+    let mut bin = vec![0; max_degree + 1];
+    for v in &nodes {
+        bin[degree[v.0]] += 1;
+    }
+    let mut start = 0;
+    for b in bin.iter_mut() {
+        let count = *b;
+        *b = start;
+        start += count;
+    }
+    // counting sort by degree: `vert` lists the nodes bucket by bucket, `pos` inverts it
+    let mut vert = vec![NodeId(0); nodes.len()];
+    let mut pos = vec![0; adj.len()];
+    let mut next = bin.clone();
+    for &v in &nodes {
+        let slot = &mut next[degree[v.0]];
+        (vert[*slot], pos[v.0]) = (v, *slot);
+        *slot += 1;
+    }
+    for i in 0..vert.len() {
+        let v = vert[i];
+        for &u in &adj[v.0] {
+            if degree[u.0] > degree[v.0] {
+                // move u to the front of its bucket, then shrink the bucket past it
+                let (du, pu) = (degree[u.0], pos[u.0]);
+                let pw = bin[du];
+                let w = vert[pw];
+                if u != w {
+                    vert.swap(pu, pw);
+                    pos[u.0] = pw;
+                    pos[w.0] = pu;
+                }
+                bin[du] += 1;
+                degree[u.0] -= 1;
+            }
+        }
+    }
+    degree
+}
+
+/// Nodes of the `k`-core, the largest subgraph in which every node has at least `k`
+/// neighbours, sorted by id.
+pub fn k_core<G>(g: G, k: usize) -> Vec<NodeId>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+{
+    let core = core_numbers(g);
+    g.node_identifiers().filter(|v| core[v.0] >= k).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph_from_edges;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Adjacency matrix of the simple undirected graph underlying `edges`.
+    fn simple_matrix(n: usize, edges: &[(usize, usize, ())]) -> Vec<Vec<bool>> {
+        let mut a = vec![vec![false; n]; n];
+        for &(u, v, ()) in edges {
+            if u != v {
+                a[u][v] = true;
+                a[v][u] = true;
+            }
+        }
+        a
+    }
+
+    /// Core numbers by repeatedly peeling nodes of degree below `k`.
+    fn peel(a: &[Vec<bool>]) -> Vec<usize> {
+        let n = a.len();
+        let mut core = vec![0; n];
+        for k in 1..n {
+            let mut alive = vec![true; n];
+            loop {
+                let low = (0..n).find(|&v| alive[v] && (0..n).filter(|&u| alive[u] && a[v][u]).count() < k);
+                match low {
+                    Some(v) => alive[v] = false,
+                    None => break,
+                }
+            }
+            (0..n).filter(|&v| alive[v]).for_each(|v| core[v] = k);
+        }
+        core
+    }
+
+    #[test]
+    fn clustering_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(50);
+        for _ in 0..40 {
+            let n = 9;
+            let edges: Vec<(usize, usize, ())> =
+                (0..22).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), ())).collect();
+            let g = graph_from_edges(n, &edges);
+            let a = simple_matrix(n, &edges);
+            let pairs: Vec<(usize, usize)> = (0..n).flat_map(|u| (0..u).map(move |w| (u, w))).collect();
+            let tri: Vec<usize> =
+                (0..n).map(|v| pairs.iter().filter(|&&(u, w)| a[v][u] && a[v][w] && a[u][w]).count()).collect();
+            assert_eq!(triangles(&g), tri);
+            assert_eq!(triangle_count(&g), tri.iter().sum::<usize>() / 3);
+            let degree: Vec<usize> = a.iter().map(|r| r.iter().filter(|&&x| x).count()).collect();
+            let local: Vec<f64> = (0..n)
+                .map(|v| if degree[v] < 2 { 0.0 } else { 2.0 * tri[v] as f64 / (degree[v] * (degree[v] - 1)) as f64 })
+                .collect();
+            assert_eq!(local_clustering(&g), local);
+            assert!((average_clustering(&g) - local.iter().sum::<f64>() / n as f64).abs() < 1e-12);
+            let triples: usize = degree.iter().map(|d| d * d.saturating_sub(1) / 2).sum();
+            let global = if triples == 0 { 0.0 } else { tri.iter().sum::<usize>() as f64 / triples as f64 };
+            assert!((global_clustering(&g) - global).abs() < 1e-12);
+            let core = peel(&a);
+            assert_eq!(core_numbers(&g), core);
+            for k in 0..4 {
+                assert_eq!(k_core(&g, k), (0..n).filter(|&v| core[v] >= k).map(NodeId).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn clustering_of_small_graphs() {
+        // a triangle with a pendant node, a self-loop and a parallel edge
+        let mut g = graph_from_edges(5, &[(0, 1, ()), (1, 2, ()), (2, 0, ()), (0, 1, ()), (2, 3, ()), (3, 3, ())]);
+        assert_eq!(triangles(&g), [1, 1, 1, 0, 0]);
+        assert_eq!(local_clustering(&g), [1.0, 1.0, 1.0 / 3.0, 0.0, 0.0]);
+        assert!((global_clustering(&g) - 0.6).abs() < 1e-12);
+        assert_eq!(core_numbers(&g), [2, 2, 2, 1, 0]);
+        g.remove_node(NodeId(1)).unwrap();
+        assert_eq!(triangle_count(&g), 0);
+        assert_eq!(core_numbers(&g), [1, 0, 1, 1, 0]);
+        assert_eq!(k_core(&g, 1), [NodeId(0), NodeId(2), NodeId(3)]);
+        let empty = graph_from_edges::<()>(0, &[]);
+        assert_eq!((average_clustering(&empty), global_clustering(&empty)), (0.0, 0.0));
+        assert!(core_numbers(&empty).is_empty());
+    }
+}
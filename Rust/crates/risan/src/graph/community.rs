@@ -0,0 +1,410 @@
+//! Community detection: modularity, Louvain and label propagation. Communities are found
+//! in the weighted undirected graph: directions are ignored and parallel edges add up.
+//!
+//! Unlike the centrality and clustering measures these run sequentially. Both methods
+//! update asynchronously: each node's choice is scored against the communities as the
+//! previous nodes left them. Scoring all nodes in parallel against a stale partition
+//! turns this into a synchronous update, which is known to oscillate (two neighbours
+//! swapping labels forever) rather than converge, and the per-node work, a scan of one
+//! neighbour list, is too small to split further.
+
+use crate::rng::SplitMix64;
+
+use super::matrix::matrix_index;
+use super::visit::{edges_once, EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use super::NodeId;
+
+/// A partition of the nodes into communities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Communities {
+    /// Community of each node, in `0..count` (zero for removed nodes).
+    pub labels: Vec<usize>,
+    pub count: usize,
+    /// Modularity of the partition.
+    pub modularity: f64,
+}
+
+/// Weighted undirected graph on the positions `0..n` of the nodes: neighbour lists without
+/// self-loops (each neighbour once, parallel edges summed) and the self-loop weight of each
+/// node.
+#[derive(Clone)]
+struct Weighted {
+    adj: Vec<Vec<(usize, f64)>>,
+    loops: Vec<f64>,
+}
+
+impl Weighted {
+    fn from_edges(n: usize, edges: impl IntoIterator<Item = (usize, usize, f64)>) -> Self {
+        let mut adj = vec![Vec::new(); n];
+        let mut loops = vec![0.0; n];
+        for (a, b, w) in edges {
+            if a == b {
+                loops[a] += w;
+            } else {
+                adj[a].push((b, w));
+                adj[b].push((a, w));
+            }
+        }
+        for list in &mut adj {
+            list.sort_by_key(|&(j, _)| j);
+            list.dedup_by(|next, kept| {
+                let same = next.0 == kept.0;
+                if same {
+                    kept.1 += next.1;
+                }
+                same
+            });
+        }
+        Self { adj, loops }
+    }
+
+    /// Weighted degree, a self-loop counting twice.
+    fn strength(&self, i: usize) -> f64 {
+        self.adj[i].iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * self.loops[i]
+    }
+
+    /// Total edge weight.
+    fn total(&self) -> f64 {
+        (0..self.adj.len()).map(|i| self.strength(i)).sum::<f64>() / 2.0
+    }
+
+    /// Modularity of a partition of the positions.
+    fn modularity(&self, community: &[usize]) -> f64 {
+        let m = self.total();
+        if m == 0.0 {
+            return 0.0;
+        }
+        let count = community.iter().max().map_or(0, |&c| c + 1);
+        let (mut inside, mut tot) = (vec![0.0; count], vec![0.0; count]);
+        for (i, &c) in community.iter().enumerate() {
+            tot[c] += self.strength(i);
+            inside[c] += self.loops[i];
+            inside[c] += self.adj[i].iter().filter(|&&(j, _)| community[j] == c).map(|&(_, w)| w).sum::<f64>() / 2.0;
+        }
+        inside.iter().zip(&tot).map(|(l, d)| l / m - (d / (2.0 * m)).powi(2)).sum()
+    }
+}
+
+fn weighted<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> (Vec<NodeId>, Weighted)
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, row) = matrix_index(g);
+    let edges = edges_once(g).map(|e| (row[e.source().0], row[e.target().0], weight(e.weight())));
+    let w = Weighted::from_edges(order.len(), edges);
+    (order, w)
+}
+
+/// Number labels `0..count` in order of first appearance and spread them over node ids.
+fn finish(order: &[NodeId], bound: usize, community: &[usize], w: &Weighted) -> Communities {
+    let mut rename = vec![usize::MAX; community.len()];
+    let mut count = 0;
+    let dense: Vec<usize> = community
+        .iter()
+        .map(|&c| {
+            if rename[c] == usize::MAX {
+                rename[c] = count;
+                count += 1;
+            }
+            rename[c]
+        })
+        .collect();
+    let mut labels = vec![0; bound];
+    for (v, &c) in order.iter().zip(&dense) {
+        labels[v.0] = c;
+    }
+    Communities { labels, count, modularity: w.modularity(&dense) }
+}
+
+/// Newman's modularity of the partition given by `labels` (indexed by node):
+/// `sum over communities of L_c / m - (D_c / 2m)^2`, with `L_c` the weight inside the
+/// community, `D_c` its total weighted degree and `m` the total weight. Zero for graphs
+/// without edges.
+pub fn modularity<G>(g: G, labels: &[usize], weight: impl Fn(&G::EdgeWeight) -> f64) -> f64
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, w) = weighted(g, weight);
+    let community: Vec<usize> = order.iter().map(|v| labels[v.0]).collect();
+    w.modularity(&community)
+}
+
+/// Louvain method (Blondel et al.): move single nodes to the neighbouring community with
+/// the largest modularity gain until none improves, collapse each community into a node,
+/// and repeat on the smaller graph. Nodes are visited in id order, so the result is
+/// deterministic. Weights must be non-negative.
+pub fn louvain<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> Communities
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, original) = weighted(g, weight);
+    let m = original.total();
+    // community of each original node, refined level by level
+    let mut membership: Vec<usize> = (0..order.len()).collect();
+    let mut level = original.clone();
+    if m > 0.0 {
+        loop {
+            let (community, moved) = local_moves(&level, m);
+            if !moved {
+                break;
+            }
+            let count = community.iter().max().map_or(0, |&c| c + 1);
+            membership.iter_mut().for_each(|c| *c = community[*c]);
+            let (community, level_ref) = (&community, &level);
+            let edges = (0..level.adj.len()).flat_map(|i| {
+                let ci = community[i];
+                // each undirected edge once, from its smaller end
+                let across = level_ref.adj[i].iter().filter(move |&&(j, _)| i < j);
+                std::iter::once((ci, ci, level_ref.loops[i])).chain(across.map(move |&(j, w)| (ci, community[j], w)))
+            });
+            level = Weighted::from_edges(count, edges);
+        }
+    }
+    finish(&order, g.node_bound(), &membership, &original)
+}
+
+/// A Louvain pass raising modularity by less than this ends the phase.
+const MIN_GAIN: f64 = 1e-7;
+
+/// One Louvain phase: passes of single-node moves until a pass gains less than
+/// `MIN_GAIN`. Returns communities numbered `0..count` and whether any node moved.
+fn local_moves(level: &Weighted, m: f64) -> (Vec<usize>, bool) {
+    let n = level.adj.len();
+    let strength: Vec<f64> = (0..n).map(|i| level.strength(i)).collect();
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut tot = strength.clone();
+    // weight from the current node to each community, reset after each node; `seen`
+    // marks the communities listed in `touched`, as zero-weight edges leave `links` at 0
+    let mut links = vec![0.0; n];
+    let mut seen = vec![false; n];
+    let mut touched = Vec::new();
+    let mut moved = false;
+    loop {
+        let mut improvement = 0.0;
+        for i in 0..n {
+            let own = community[i];
+            for &(j, w) in &level.adj[i] {
+                let c = community[j];
+                if !std::mem::replace(&mut seen[c], true) {
+                    touched.push(c);
+                }
+                links[c] += w;
+            }
+            tot[own] -= strength[i];
+            // gain of joining c, up to a positive factor: links to c minus the expected share
+            let gain = |c: usize, links: &[f64]| links[c] - tot[c] * strength[i] / (2.0 * m);
+            let stay = gain(own, &links);
+            let mut best = (own, stay);
+            for &c in &touched {
+                let g = gain(c, &links);
+                if g > best.1 + 1e-12 {
+                    best = (c, g);
+                }
+            }
+            tot[best.0] += strength[i];
+            if best.0 != own {
+                community[i] = best.0;
+                improvement += (best.1 - stay) / m;
+                moved = true;
+            }
+            for c in touched.drain(..) {
+                links[c] = 0.0;
+                seen[c] = false;
+            }
+        }
+        if improvement < MIN_GAIN {
+            break;
+        }
+    }
+    let mut rename = vec![usize::MAX; n];
+    let mut count = 0;
+    for c in community.iter_mut() {
+        if rename[*c] == usize::MAX {
+            rename[*c] = count;
+            count += 1;
+        }
+        *c = rename[*c];
+    }
+    (community, moved)
+}
+
+/// Asynchronous label propagation (Raghavan, Albert and Kumara): every node starts in its
+/// own community and repeatedly adopts the label carrying the most edge weight among its
+/// neighbours, until each node already holds such a label. Nodes are visited in a shuffled
+/// order and ties broken at random, from a fixed seed so results are reproducible. Near
+/// linear time, but the partition is usually of lower modularity than Louvain's.
+pub fn label_propagation<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> Communities
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, w) = weighted(g, weight);
+    let n = order.len();
+    let mut label: Vec<usize> = (0..n).collect();
+    let mut rng = SplitMix64::new(0x1abe1);
+    let mut visit: Vec<usize> = (0..n).collect();
+    let mut score = vec![0.0; n];
+    let mut seen = vec![false; n];
+    let mut touched = Vec::new();
+    let mut candidates = Vec::new();
+    for _round in 0..1000 {
+        rng.shuffle(&mut visit);
+        let mut changed = false;
+        for &i in &visit {
+            for &(j, x) in &w.adj[i] {
+                if !std::mem::replace(&mut seen[label[j]], true) {
+                    touched.push(label[j]);
+                }
+                score[label[j]] += x;
+            }
+            let best = touched.iter().map(|&c| score[c]).fold(0.0, f64::max);
+            candidates.extend(touched.iter().copied().filter(|&c| score[c] == best && best > 0.0));
+            if !candidates.is_empty() && !candidates.contains(&label[i]) {
+                label[i] = candidates[rng.below(candidates.len() as u64) as usize];
+                changed = true;
+            }
+            for c in touched.drain(..) {
+                score[c] = 0.0;
+                seen[c] = false;
+            }
+            candidates.clear();
+        }
+        if !changed {
+            break;
+        }
+    }
+    finish(&order, g.node_bound(), &label, &w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, Graph, Undirected};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Cliques on consecutive node ranges, each joined to the next by one edge of weight
+    /// `bridge`.
+    fn chained_cliques(sizes: &[usize], bridge: f64) -> Graph<(), f64> {
+        let mut edges = Vec::new();
+        let mut start = 0;
+        for (k, &size) in sizes.iter().enumerate() {
+            edges.extend((start..start + size).flat_map(|i| (start..i).map(move |j| (i, j, 1.0))));
+            if k + 1 < sizes.len() {
+                edges.push((start + size - 1, start + size, bridge));
+            }
+            start += size;
+        }
+        graph_from_edges(start, &edges)
+    }
+
+    /// Modularity from the dense definition `1/2m sum_ij (A_ij - k_i k_j / 2m) [c_i = c_j]`.
+    fn dense_modularity(n: usize, edges: &[(usize, usize, f64)], labels: &[usize]) -> f64 {
+        let mut a = vec![vec![0.0; n]; n];
+        for &(u, v, w) in edges {
+            a[u][v] += w;
+            a[v][u] += w;
+        }
+        let k: Vec<f64> = a.iter().map(|r| r.iter().sum()).collect();
+        let two_m: f64 = k.iter().sum();
+        if two_m == 0.0 {
+            return 0.0;
+        }
+        let pairs = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).filter(|&(i, j)| labels[i] == labels[j]);
+        pairs.map(|(i, j)| a[i][j] - k[i] * k[j] / two_m).sum::<f64>() / two_m
+    }
+
+    /// The label of each clique's nodes, checking that they agree.
+    fn clique_labels(labels: &[usize], sizes: &[usize]) -> Vec<usize> {
+        let mut start = 0;
+        sizes
+            .iter()
+            .map(|&size| {
+                let group = &labels[start..start + size];
+                start += size;
+                assert!(group.iter().all(|&l| l == group[0]), "clique split: {:?}", labels);
+                group[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn modularity_matches_the_dense_definition() {
+        let mut rng = StdRng::seed_from_u64(5050);
+        for _ in 0..30 {
+            let n = 8;
+            let edges: Vec<_> =
+                (0..14).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..4) as f64)).collect();
+            let g = graph_from_edges(n, &edges);
+            let labels: Vec<usize> = (0..n).map(|_| rng.gen_range(0..3)).collect();
+            let expected = dense_modularity(n, &edges, &labels);
+            assert!((modularity(&g, &labels, |&w| w) - expected).abs() < 1e-12);
+            assert!((modularity(Undirected(&g), &labels, |&w| w) - expected).abs() < 1e-12);
+        }
+        assert_eq!(modularity(&graph_from_edges(3, &[]), &[0, 1, 2], |&w| w), 0.0);
+    }
+
+    #[test]
+    fn louvain_finds_chained_cliques() {
+        let sizes = [5, 4, 6, 5];
+        let g = chained_cliques(&sizes, 1.0);
+        let found = louvain(&g, |&w| w);
+        assert_eq!(found.count, 4);
+        assert_eq!(clique_labels(&found.labels, &sizes), [0, 1, 2, 3]);
+        assert!((found.modularity - modularity(&g, &found.labels, |&w| w)).abs() < 1e-12);
+        // the same on the undirected view, and with zero-weight bridges
+        assert_eq!(louvain(Undirected(&g), |&w| w), found);
+        let loose = chained_cliques(&sizes, 0.0);
+        assert_eq!(clique_labels(&louvain(&loose, |&w| w).labels, &sizes), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn louvain_never_loses_to_singletons() {
+        let mut rng = StdRng::seed_from_u64(505);
+        for _ in 0..30 {
+            let n = 12;
+            let edges: Vec<_> =
+                (0..25).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..3) as f64)).collect();
+            let g = graph_from_edges(n, &edges);
+            let found = louvain(&g, |&w| w);
+            let singletons: Vec<usize> = (0..n).collect();
+            assert!(found.modularity >= modularity(&g, &singletons, |&w| w) - 1e-12);
+            assert!((found.modularity - dense_modularity(n, &edges, &found.labels)).abs() < 1e-12);
+            assert!(found.labels.iter().all(|&l| l < found.count));
+        }
+    }
+
+    #[test]
+    fn label_propagation_separates_loosely_joined_cliques() {
+        let sizes = [6, 5, 7];
+        for bridge in [0.0, 0.1] {
+            let mut g = chained_cliques(&sizes, bridge);
+            // zero-weight edges from the first clique into the others must not sway the vote
+            g.add_edge(NodeId(0), NodeId(7), 0.0).unwrap();
+            g.add_edge(NodeId(1), NodeId(12), 0.0).unwrap();
+            let found = label_propagation(&g, |&w| w);
+            assert_eq!(found.count, 3);
+            assert_eq!(clique_labels(&found.labels, &sizes), [0, 1, 2]);
+            assert_eq!(label_propagation(&g, |&w| w), found);
+        }
+        let mut g = graph_from_edges(4, &[(0, 1, 1.0)]);
+        g.remove_node(NodeId(3)).unwrap();
+        let found = label_propagation(&g, |&w| w);
+        assert_eq!((found.labels, found.count), (vec![0, 0, 1, 0], 2));
+    }
+
+    #[test]
+    fn zero_weight_edges_do_not_bias_ties() {
+        // gadgets of two heavy pairs {p1, p2} and {q1, q2}, and a node x tied between them:
+        // x - p2 and x - q1 weigh 1, and x - p1 weighs 0, which must not count as a vote
+        let gadgets = 400;
+        let mut edges = Vec::new();
+        for k in 0..gadgets {
+            let [p1, p2, q1, q2, x] = [0, 1, 2, 3, 4].map(|i| 5 * k + i);
+            edges.extend([(p1, p2, 5.0), (q1, q2, 5.0), (x, p2, 1.0), (x, q1, 1.0), (x, p1, 0.0)]);
+        }
+        let found = label_propagation(&graph_from_edges(5 * gadgets, &edges), |&w| w);
+        let with_p = (0..gadgets).filter(|k| found.labels[5 * k + 4] == found.labels[5 * k]).count();
+        // a fair tie-break sides with p about half the time; counting p twice made it 2/3
+        assert!((160..240).contains(&with_p), "x joined p in {} of {} gadgets", with_p, gadgets);
+    }
+}
@@ -0,0 +1,429 @@
+//! Connectivity: strongly and weakly connected components, bridges, articulation
+//! points, bipartiteness and undirected cycle detection. The undirected algorithms ignore
+//! edge direction and see every edge once, whichever way an undirected graph reports it.
+
+use std::collections::VecDeque;
+
+use super::traversal::{depth_first_search, Control, DfsEvent};
+use super::visit::{
+    edges_once, Direction, EdgeRef, GraphProp, IntoEdgeReferences, IntoNeighbors, IntoNeighborsDirected,
+    IntoNodeIdentifiers, NodeCount, NodeIndexable,
+};
+use super::{EdgeId, NodeId};
+
+/// For each node, its incident edges in either direction with the opposite endpoint.
+/// Self-loops appear once.
+pub(crate) fn undirected_adjacency<G>(g: G) -> Vec<Vec<(EdgeId, NodeId)>>
+where
+    G: IntoEdgeReferences + GraphProp + NodeIndexable,
+{
+    let mut adj = vec![Vec::new(); g.node_bound()];
+    for e in edges_once(g) {
+        let (a, b) = (e.source(), e.target());
+        adj[a.0].push((e.id(), b));
+        if a != b {
+            adj[b.0].push((e.id(), a));
+        }
+    }
+    adj
+}
+
+struct TarjanState<I> {
+    index: Vec<usize>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<NodeId>,
+    /// Simulated recursion: `(node, its remaining successors)`.
+    call: Vec<(NodeId, I)>,
+    counter: usize,
+}
+
+impl<I> TarjanState<I> {
+    fn visit(&mut self, v: NodeId, successors: I) {
+        self.index[v.0] = self.counter;
+        self.low[v.0] = self.counter;
+        self.counter += 1;
+        self.stack.push(v);
+        self.on_stack[v.0] = true;
+        self.call.push((v, successors));
+    }
+}
+
+/// Tarjan's algorithm (iterative). Components come out in reverse topological order of
+/// the condensation: no edge leads from a component to a later one.
+pub fn tarjan_scc<G: IntoNeighbors + IntoNodeIdentifiers + NodeIndexable>(g: G) -> Vec<Vec<NodeId>> {
+    let n = g.node_bound();
+    let mut t = TarjanState {
+        index: vec![usize::MAX; n],
+        low: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        call: Vec::new(),
+        counter: 0,
+    };
+    let mut components = Vec::new();
+    for root in g.node_identifiers() {
+        if t.index[root.0] != usize::MAX {
+            continue;
+        }
+        t.visit(root, g.neighbors(root));
+        while let Some((v, successors)) = t.call.last_mut() {
+            let v = *v;
+            if let Some(w) = successors.next() {
+                if t.index[w.0] == usize::MAX {
+                    t.visit(w, g.neighbors(w));
+                } else if t.on_stack[w.0] {
+                    t.low[v.0] = t.low[v.0].min(t.index[w.0]);
+                }
+                continue;
+            }
+            t.call.pop();
+            if let Some(&(u, _)) = t.call.last() {
+                t.low[u.0] = t.low[u.0].min(t.low[v.0]);
+            }
+            if t.low[v.0] == t.index[v.0] {
+                let mut comp = Vec::new();
+                loop {
+                    let w = t.stack.pop().unwrap();
+                    t.on_stack[w.0] = false;
+                    comp.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                components.push(comp);
+            }
+        }
+    }
+    components
+}
+
+/// Kosaraju's algorithm: a depth-first pass for finishing order, then searches on the
+/// reversed graph. Components come out in topological order of the condensation.
+pub fn kosaraju_scc<G>(g: G) -> Vec<Vec<NodeId>>
+where
+    G: IntoNeighborsDirected + IntoNodeIdentifiers + NodeIndexable + NodeCount,
+{
+    let mut finish = Vec::with_capacity(g.node_count());
+    depth_first_search(g, g.node_identifiers(), |event| {
+        if let DfsEvent::Finish(v) = event {
+            finish.push(v);
+        }
+        Control::Continue
+    });
+    let mut assigned = vec![false; g.node_bound()];
+    let mut components = Vec::new();
+    for &root in finish.iter().rev() {
+        if std::mem::replace(&mut assigned[root.0], true) {
+            continue;
+        }
+        let mut comp = vec![root];
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            for u in g.neighbors_directed(v, Direction::Incoming) {
+                if !std::mem::replace(&mut assigned[u.0], true) {
+                    comp.push(u);
+                    stack.push(u);
+                }
+            }
+        }
+        components.push(comp);
+    }
+    components
+}
+
+/// Strongly connected components (Tarjan).
+pub fn strongly_connected_components<G>(g: G) -> Vec<Vec<NodeId>>
+where
+    G: IntoNeighbors + IntoNodeIdentifiers + NodeIndexable,
+{
+    tarjan_scc(g)
+}
+
+/// Components of the graph with edge directions ignored, each in BFS order.
+pub fn weakly_connected_components<G>(g: G) -> Vec<Vec<NodeId>>
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let adj = undirected_adjacency(g);
+    let mut seen = vec![false; g.node_bound()];
+    let mut components = Vec::new();
+    for root in g.node_identifiers() {
+        if std::mem::replace(&mut seen[root.0], true) {
+            continue;
+        }
+        let mut comp = vec![root];
+        let mut queue = VecDeque::from([root]);
+        while let Some(v) = queue.pop_front() {
+            for &(_, w) in &adj[v.0] {
+                if !std::mem::replace(&mut seen[w.0], true) {
+                    comp.push(w);
+                    queue.push_back(w);
+                }
+            }
+        }
+        components.push(comp);
+    }
+    components
+}
+
+/// Discovery times and low-links of an undirected DFS that never re-enters through the
+/// edge it arrived by (so parallel edges are not mistaken for tree edges).
+struct LowLink {
+    disc: Vec<usize>,
+    low: Vec<usize>,
+    /// `(parent, child, edge)` for every tree edge, in the order children finish.
+    tree_edges: Vec<(NodeId, NodeId, EdgeId)>,
+    roots: Vec<NodeId>,
+}
+
+fn low_link<G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable>(g: G) -> LowLink {
+    let adj = undirected_adjacency(g);
+    let n = g.node_bound();
+    let mut ll = LowLink { disc: vec![usize::MAX; n], low: vec![0; n], tree_edges: Vec::new(), roots: Vec::new() };
+    let mut time = 0;
+    for root in g.node_identifiers() {
+        if ll.disc[root.0] != usize::MAX {
+            continue;
+        }
+        ll.roots.push(root);
+        ll.disc[root.0] = time;
+        ll.low[root.0] = time;
+        time += 1;
+        // (node, edge it was entered by, next adjacency index)
+        let mut stack: Vec<(NodeId, Option<EdgeId>, usize)> = vec![(root, None, 0)];
+        while let Some(&mut (v, via, ref mut i)) = stack.last_mut() {
+            if let Some(&(eid, w)) = adj[v.0].get(*i) {
+                *i += 1;
+                if Some(eid) == via || w == v {
+                    continue;
+                }
+                if ll.disc[w.0] == usize::MAX {
+                    ll.disc[w.0] = time;
+                    ll.low[w.0] = time;
+                    time += 1;
+                    stack.push((w, Some(eid), 0));
+                } else {
+                    ll.low[v.0] = ll.low[v.0].min(ll.disc[w.0]);
+                }
+                continue;
+            }
+            stack.pop();
+            if let Some(&(u, _, _)) = stack.last() {
+                ll.low[u.0] = ll.low[u.0].min(ll.low[v.0]);
+                ll.tree_edges.push((u, v, via.unwrap()));
+            }
+        }
+    }
+    ll
+}
+
+/// Edges whose removal disconnects their endpoints, treating the graph as undirected.
+pub fn bridges<G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable>(g: G) -> Vec<EdgeId> {
+    let ll = low_link(g);
+    ll.tree_edges.iter().filter(|&&(u, v, _)| ll.low[v.0] > ll.disc[u.0]).map(|&(_, _, e)| e).collect()
+}
+
+/// Nodes whose removal increases the number of connected components, treating the graph
+/// as undirected. Sorted by id.
+pub fn articulation_points<G>(g: G) -> Vec<NodeId>
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let ll = low_link(g);
+    let mut is_cut = vec![false; g.node_bound()];
+    let mut children = vec![0usize; g.node_bound()];
+    for &(u, v, _) in &ll.tree_edges {
+        children[u.0] += 1;
+        if ll.low[v.0] >= ll.disc[u.0] {
+            is_cut[u.0] = true;
+        }
+    }
+    // a DFS root is a cut vertex exactly when it has more than one tree child
+    for &r in &ll.roots {
+        is_cut[r.0] = children[r.0] > 1;
+    }
+    g.node_identifiers().filter(|v| is_cut[v.0]).collect()
+}
+
+/// A two-colouring with every edge joining different sides (`false`/`true`, indexed by
+/// node), or `None` if the graph has an odd cycle. Directions are ignored.
+pub fn bipartition<G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable>(g: G) -> Option<Vec<bool>> {
+    let adj = undirected_adjacency(g);
+    let mut side: Vec<Option<bool>> = vec![None; g.node_bound()];
+    for root in g.node_identifiers() {
+        if side[root.0].is_some() {
+            continue;
+        }
+        side[root.0] = Some(false);
+        let mut queue = VecDeque::from([root]);
+        while let Some(v) = queue.pop_front() {
+            let s = side[v.0].unwrap();
+            for &(_, w) in &adj[v.0] {
+                match side[w.0] {
+                    None => {
+                        side[w.0] = Some(!s);
+                        queue.push_back(w);
+                    }
+                    Some(t) if t == s => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    Some(side.into_iter().map(|s| s.unwrap_or(false)).collect())
+}
+
+pub fn is_bipartite<G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable>(g: G) -> bool {
+    bipartition(g).is_some()
+}
+
+/// Whether the graph, with directions ignored, contains a cycle. Self-loops and parallel
+/// edges count.
+pub fn is_cyclic_undirected<G>(g: G) -> bool
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable + NodeCount,
+{
+    let components = weakly_connected_components(g).len();
+    // a forest on V nodes with c components has exactly V - c edges
+    edges_once(g).count() + components > g.node_count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, Graph};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_graph(rng: &mut StdRng, n: usize, m: usize) -> Graph<(), ()> {
+        let edges: Vec<(usize, usize, ())> = (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), ())).collect();
+        graph_from_edges(n, &edges)
+    }
+
+    fn sorted(mut components: Vec<Vec<NodeId>>) -> Vec<Vec<NodeId>> {
+        components.iter_mut().for_each(|c| c.sort());
+        components.sort();
+        components
+    }
+
+    fn reachable(g: &Graph<(), ()>) -> Vec<Vec<bool>> {
+        let n = g.node_bound();
+        let mut r = vec![vec![false; n]; n];
+        for (i, row) in r.iter_mut().enumerate() {
+            row[i] = true;
+        }
+        for (_, e) in g.edges() {
+            r[e.from.0][e.to.0] = true;
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    r[i][j] |= r[i][k] && r[k][j];
+                }
+            }
+        }
+        r
+    }
+
+    #[test]
+    fn strongly_connected_components_match_reachability() {
+        let mut rng = StdRng::seed_from_u64(43);
+        for _ in 0..50 {
+            let g = random_graph(&mut rng, 10, 14);
+            let r = reachable(&g);
+            let tarjan = tarjan_scc(&g);
+            let kosaraju = kosaraju_scc(&g);
+            assert_eq!(sorted(tarjan.clone()), sorted(kosaraju.clone()));
+            let mut component = [0; 10];
+            for (i, c) in kosaraju.iter().enumerate() {
+                for v in c {
+                    component[v.0] = i;
+                }
+            }
+            for u in 0..10 {
+                for v in 0..10 {
+                    assert_eq!(component[u] == component[v], r[u][v] && r[v][u]);
+                }
+            }
+            // Kosaraju's components are in topological order, Tarjan's in reverse
+            for (_, e) in g.edges() {
+                assert!(component[e.from.0] <= component[e.to.0]);
+            }
+            let position = |v: NodeId| tarjan.iter().position(|c| c.contains(&v)).unwrap();
+            for (_, e) in g.edges() {
+                assert!(position(e.from) >= position(e.to));
+            }
+            assert_eq!(strongly_connected_components(&g), tarjan);
+        }
+    }
+
+    #[test]
+    fn weak_components_ignore_direction() {
+        let g = graph_from_edges(6, &[(1, 0, ()), (1, 2, ()), (4, 3, ())]);
+        let components = sorted(weakly_connected_components(&g));
+        assert_eq!(components, [vec![NodeId(0), NodeId(1), NodeId(2)], vec![NodeId(3), NodeId(4)], vec![NodeId(5)]]);
+    }
+
+    #[test]
+    fn bridges_and_articulation_points_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(44);
+        for _ in 0..50 {
+            let g = random_graph(&mut rng, 9, 11);
+            let components = weakly_connected_components(&g);
+            let count = components.len();
+            let mut expected_bridges: Vec<EdgeId> = g
+                .edge_ids()
+                .filter(|&e| {
+                    let mut h = g.clone();
+                    h.remove_edge(e).unwrap();
+                    weakly_connected_components(&h).len() > count
+                })
+                .collect();
+            let mut found = bridges(&g);
+            found.sort();
+            expected_bridges.sort();
+            assert_eq!(found, expected_bridges);
+
+            let expected_cuts: Vec<NodeId> = g
+                .node_ids()
+                .filter(|&v| {
+                    let mut h = g.clone();
+                    h.remove_node(v).unwrap();
+                    // removing a node that is a component of its own drops that component
+                    let alone = components.iter().any(|c| c == &[v]);
+                    weakly_connected_components(&h).len() > count - usize::from(alone)
+                })
+                .collect();
+            assert_eq!(articulation_points(&g), expected_cuts);
+        }
+    }
+
+    #[test]
+    fn parallel_edges_are_not_bridges() {
+        let g = graph_from_edges(3, &[(0, 1, ()), (1, 0, ()), (1, 2, ())]);
+        assert_eq!(bridges(&g), [EdgeId(2)]);
+        assert_eq!(articulation_points(&g), [NodeId(1)]);
+        assert!(is_cyclic_undirected(&g));
+    }
+
+    #[test]
+    fn bipartiteness() {
+        let even = graph_from_edges(6, &[(0, 1, ()), (1, 2, ()), (2, 3, ()), (3, 4, ()), (4, 5, ()), (5, 0, ())]);
+        let sides = bipartition(&even).unwrap();
+        for (_, e) in even.edges() {
+            assert_ne!(sides[e.from.0], sides[e.to.0]);
+        }
+        assert!(!is_bipartite(&graph_from_edges(5, &[(0, 1, ()), (1, 2, ()), (2, 3, ()), (3, 4, ()), (4, 0, ())])));
+        assert!(!is_bipartite(&graph_from_edges(1, &[(0, 0, ())])));
+        assert!(is_bipartite(&graph_from_edges::<()>(3, &[])));
+    }
+
+    #[test]
+    fn undirected_cycles() {
+        assert!(!is_cyclic_undirected(&graph_from_edges(5, &[(0, 1, ()), (2, 1, ()), (1, 3, ()), (4, 3, ())])));
+        assert!(is_cyclic_undirected(&graph_from_edges(4, &[(0, 1, ()), (2, 1, ()), (2, 0, ())])));
+        assert!(is_cyclic_undirected(&graph_from_edges(2, &[(1, 1, ())])));
+        assert!(!is_cyclic_undirected(&graph_from_edges::<()>(0, &[])));
+    }
+}
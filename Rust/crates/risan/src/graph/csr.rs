@@ -0,0 +1,363 @@
+//! Immutable compressed sparse row graph: the outgoing edges of each node are stored
+//! contiguously, so scanning a neighbourhood is a slice walk with no per-edge indirection.
+//! A second, transposed index lists the incoming edges the same way.
+
+use std::ops::Range;
+
+use super::visit::{
+    Direction, EdgeReference, GraphBase, GraphProp, IntoEdgeReferences, IntoEdges, IntoEdgesDirected, IntoNeighbors,
+    IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount, NodeIndexable,
+};
+use super::{EdgeId, Graph, GraphError, NodeId};
+
+/// Directed multigraph in compressed sparse row form.
+///
+/// Nodes are numbered `0..node_count()`. Edges are numbered by position: the outgoing
+/// edges of node `v` are `offsets[v]..offsets[v + 1]`, in the order they were supplied.
+/// Incoming edges are kept in the same layout, grouped by target in id order, so
+/// `Reversed`, `Undirected` and the algorithms that walk edges backwards work too.
+#[derive(Debug, Clone)]
+pub struct CsrGraph<N, E> {
+    nodes: Vec<N>,
+    offsets: Vec<usize>,
+    targets: Vec<NodeId>,
+    edges: Vec<E>,
+    in_offsets: Vec<usize>,
+    /// Incoming edge ids and their sources, grouped by target.
+    in_edges: Vec<EdgeId>,
+    sources: Vec<NodeId>,
+}
+
+impl<N, E> CsrGraph<N, E> {
+    /// Build from node data and `(source, target, data)` triples, with a counting sort by
+    /// source. Fails if an endpoint is not below `nodes.len()`.
+    pub fn from_edges(nodes: Vec<N>, edges: impl IntoIterator<Item = (NodeId, NodeId, E)>) -> Result<Self, GraphError> {
+        let n = nodes.len();
+        let triples: Vec<(NodeId, NodeId, E)> = edges.into_iter().collect();
+        let mut offsets = vec![0; n + 1];
+        for (from, to, _) in &triples {
+            for id in [*from, *to] {
+                if id.0 >= n {
+                    return Err(GraphError::NodeNotFound(id));
+                }
+            }
+            offsets[from.0 + 1] += 1;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+        let mut next = offsets.clone();
+        let mut slots: Vec<Option<(NodeId, E)>> = (0..triples.len()).map(|_| None).collect();
+        for (from, to, data) in triples {
+            slots[next[from.0]] = Some((to, data));
+            next[from.0] += 1;
+        }
+        let (targets, edges): (Vec<NodeId>, Vec<E>) =
+            slots.into_iter().map(|s| s.expect("every slot is filled")).unzip();
+
+        // the same counting sort by target; scanning sources in order keeps ids ascending
+        let mut in_offsets = vec![0; n + 1];
+        for to in &targets {
+            in_offsets[to.0 + 1] += 1;
+        }
+        for v in 0..n {
+            in_offsets[v + 1] += in_offsets[v];
+        }
+        let mut next = in_offsets.clone();
+        let mut in_edges = vec![EdgeId(0); targets.len()];
+        let mut sources = vec![NodeId(0); targets.len()];
+        for v in 0..n {
+            for k in offsets[v]..offsets[v + 1] {
+                let slot = &mut next[targets[k].0];
+                (in_edges[*slot], sources[*slot]) = (EdgeId(k), NodeId(v));
+                *slot += 1;
+            }
+        }
+        Ok(Self { nodes, offsets, targets, edges, in_offsets, in_edges, sources })
+    }
+
+    /// Copy a `Graph`. Live nodes are renumbered densely in `node_ids` order, so ids are
+    /// unchanged unless nodes were removed.
+    pub fn from_graph(g: &Graph<N, E>) -> Self
+    where
+        N: Clone,
+        E: Clone,
+    {
+        let mut index = vec![NodeId(0); g.node_bound()];
+        for (i, v) in g.node_ids().enumerate() {
+            index[v.0] = NodeId(i);
+        }
+        let nodes = g.node_ids().map(|v| g.get_node(v).unwrap().clone()).collect();
+        let edges = g.node_ids().flat_map(|v| g.edges_from(v)).map(|(_, e)| (index[e.from.0], index[e.to.0], e.data.clone()));
+        Self::from_edges(nodes, edges).expect("endpoints of a graph's edges are its nodes")
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    pub fn get_node(&self, id: NodeId) -> Option<&N> {
+        self.nodes.get(id.0)
+    }
+
+    pub fn get_edge(&self, id: EdgeId) -> Option<&E> {
+        self.edges.get(id.0)
+    }
+
+    /// Ids of the outgoing edges of `v`; empty for missing nodes.
+    pub fn edge_range(&self, v: NodeId) -> Range<usize> {
+        match self.offsets.get(v.0..v.0 + 2) {
+            Some(w) => w[0]..w[1],
+            None => 0..0,
+        }
+    }
+
+    /// Targets of the outgoing edges of `v`, one per edge.
+    pub fn neighbors(&self, v: NodeId) -> &[NodeId] {
+        &self.targets[self.edge_range(v)]
+    }
+
+    pub fn out_degree(&self, v: NodeId) -> usize {
+        self.edge_range(v).len()
+    }
+
+    /// Positions of the incoming edges of `v` in the transposed index; empty for missing nodes.
+    fn in_range(&self, v: NodeId) -> Range<usize> {
+        match self.in_offsets.get(v.0..v.0 + 2) {
+            Some(w) => w[0]..w[1],
+            None => 0..0,
+        }
+    }
+
+    /// Sources of the incoming edges of `v`, one per edge.
+    pub fn predecessors(&self, v: NodeId) -> &[NodeId] {
+        &self.sources[self.in_range(v)]
+    }
+
+    pub fn in_degree(&self, v: NodeId) -> usize {
+        self.in_range(v).len()
+    }
+
+    /// Incoming edges of `v` as `(id, source, data)`, in id order.
+    pub fn edges_to(&self, v: NodeId) -> impl Iterator<Item = (EdgeId, NodeId, &E)> + '_ {
+        self.in_range(v).map(move |k| (self.in_edges[k], self.sources[k], &self.edges[self.in_edges[k].0]))
+    }
+
+    /// Outgoing edges of `v` as `(id, target, data)`.
+    pub fn edges_from(&self, v: NodeId) -> impl Iterator<Item = (EdgeId, NodeId, &E)> + '_ {
+        self.edge_range(v).map(move |k| (EdgeId(k), self.targets[k], &self.edges[k]))
+    }
+
+    /// Source and target of an edge.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Option<(NodeId, NodeId)> {
+        let target = *self.targets.get(id.0)?;
+        // the source is the last node whose range starts at or before the edge
+        let source = self.offsets.partition_point(|&start| start <= id.0) - 1;
+        Some((NodeId(source), target))
+    }
+
+    /// Every edge as `(id, source, target, data)`, grouped by source.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, NodeId, NodeId, &E)> + '_ {
+        self.node_ids().flat_map(move |v| self.edges_from(v).map(move |(id, to, data)| (id, v, to, data)))
+    }
+}
+
+impl<N, E> GraphBase for CsrGraph<N, E> {
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<N, E> GraphProp for CsrGraph<N, E> {
+    fn is_directed(&self) -> bool {
+        true
+    }
+}
+
+impl<N, E> NodeIndexable for &CsrGraph<N, E> {
+    fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<N, E> NodeCount for &CsrGraph<N, E> {
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl<N, E> IntoNodeIdentifiers for &CsrGraph<N, E> {
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+        self.node_ids()
+    }
+}
+
+impl<N, E> IntoNeighbors for &CsrGraph<N, E> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        CsrGraph::neighbors(self, v).iter().copied()
+    }
+}
+
+impl<'a, N, E> IntoEdges for &'a CsrGraph<N, E> {
+    type EdgeRef = EdgeReference<'a, E>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = EdgeReference<'a, E>> {
+        self.edges_from(v).map(move |(id, target, weight)| EdgeReference { id, source: v, target, weight })
+    }
+}
+
+impl<'a, N, E> IntoEdgeReferences for &'a CsrGraph<N, E> {
+    type EdgeRef = EdgeReference<'a, E>;
+    fn edge_references(self) -> impl Iterator<Item = EdgeReference<'a, E>> {
+        CsrGraph::edges(self).map(|(id, source, target, weight)| EdgeReference { id, source, target, weight })
+    }
+}
+
+impl<N, E> IntoNeighborsDirected for &CsrGraph<N, E> {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId> {
+        let ends = match dir {
+            Direction::Outgoing => CsrGraph::neighbors(self, v),
+            Direction::Incoming => self.predecessors(v),
+        };
+        ends.iter().copied()
+    }
+}
+
+impl<N, E> IntoEdgesDirected for &CsrGraph<N, E> {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = Self::EdgeRef> {
+        let range = match dir {
+            Direction::Outgoing => self.edge_range(v),
+            Direction::Incoming => self.in_range(v),
+        };
+        range.map(move |k| match dir {
+            Direction::Outgoing => {
+                EdgeReference { id: EdgeId(k), source: v, target: self.targets[k], weight: &self.edges[k] }
+            }
+            Direction::Incoming => {
+                let id = self.in_edges[k];
+                EdgeReference { id, source: self.sources[k], target: v, weight: &self.edges[id.0] }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::visit::EdgeRef;
+    use crate::graph::{
+        dijkstra, floyd_warshall, graph_from_edges, kosaraju_scc, tarjan_scc, topological_sort,
+        weakly_connected_components, Reversed, Undirected,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_graph(rng: &mut StdRng, n: usize, m: usize) -> Graph<(), i64> {
+        let edges: Vec<(usize, usize, i64)> =
+            (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..10))).collect();
+        graph_from_edges(n, &edges)
+    }
+
+    #[test]
+    fn from_edges_groups_by_source_in_input_order() {
+        let edges = [(2, 0, 'a'), (0, 1, 'b'), (2, 1, 'c'), (0, 0, 'd'), (2, 2, 'e')];
+        let csr = CsrGraph::from_edges(vec!["x", "y", "z", "w"], edges.map(|(u, v, e)| (NodeId(u), NodeId(v), e)))
+            .unwrap();
+        assert_eq!((csr.node_count(), csr.edge_count()), (4, 5));
+        assert_eq!(csr.neighbors(NodeId(0)), [NodeId(1), NodeId(0)]);
+        assert_eq!(csr.neighbors(NodeId(1)), []);
+        assert_eq!(csr.neighbors(NodeId(2)), [NodeId(0), NodeId(1), NodeId(2)]);
+        assert_eq!((csr.out_degree(NodeId(2)), csr.out_degree(NodeId(3)), csr.out_degree(NodeId(9))), (3, 0, 0));
+        assert_eq!(csr.edge_range(NodeId(9)), 0..0);
+        let all: Vec<(usize, usize, usize, char)> = csr.edges().map(|(id, u, v, &e)| (id.0, u.0, v.0, e)).collect();
+        assert_eq!(all, [(0, 0, 1, 'b'), (1, 0, 0, 'd'), (2, 2, 0, 'a'), (3, 2, 1, 'c'), (4, 2, 2, 'e')]);
+        for (id, u, v, &e) in csr.edges() {
+            assert_eq!(csr.edge_endpoints(id), Some((u, v)));
+            assert_eq!(csr.get_edge(id), Some(&e));
+        }
+        assert_eq!(csr.edge_endpoints(EdgeId(5)), None);
+        assert_eq!((csr.get_node(NodeId(3)), csr.get_node(NodeId(4))), (Some(&"w"), None));
+    }
+
+    #[test]
+    fn from_edges_rejects_missing_endpoints() {
+        let bad = CsrGraph::from_edges(vec![(); 2], [(NodeId(0), NodeId(1), ()), (NodeId(1), NodeId(2), ())]);
+        assert!(matches!(bad, Err(GraphError::NodeNotFound(NodeId(2)))));
+        let bad = CsrGraph::from_edges(Vec::<()>::new(), [(NodeId(0), NodeId(0), ())]);
+        assert!(matches!(bad, Err(GraphError::NodeNotFound(NodeId(0)))));
+    }
+
+    #[test]
+    fn from_graph_renumbers_live_nodes() {
+        let mut rng = StdRng::seed_from_u64(47);
+        let mut g = random_graph(&mut rng, 8, 20);
+        g.remove_node(NodeId(2)).unwrap();
+        g.remove_node(NodeId(5)).unwrap();
+        let csr = CsrGraph::from_graph(&g);
+        let live: Vec<NodeId> = g.node_ids().collect();
+        assert_eq!((csr.node_count(), csr.edge_count()), (6, g.edge_count()));
+        for (i, &v) in live.iter().enumerate() {
+            assert_eq!(csr.get_node(NodeId(i)), g.get_node(v));
+            let mut expected: Vec<(NodeId, i64)> = g.edges_from(v).map(|(_, e)| (e.to, e.data)).collect();
+            let mut got: Vec<(NodeId, i64)> = csr.edges_from(NodeId(i)).map(|(_, t, &w)| (live[t.0], w)).collect();
+            expected.sort();
+            got.sort();
+            assert_eq!(got, expected);
+        }
+    }
+
+    #[test]
+    fn algorithms_agree_with_the_source_graph() {
+        let mut rng = StdRng::seed_from_u64(4747);
+        for _ in 0..20 {
+            let g = random_graph(&mut rng, 12, 30);
+            let csr = CsrGraph::from_graph(&g);
+            for s in g.node_ids() {
+                assert_eq!(dijkstra(&csr, s, |&w| w).dist, dijkstra(&g, s, |&w| w).dist);
+            }
+            assert_eq!(floyd_warshall(&csr, |&w| w).unwrap().dist, floyd_warshall(&g, |&w| w).unwrap().dist);
+            let sorted = |mut c: Vec<Vec<NodeId>>| {
+                c.iter_mut().for_each(|c| c.sort());
+                c.sort();
+                c
+            };
+            assert_eq!(sorted(tarjan_scc(&csr)), sorted(kosaraju_scc(&g)));
+            assert_eq!(sorted(kosaraju_scc(&csr)), sorted(kosaraju_scc(&g)));
+            assert_eq!(topological_sort(&csr).is_ok(), topological_sort(&g).is_ok());
+            for s in g.node_ids() {
+                assert_eq!(dijkstra(Reversed(&csr), s, |&w| w).dist, dijkstra(Reversed(&g), s, |&w| w).dist);
+                assert_eq!(dijkstra(Undirected(&csr), s, |&w| w).dist, dijkstra(Undirected(&g), s, |&w| w).dist);
+            }
+            assert_eq!(sorted(weakly_connected_components(&csr)), sorted(weakly_connected_components(&g)));
+        }
+    }
+
+    #[test]
+    fn incoming_edges_transpose_the_outgoing_ones() {
+        let mut rng = StdRng::seed_from_u64(4748);
+        let csr = CsrGraph::from_graph(&random_graph(&mut rng, 9, 40));
+        let mut total = 0;
+        for v in csr.node_ids() {
+            let expected: Vec<(EdgeId, NodeId, &i64)> =
+                csr.edges().filter(|e| e.2 == v).map(|(id, from, _, data)| (id, from, data)).collect();
+            assert_eq!(csr.edges_to(v).collect::<Vec<_>>(), expected);
+            assert_eq!(csr.predecessors(v), expected.iter().map(|e| e.1).collect::<Vec<_>>());
+            assert_eq!(csr.in_degree(v), expected.len());
+            let incoming: Vec<_> =
+                csr.edges_directed(v, Direction::Incoming).map(|e| (e.id(), e.source(), e.target())).collect();
+            assert_eq!(incoming, expected.iter().map(|e| (e.0, e.1, v)).collect::<Vec<_>>());
+            let outgoing: Vec<NodeId> = csr.neighbors_directed(v, Direction::Outgoing).collect();
+            assert_eq!(outgoing, CsrGraph::neighbors(&csr, v));
+            total += csr.in_degree(v);
+        }
+        assert_eq!(total, csr.edge_count());
+        assert!(csr.predecessors(NodeId(9)).is_empty());
+        assert_eq!(csr.edges_to(NodeId(42)).count(), 0);
+    }
+}
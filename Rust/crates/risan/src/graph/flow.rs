@@ -0,0 +1,497 @@
+//! Maximum flow (Dinic, push–relabel), minimum cuts and min-cost max-flow. Capacities
+//! and costs come from closures over the edge data; use integer types for exact results.
+//!
+//! The algorithms run on any graph through the `visit` traits. An undirected edge becomes
+//! a pair of opposite arcs with the full capacity each, and its entry in `flow` is the net
+//! amount it carries, in whichever direction.
+
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Mul;
+
+use super::shortest_path::{Entry, SignedWeight, Weight};
+use super::visit::{EdgeRef, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use super::{EdgeId, NodeId};
+
+/// A maximum flow: its value and the flow on each edge, indexed by `EdgeId` up to the
+/// largest id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaxFlow<C> {
+    pub value: C,
+    pub flow: Vec<C>,
+}
+
+/// A minimum `s`-`t` cut: its capacity, the nodes on the source side and the cut edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinCut<C> {
+    pub value: C,
+    pub source_side: Vec<NodeId>,
+    pub edges: Vec<EdgeId>,
+}
+
+/// A minimum-cost maximum flow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinCostFlow<C> {
+    pub value: C,
+    pub cost: C,
+    pub flow: Vec<C>,
+}
+
+/// Residual network: the `k`-th edge reference becomes arc `2k` (forward) and arc `2k + 1`
+/// (reverse), so `a ^ 1` is the partner of arc `a` and the flow along reference `k` is
+/// `cap[2k + 1]`. Self-loops can never carry useful flow and are left out.
+struct Residual<C> {
+    head: Vec<NodeId>,
+    cap: Vec<C>,
+    adj: Vec<Vec<usize>>,
+    /// Edge id of each arc pair.
+    edge: Vec<EdgeId>,
+    /// One past the largest edge id, self-loops included.
+    edge_bound: usize,
+}
+
+impl<C: Weight> Residual<C> {
+    fn new<G>(g: G, capacity: impl Fn(&G::EdgeWeight) -> C) -> Self
+    where
+        G: IntoEdgeReferences + NodeIndexable,
+    {
+        let adj = vec![Vec::new(); g.node_bound()];
+        let mut r = Self { head: Vec::new(), cap: Vec::new(), adj, edge: Vec::new(), edge_bound: 0 };
+        for e in g.edge_references() {
+            r.edge_bound = r.edge_bound.max(e.id().0 + 1);
+            if e.source() == e.target() {
+                continue;
+            }
+            let a = r.head.len();
+            r.head.extend([e.target(), e.source()]);
+            r.cap.extend([capacity(e.weight()), C::zero()]);
+            r.edge.push(e.id());
+            r.adj[e.source().0].push(a);
+            r.adj[e.target().0].push(a + 1);
+        }
+        r
+    }
+
+    fn push(&mut self, a: usize, amount: C) {
+        self.cap[a] = self.cap[a] - amount;
+        self.cap[a ^ 1] = self.cap[a ^ 1] + amount;
+    }
+
+    /// Flow per edge id. The two orientations of an undirected edge cancel, leaving the net
+    /// amount.
+    fn flows(&self) -> Vec<C> {
+        let mut flow = vec![C::zero(); self.edge_bound];
+        for (k, id) in self.edge.iter().enumerate() {
+            let (f, seen) = (self.cap[2 * k + 1], flow[id.0]);
+            flow[id.0] = if f > seen { f - seen } else { seen - f };
+        }
+        flow
+    }
+
+    /// Nodes reachable from `s` through arcs with spare capacity.
+    fn reachable(&self, s: NodeId) -> Vec<bool> {
+        let mut seen = vec![false; self.adj.len()];
+        seen[s.0] = true;
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &a in &self.adj[u.0] {
+                let v = self.head[a];
+                if self.cap[a] > C::zero() && !std::mem::replace(&mut seen[v.0], true) {
+                    queue.push_back(v);
+                }
+            }
+        }
+        seen
+    }
+}
+
+fn min<C: PartialOrd>(a: C, b: C) -> C {
+    if b < a { b } else { a }
+}
+
+/// Dinic's algorithm: blocking flows on BFS level graphs, `O(V^2 E)` in general and
+/// `O(E sqrt V)` on unit networks.
+pub fn dinic<G, C>(g: G, s: NodeId, t: NodeId, capacity: impl Fn(&G::EdgeWeight) -> C) -> MaxFlow<C>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    C: Weight,
+{
+    let mut r = Residual::new(g, capacity);
+    let value = dinic_residual(&mut r, s, t);
+    MaxFlow { value, flow: r.flows() }
+}
+
+fn dinic_residual<C: Weight>(r: &mut Residual<C>, s: NodeId, t: NodeId) -> C {
+    let n = r.adj.len();
+    let mut total = C::zero();
+    if s == t {
+        return total;
+    }
+    loop {
+        let mut level = vec![usize::MAX; n];
+        level[s.0] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &a in &r.adj[u.0] {
+                let v = r.head[a];
+                if r.cap[a] > C::zero() && level[v.0] == usize::MAX {
+                    level[v.0] = level[u.0] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        if level[t.0] == usize::MAX {
+            return total;
+        }
+        // blocking flow: advance along admissible arcs, retreat from dead ends
+        let mut next_arc = vec![0; n];
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = s;
+        loop {
+            if u == t {
+                let bottleneck = path.iter().map(|&a| r.cap[a]).reduce(min).unwrap();
+                for &a in &path {
+                    r.push(a, bottleneck);
+                }
+                total = total + bottleneck;
+                path.clear();
+                u = s;
+                continue;
+            }
+            let admissible = r.adj[u.0][next_arc[u.0]..]
+                .iter()
+                .position(|&a| r.cap[a] > C::zero() && level[r.head[a].0] == level[u.0] + 1);
+            match admissible {
+                Some(k) => {
+                    next_arc[u.0] += k;
+                    let a = r.adj[u.0][next_arc[u.0]];
+                    path.push(a);
+                    u = r.head[a];
+                }
+                None => {
+                    next_arc[u.0] = r.adj[u.0].len();
+                    level[u.0] = usize::MAX;
+                    let Some(a) = path.pop() else { break };
+                    u = r.head[a ^ 1];
+                    next_arc[u.0] += 1;
+                }
+            }
+        }
+    }
+}
+
+/// FIFO push–relabel with the current-arc rule, `O(V^3)`.
+pub fn push_relabel<G, C>(g: G, s: NodeId, t: NodeId, capacity: impl Fn(&G::EdgeWeight) -> C) -> MaxFlow<C>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    C: Weight,
+{
+    let mut r = Residual::new(g, capacity);
+    let n = r.adj.len();
+    let mut height = vec![0usize; n];
+    let mut excess = vec![C::zero(); n];
+    let mut current = vec![0usize; n];
+    let mut active = VecDeque::new();
+    if s != t {
+        height[s.0] = n;
+        for i in 0..r.adj[s.0].len() {
+            let a = r.adj[s.0][i];
+            let c = r.cap[a];
+            if c > C::zero() {
+                let v = r.head[a];
+                r.push(a, c);
+                if v != t && excess[v.0] == C::zero() {
+                    active.push_back(v);
+                }
+                excess[v.0] = excess[v.0] + c;
+            }
+        }
+    }
+    while let Some(u) = active.pop_front() {
+        while excess[u.0] > C::zero() {
+            if current[u.0] == r.adj[u.0].len() {
+                // relabel to one above the lowest residual neighbour
+                height[u.0] = r.adj[u.0].iter().filter(|&&a| r.cap[a] > C::zero()).map(|&a| height[r.head[a].0] + 1).min().unwrap();
+                current[u.0] = 0;
+                continue;
+            }
+            let a = r.adj[u.0][current[u.0]];
+            let v = r.head[a];
+            if r.cap[a] > C::zero() && height[u.0] == height[v.0] + 1 {
+                let d = min(excess[u.0], r.cap[a]);
+                r.push(a, d);
+                excess[u.0] = excess[u.0] - d;
+                if v != s && v != t && excess[v.0] == C::zero() {
+                    active.push_back(v);
+                }
+                excess[v.0] = excess[v.0] + d;
+            } else {
+                current[u.0] += 1;
+            }
+        }
+    }
+    MaxFlow { value: if s == t { C::zero() } else { excess[t.0] }, flow: r.flows() }
+}
+
+/// Minimum `s`-`t` cut from a maximum flow: the source side is everything still reachable
+/// in the residual network. An undirected cut edge is listed once.
+pub fn min_cut<G, C>(g: G, s: NodeId, t: NodeId, capacity: impl Fn(&G::EdgeWeight) -> C) -> MinCut<C>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+    C: Weight,
+{
+    let mut r = Residual::new(g, capacity);
+    let value = dinic_residual(&mut r, s, t);
+    let side = r.reachable(s);
+    MinCut {
+        value,
+        source_side: g.node_identifiers().filter(|v| side[v.0]).collect(),
+        edges: g.edge_references().filter(|e| side[e.source().0] && !side[e.target().0]).map(|e| e.id()).collect(),
+    }
+}
+
+/// Maximum flow of minimum total cost by successive shortest paths: Bellman–Ford
+/// potentials (so negative costs are allowed, negative cycles are not), then Dijkstra on
+/// reduced costs for each augmentation. The cost type must be signed, for the reverse arcs;
+/// a negative-cost undirected edge is itself a negative cycle.
+pub fn min_cost_max_flow<G, C>(
+    g: G,
+    s: NodeId,
+    t: NodeId,
+    capacity: impl Fn(&G::EdgeWeight) -> C,
+    cost: impl Fn(&G::EdgeWeight) -> C,
+) -> MinCostFlow<C>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    C: SignedWeight + Mul<Output = C>,
+{
+    let mut r = Residual::new(g, capacity);
+    let n = r.adj.len();
+    // same references, in the same order, as the residual's arc pairs
+    let arc_cost: Vec<C> = g
+        .edge_references()
+        .filter(|e| e.source() != e.target())
+        .flat_map(|e| {
+            let c = cost(e.weight());
+            [c, -c]
+        })
+        .collect();
+    let mut result = MinCostFlow { value: C::zero(), cost: C::zero(), flow: Vec::new() };
+    if s == t {
+        result.flow = r.flows();
+        return result;
+    }
+
+    // initial potentials: shortest distances from s over arcs with capacity
+    let mut pot: Vec<Option<C>> = vec![None; n];
+    pot[s.0] = Some(C::zero());
+    for _ in 0..n {
+        let mut changed = false;
+        for u in 0..n {
+            let Some(du) = pot[u] else { continue };
+            for &a in &r.adj[u] {
+                let v = r.head[a].0;
+                if r.cap[a] > C::zero() && pot[v].is_none_or(|dv| du + arc_cost[a] < dv) {
+                    pot[v] = Some(du + arc_cost[a]);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut pot: Vec<C> = pot.into_iter().map(|p| p.unwrap_or(C::zero())).collect();
+
+    loop {
+        let mut dist: Vec<Option<C>> = vec![None; n];
+        let mut via = vec![usize::MAX; n];
+        let mut done = vec![false; n];
+        dist[s.0] = Some(C::zero());
+        let mut heap = BinaryHeap::from([Entry { cost: C::zero(), node: s }]);
+        while let Some(Entry { cost: d, node: u }) = heap.pop() {
+            if std::mem::replace(&mut done[u.0], true) {
+                continue;
+            }
+            for &a in &r.adj[u.0] {
+                let v = r.head[a];
+                if r.cap[a] <= C::zero() || done[v.0] {
+                    continue;
+                }
+                let nd = d + arc_cost[a] + pot[u.0] - pot[v.0];
+                if dist[v.0].is_none_or(|dv| nd < dv) {
+                    dist[v.0] = Some(nd);
+                    via[v.0] = a;
+                    heap.push(Entry { cost: nd, node: v });
+                }
+            }
+        }
+        if dist[t.0].is_none() {
+            break;
+        }
+        for v in 0..n {
+            if let Some(d) = dist[v] {
+                pot[v] = pot[v] + d;
+            }
+        }
+        let mut path = Vec::new();
+        let mut v = t;
+        while v != s {
+            let a = via[v.0];
+            path.push(a);
+            v = r.head[a ^ 1];
+        }
+        let bottleneck = path.iter().map(|&a| r.cap[a]).reduce(min).unwrap();
+        for &a in &path {
+            r.push(a, bottleneck);
+            result.cost = result.cost + bottleneck * arc_cost[a];
+        }
+        result.value = result.value + bottleneck;
+    }
+    result.flow = r.flows();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::matching::hungarian;
+    use crate::graph::{graph_from_edges, CsrGraph, Graph, Undirected};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Capacity constraints and conservation at every node but `s` and `t`.
+    fn check_flow(g: &Graph<(), u32>, s: NodeId, t: NodeId, flow: &MaxFlow<u32>) {
+        let mut net = vec![0i64; g.node_bound()];
+        for (id, e) in g.edges() {
+            assert!(flow.flow[id.0] <= e.data);
+            if e.from != e.to {
+                net[e.from.0] -= flow.flow[id.0] as i64;
+                net[e.to.0] += flow.flow[id.0] as i64;
+            }
+        }
+        for v in g.node_ids() {
+            let expected = if v == s && s != t {
+                -(flow.value as i64)
+            } else if v == t && s != t {
+                flow.value as i64
+            } else {
+                0
+            };
+            assert_eq!(net[v.0], expected);
+        }
+    }
+
+    #[test]
+    fn classic_network() {
+        // CLRS figure 26.1, maximum flow 23
+        let g = graph_from_edges(
+            6,
+            &[(0, 1, 16), (0, 2, 13), (2, 1, 4), (1, 3, 12), (3, 2, 9), (2, 4, 14), (4, 3, 7), (3, 5, 20), (4, 5, 4)],
+        );
+        let (s, t) = (NodeId(0), NodeId(5));
+        for flow in [dinic(&g, s, t, |&c| c), push_relabel(&g, s, t, |&c| c)] {
+            assert_eq!(flow.value, 23);
+            check_flow(&g, s, t, &flow);
+        }
+        let cut = min_cut(&g, s, t, |&c| c);
+        assert_eq!(cut.value, 23);
+        assert_eq!(cut.edges.iter().map(|&e| *g.get_edge(e).unwrap()).sum::<u32>(), 23);
+        assert!(cut.source_side.contains(&s) && !cut.source_side.contains(&t));
+    }
+
+    #[test]
+    fn max_flow_algorithms_agree() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for _ in 0..100 {
+            let edges: Vec<(usize, usize, u32)> =
+                (0..20).map(|_| (rng.gen_range(0..8), rng.gen_range(0..8), rng.gen_range(0..10))).collect();
+            let g = graph_from_edges(8, &edges);
+            let (s, t) = (NodeId(0), NodeId(7));
+            let a = dinic(&g, s, t, |&c| c);
+            let b = push_relabel(&g, s, t, |&c| c);
+            assert_eq!(a.value, b.value);
+            check_flow(&g, s, t, &a);
+            check_flow(&g, s, t, &b);
+            let cut = min_cut(&g, s, t, |&c| c);
+            assert_eq!(cut.value, a.value);
+            assert_eq!(cut.edges.iter().map(|&e| *g.get_edge(e).unwrap()).sum::<u32>(), a.value);
+        }
+        let g = graph_from_edges(2, &[(0, 1, 5)]);
+        assert_eq!(dinic(&g, NodeId(0), NodeId(0), |&c| c).value, 0);
+        assert_eq!(push_relabel(&g, NodeId(1), NodeId(0), |&c| c).value, 0);
+    }
+
+    #[test]
+    fn flows_on_csr_graphs_and_undirected_views() {
+        let mut rng = StdRng::seed_from_u64(48);
+        for _ in 0..50 {
+            let edges: Vec<(usize, usize, (u32, i64))> = (0..16)
+                .map(|_| (rng.gen_range(0..7), rng.gen_range(0..7), (rng.gen_range(0..10), rng.gen_range(0..5))))
+                .collect();
+            let g = graph_from_edges(7, &edges);
+            let (s, t) = (NodeId(0), NodeId(6));
+            let cap = |e: &(u32, i64)| e.0;
+            let signed = |e: &(u32, i64)| e.0 as i64;
+
+            let csr = CsrGraph::from_graph(&g);
+            let expected = dinic(&g, s, t, cap);
+            for flow in [dinic(&csr, s, t, cap), push_relabel(&csr, s, t, cap)] {
+                assert_eq!(flow.value, expected.value);
+                assert_eq!(flow.flow.len(), csr.edge_count());
+                assert!(flow.flow.iter().enumerate().all(|(k, &f)| f <= csr.get_edge(EdgeId(k)).unwrap().0));
+            }
+            assert_eq!(min_cut(&csr, s, t, cap).value, expected.value);
+            let (a, b) = (min_cost_max_flow(&csr, s, t, signed, |e| e.1), min_cost_max_flow(&g, s, t, signed, |e| e.1));
+            assert_eq!((a.value, a.cost), (b.value, b.cost));
+
+            // an undirected edge behaves like a pair of opposite edges of the same capacity
+            let both: Vec<_> = edges.iter().flat_map(|&(u, v, w)| [(u, v, w), (v, u, w)]).collect();
+            let doubled = graph_from_edges(7, &both);
+            let u = Undirected(&g);
+            let expected = dinic(&doubled, s, t, cap);
+            for flow in [dinic(u, s, t, cap), push_relabel(u, s, t, cap)] {
+                assert_eq!(flow.value, expected.value);
+                assert!(g.edges().all(|(id, e)| flow.flow[id.0] <= e.data.0));
+            }
+            let cut = min_cut(u, s, t, cap);
+            assert_eq!(cut.value, expected.value);
+            assert_eq!(cut.edges.iter().map(|&e| g.get_edge(e).unwrap().0).sum::<u32>(), cut.value);
+            let a = min_cost_max_flow(u, s, t, signed, |e| e.1);
+            let b = min_cost_max_flow(&doubled, s, t, signed, |e| e.1);
+            assert_eq!((a.value, a.cost), (b.value, b.cost));
+            assert!(g.edges().all(|(id, e)| a.flow[id.0] <= signed(&e.data)));
+        }
+    }
+
+    #[test]
+    fn min_cost_flow_solves_assignments() {
+        let mut rng = StdRng::seed_from_u64(46);
+        for _ in 0..30 {
+            let n = 5;
+            let costs: Vec<Vec<i64>> = (0..n).map(|_| (0..n).map(|_| rng.gen_range(-10..30)).collect()).collect();
+            // source 0, rows 1..=n, columns n+1..=2n, sink 2n+1; data is (capacity, cost)
+            let mut edges = Vec::new();
+            for (i, row) in costs.iter().enumerate() {
+                edges.push((0, 1 + i, (1, 0)));
+                edges.push((1 + n + i, 2 * n + 1, (1, 0)));
+                edges.extend(row.iter().enumerate().map(|(j, &c)| (1 + i, 1 + n + j, (1, c))));
+            }
+            let g = graph_from_edges(2 * n + 2, &edges);
+            let v: Vec<NodeId> = g.node_ids().collect();
+            let flow = min_cost_max_flow(&g, v[0], v[2 * n + 1], |e| e.0, |e| e.1);
+            assert_eq!(flow.value, n as i64);
+            assert_eq!(flow.cost, hungarian(&costs).cost);
+            let cost: i64 = g.edges().map(|(id, e)| flow.flow[id.0] * e.data.1).sum();
+            assert_eq!(cost, flow.cost);
+        }
+    }
+
+    #[test]
+    fn min_cost_flow_prefers_cheap_paths() {
+        // two units from 0 to 3: the direct edge is cheap but carries one unit
+        let g = graph_from_edges(4, &[(0, 3, (1, 1)), (0, 1, (2, 2)), (1, 3, (1, 2)), (1, 2, (1, -3)), (2, 3, (1, 5))]);
+        let flow = min_cost_max_flow(&g, NodeId(0), NodeId(3), |e| e.0, |e| e.1);
+        assert_eq!((flow.value, flow.cost), (3, 1 + 4 + 4));
+        assert_eq!(flow.flow, [1, 2, 1, 1, 1]);
+    }
+}
@@ -0,0 +1,1211 @@
+//! Reading and writing graphs: Graphviz DOT, GraphML, delimited edge lists (CSV) and
+//! Matrix Market adjacency matrices. Node and edge data go through caller closures; the
+//! text formats carry them as string attributes or fields.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use super::{Graph, NodeId};
+
+/// Named string attributes in the order they were written.
+pub type Attributes = Vec<(String, String)>;
+
+/// Value of the attribute `name`, if present.
+pub fn attribute<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+/// Set `name` to `value`, replacing an earlier value.
+fn set_attribute(attrs: &mut Attributes, name: &str, value: &str) {
+    match attrs.iter_mut().find(|(k, _)| k == name) {
+        Some(slot) => slot.1 = value.to_string(),
+        None => attrs.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// Error returned by the graph readers.
+#[derive(Debug)]
+pub enum ReadGraphError {
+    Io(io::Error),
+    /// Malformed input, or a data closure rejected a value.
+    Parse { line: usize, message: String },
+}
+
+impl ReadGraphError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        ReadGraphError::Parse { line, message: message.into() }
+    }
+}
+
+impl fmt::Display for ReadGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadGraphError::Io(e) => write!(f, "I/O error: {}", e),
+            ReadGraphError::Parse { line, message } => write!(f, "{} on line {}", message, line),
+        }
+    }
+}
+
+impl std::error::Error for ReadGraphError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadGraphError::Io(e) => Some(e),
+            ReadGraphError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadGraphError {
+    fn from(e: io::Error) -> Self {
+        ReadGraphError::Io(e)
+    }
+}
+
+/// Nodes and edges collected by a reader before the data closures run: names with
+/// attributes, and edges as node indices.
+struct Collected {
+    nodes: Vec<(String, Attributes, usize)>,
+    index: HashMap<String, usize>,
+    edges: Vec<(usize, usize, Attributes, usize)>,
+}
+
+impl Collected {
+    fn new() -> Self {
+        Self { nodes: Vec::new(), index: HashMap::new(), edges: Vec::new() }
+    }
+
+    /// Index of the node `name`, creating it with `attrs` on first sight.
+    fn node(&mut self, name: &str, attrs: &[(String, String)], line: usize) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i;
+        }
+        self.nodes.push((name.to_string(), attrs.to_vec(), line));
+        self.index.insert(name.to_string(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn build<N, E>(
+        self,
+        mut node: impl FnMut(&str, &[(String, String)]) -> Result<N, String>,
+        mut edge: impl FnMut(&[(String, String)]) -> Result<E, String>,
+    ) -> Result<Graph<N, E>, ReadGraphError> {
+        let mut g = Graph::new();
+        let mut ids = Vec::with_capacity(self.nodes.len());
+        for (name, attrs, line) in &self.nodes {
+            ids.push(g.add_node(node(name, attrs).map_err(|m| ReadGraphError::parse(*line, m))?));
+        }
+        for (a, b, attrs, line) in &self.edges {
+            let data = edge(attrs).map_err(|m| ReadGraphError::parse(*line, m))?;
+            g.add_edge(ids[*a], ids[*b], data).expect("endpoints were just added");
+        }
+        Ok(g)
+    }
+}
+
+// ---------------------------------------------------------------------------------------
+// DOT
+// ---------------------------------------------------------------------------------------
+
+/// `s` as a DOT id: bare when it is an identifier or numeral other than a keyword,
+/// quoted with `"` and `\` escaped otherwise.
+fn dot_id(s: &str) -> String {
+    const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+    let identifier = !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let numeral = s.parse::<f64>().is_ok() && s.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-');
+    let keyword = KEYWORDS.iter().any(|kw| s.eq_ignore_ascii_case(kw));
+    if (identifier || numeral) && !keyword {
+        s.to_string()
+    } else {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+fn write_dot_attributes(out: &mut impl Write, attrs: &[(String, String)]) -> io::Result<()> {
+    if attrs.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = attrs.iter().map(|(k, v)| format!("{}={}", dot_id(k), dot_id(v))).collect();
+    write!(out, " [{}]", list.join(", "))
+}
+
+/// Write `g` as a Graphviz `digraph`, naming nodes by their `NodeId` index. The closures
+/// give the attributes of each node and edge, e.g. `label` or `weight`.
+pub fn write_dot<N, E>(
+    g: &Graph<N, E>,
+    out: &mut impl Write,
+    node_attrs: impl Fn(NodeId, &N) -> Attributes,
+    edge_attrs: impl Fn(&E) -> Attributes,
+) -> io::Result<()> {
+    writeln!(out, "digraph {{")?;
+    for v in g.node_ids() {
+        write!(out, "    {}", v.0)?;
+        write_dot_attributes(out, &node_attrs(v, &g.node(v).unwrap().data))?;
+        writeln!(out, ";")?;
+    }
+    for (_, e) in g.edges() {
+        write!(out, "    {} -> {}", e.from.0, e.to.0)?;
+        write_dot_attributes(out, &edge_attrs(&e.data))?;
+        writeln!(out, ";")?;
+    }
+    writeln!(out, "}}")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    /// An identifier, numeral, quoted string or HTML string.
+    Id { text: String, quoted: bool },
+    /// `->` or `--`.
+    EdgeOp,
+    Punct(char),
+}
+
+fn lex_dot(src: &str) -> Result<Vec<(DotToken, usize)>, ReadGraphError> {
+    let mut tokens: Vec<(DotToken, usize)> = Vec::new();
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '#' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        None => return Err(ReadGraphError::parse(line, "unterminated comment")),
+                        Some('/') if prev == '*' => break,
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            prev = c;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(ReadGraphError::parse(start, "unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => text.push(c),
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                text.push('\\');
+                                text.push(c);
+                            }
+                            None => return Err(ReadGraphError::parse(start, "unterminated string")),
+                        },
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            text.push(c);
+                        }
+                    }
+                }
+                // "a" + "b" concatenates
+                if let Some((DotToken::Punct('+'), _)) = tokens.last() {
+                    if let Some((DotToken::Id { text: prev, quoted: true }, _)) = tokens.get(tokens.len().wrapping_sub(2)) {
+                        let joined = format!("{}{}", prev, text);
+                        tokens.truncate(tokens.len() - 2);
+                        tokens.push((DotToken::Id { text: joined, quoted: true }, start));
+                        continue;
+                    }
+                }
+                tokens.push((DotToken::Id { text, quoted: true }, start));
+            }
+            '<' => {
+                let start = line;
+                let mut depth = 1;
+                let mut text = String::new();
+                loop {
+                    let Some(c) = chars.next() else {
+                        return Err(ReadGraphError::parse(start, "unterminated HTML string"));
+                    };
+                    match c {
+                        '<' => depth += 1,
+                        '>' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        '\n' => line += 1,
+                        _ => {}
+                    }
+                    text.push(c);
+                }
+                tokens.push((DotToken::Id { text, quoted: true }, start));
+            }
+            '-' if matches!(chars.peek(), Some('>') | Some('-')) => {
+                chars.next();
+                tokens.push((DotToken::EdgeOp, line));
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' | '+' => tokens.push((DotToken::Punct(c), line)),
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' || !c.is_ascii() => {
+                let mut text = c.to_string();
+                while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || c == '_' || c == '.' || !c.is_ascii()) {
+                    text.push(c);
+                }
+                tokens.push((DotToken::Id { text, quoted: false }, line));
+            }
+            c => return Err(ReadGraphError::parse(line, format!("unexpected character '{}'", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<(DotToken, usize)>,
+    pos: usize,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or(self.tokens.last()).map_or(1, |&(_, l)| l)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&DotToken::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ReadGraphError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(ReadGraphError::parse(self.line(), format!("expected '{}'", c)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, ReadGraphError> {
+        match self.tokens.get(self.pos) {
+            Some((DotToken::Id { text, .. }, _)) => {
+                self.pos += 1;
+                Ok(text.clone())
+            }
+            _ => Err(ReadGraphError::parse(self.line(), "expected an identifier")),
+        }
+    }
+
+    /// Whether the next token is the unquoted keyword `kw` (case-insensitive).
+    fn at_keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(DotToken::Id { text, quoted: false }) if text.eq_ignore_ascii_case(kw))
+    }
+
+    /// `[a=b, c=d][e=f]`, possibly absent.
+    fn attr_lists(&mut self) -> Result<Attributes, ReadGraphError> {
+        let mut attrs = Attributes::new();
+        while self.eat('[') {
+            while !self.eat(']') {
+                let key = self.id()?;
+                let value = if self.eat('=') { self.id()? } else { "true".to_string() };
+                set_attribute(&mut attrs, &key, &value);
+                if !self.eat(',') {
+                    self.eat(';');
+                }
+            }
+        }
+        Ok(attrs)
+    }
+
+    /// A node id, dropping any `:port:compass` suffix.
+    fn node_id(&mut self) -> Result<String, ReadGraphError> {
+        let name = self.id()?;
+        for _ in 0..2 {
+            if self.eat(':') {
+                self.id()?;
+            }
+        }
+        Ok(name)
+    }
+}
+
+/// Read a Graphviz `graph` or `digraph`. Nodes appear in order of first mention and
+/// receive their name and attributes (including `node [...]` defaults); undirected `--`
+/// edges are added once, in the direction written. In quoted strings `\"` and `\\` are
+/// unescaped and other backslash sequences (label escapes like `\n`) kept as written.
+/// Subgraphs are not supported.
+pub fn read_dot<N, E>(
+    mut input: impl BufRead,
+    node: impl FnMut(&str, &[(String, String)]) -> Result<N, String>,
+    edge: impl FnMut(&[(String, String)]) -> Result<E, String>,
+) -> Result<Graph<N, E>, ReadGraphError> {
+    let mut src = String::new();
+    input.read_to_string(&mut src)?;
+    let mut p = DotParser { tokens: lex_dot(&src)?, pos: 0 };
+    if p.at_keyword("strict") {
+        p.pos += 1;
+    }
+    if !p.at_keyword("graph") && !p.at_keyword("digraph") {
+        return Err(ReadGraphError::parse(p.line(), "expected 'graph' or 'digraph'"));
+    }
+    p.pos += 1;
+    if !p.eat('{') {
+        p.id()?;
+        p.expect('{')?;
+    }
+    let mut found = Collected::new();
+    let mut node_defaults = Attributes::new();
+    let mut edge_defaults = Attributes::new();
+    while !p.eat('}') {
+        let line = p.line();
+        if p.peek().is_none() {
+            return Err(ReadGraphError::parse(line, "expected '}'"));
+        }
+        if p.at_keyword("subgraph") || p.peek() == Some(&DotToken::Punct('{')) {
+            return Err(ReadGraphError::parse(line, "subgraphs are not supported"));
+        }
+        let defaults = ["graph", "node", "edge"].into_iter().find(|kw| p.at_keyword(kw));
+        if let Some(kw) = defaults {
+            p.pos += 1;
+            let attrs = p.attr_lists()?;
+            let target = match kw {
+                "node" => &mut node_defaults,
+                "edge" => &mut edge_defaults,
+                _ => &mut Attributes::new(),
+            };
+            for (k, v) in &attrs {
+                set_attribute(target, k, v);
+            }
+        } else {
+            let first = p.node_id()?;
+            if p.eat('=') {
+                // graph attribute
+                p.id()?;
+            } else {
+                let mut chain = vec![first];
+                while p.peek() == Some(&DotToken::EdgeOp) {
+                    p.pos += 1;
+                    chain.push(p.node_id()?);
+                }
+                let attrs = p.attr_lists()?;
+                if chain.len() == 1 {
+                    let i = found.node(&chain[0], &node_defaults, line);
+                    for (k, v) in &attrs {
+                        set_attribute(&mut found.nodes[i].1, k, v);
+                    }
+                } else {
+                    let mut edge_attrs = edge_defaults.clone();
+                    for (k, v) in &attrs {
+                        set_attribute(&mut edge_attrs, k, v);
+                    }
+                    let ends: Vec<usize> = chain.iter().map(|name| found.node(name, &node_defaults, line)).collect();
+                    for pair in ends.windows(2) {
+                        found.edges.push((pair[0], pair[1], edge_attrs.clone(), line));
+                    }
+                }
+            }
+        }
+        p.eat(';');
+    }
+    if p.peek().is_some() {
+        return Err(ReadGraphError::parse(p.line(), "unexpected input after the graph"));
+    }
+    found.build(node, edge)
+}
+
+// ---------------------------------------------------------------------------------------
+// GraphML
+// ---------------------------------------------------------------------------------------
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Replace the predefined and numeric character references; unknown entities are kept.
+fn xml_unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else { break };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|h| u32::from_str_radix(h, 16))
+                .or_else(|| entity.strip_prefix('#').map(|d| d.parse()))
+                .and_then(|n| n.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Write `g` as a directed GraphML document. The attributes returned by the closures
+/// become `<data>` elements, declared as string keys.
+pub fn write_graphml<N, E>(
+    g: &Graph<N, E>,
+    out: &mut impl Write,
+    node_attrs: impl Fn(NodeId, &N) -> Attributes,
+    edge_attrs: impl Fn(&E) -> Attributes,
+) -> io::Result<()> {
+    let nodes: Vec<(NodeId, Attributes)> = g.node_ids().map(|v| (v, node_attrs(v, &g.node(v).unwrap().data))).collect();
+    let edges: Vec<_> = g.edges().map(|(id, e)| (id, e.from, e.to, edge_attrs(&e.data))).collect();
+    // key ids: "n0", "n1", ... for node attributes and "e0", ... for edge attributes
+    let mut node_keys: Vec<&str> = Vec::new();
+    for (k, _) in nodes.iter().flat_map(|(_, a)| a) {
+        if !node_keys.contains(&k.as_str()) {
+            node_keys.push(k);
+        }
+    }
+    let mut edge_keys: Vec<&str> = Vec::new();
+    for (k, _) in edges.iter().flat_map(|(.., a)| a) {
+        if !edge_keys.contains(&k.as_str()) {
+            edge_keys.push(k);
+        }
+    }
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+    for (prefix, target, keys) in [("n", "node", &node_keys), ("e", "edge", &edge_keys)] {
+        for (i, k) in keys.iter().enumerate() {
+            writeln!(out, r#"  <key id="{}{}" for="{}" attr.name="{}" attr.type="string"/>"#, prefix, i, target, xml_escape(k))?;
+        }
+    }
+    writeln!(out, r#"  <graph id="G" edgedefault="directed">"#)?;
+    let write_data = |out: &mut dyn Write, prefix: &str, keys: &[&str], attrs: &Attributes| -> io::Result<()> {
+        for (k, v) in attrs {
+            let i = keys.iter().position(|key| key == k).unwrap();
+            writeln!(out, r#"      <data key="{}{}">{}</data>"#, prefix, i, xml_escape(v))?;
+        }
+        Ok(())
+    };
+    for (v, attrs) in &nodes {
+        if attrs.is_empty() {
+            writeln!(out, r#"    <node id="n{}"/>"#, v.0)?;
+        } else {
+            writeln!(out, r#"    <node id="n{}">"#, v.0)?;
+            write_data(out, "n", &node_keys, attrs)?;
+            writeln!(out, "    </node>")?;
+        }
+    }
+    for (id, from, to, attrs) in &edges {
+        let open = format!(r#"<edge id="e{}" source="n{}" target="n{}""#, id.0, from.0, to.0);
+        if attrs.is_empty() {
+            writeln!(out, "    {}/>", open)?;
+        } else {
+            writeln!(out, "    {}>", open)?;
+            write_data(out, "e", &edge_keys, attrs)?;
+            writeln!(out, "    </edge>")?;
+        }
+    }
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+enum XmlEvent<'a> {
+    /// Element name without namespace prefix, attributes, and whether it is self-closing.
+    Start(&'a str, Vec<(&'a str, String)>, bool),
+    End(&'a str),
+    Text(String),
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Offset just past the `>` closing the tag at the start of `rest`, skipping quoted
+/// attribute values (which may contain `>`).
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, b) in rest.bytes().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), b) if b == q => quote = None,
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split an XML document into elements and text, skipping the prolog, comments, doctype
+/// and processing instructions. Each event carries its line number.
+fn lex_xml(src: &str) -> Result<Vec<(XmlEvent<'_>, usize)>, ReadGraphError> {
+    let mut events = Vec::new();
+    let mut pos = 0;
+    // line of `pos`, counted incrementally since `pos` only moves forward
+    let (mut line, mut counted) = (1, 0);
+    while pos < src.len() {
+        line += src.as_bytes()[counted..pos].iter().filter(|&&b| b == b'\n').count();
+        counted = pos;
+        let rest = &src[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            events.push((XmlEvent::Text(xml_unescape(&rest[..end])), line));
+            pos += end;
+            continue;
+        }
+        let skip_to = |marker: &str| {
+            rest.find(marker).map(|i| i + marker.len()).ok_or_else(|| ReadGraphError::parse(line, "unterminated markup"))
+        };
+        if rest.starts_with("<!--") {
+            pos += skip_to("-->")?;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").ok_or_else(|| ReadGraphError::parse(line, "unterminated CDATA"))?;
+            events.push((XmlEvent::Text(cdata[..end].to_string()), line));
+            pos += "<![CDATA[".len() + end + 3;
+        } else if rest.starts_with("<?") {
+            pos += skip_to("?>")?;
+        } else if rest.starts_with("<!") {
+            pos += skip_to(">")?;
+        } else {
+            let end = tag_end(rest).ok_or_else(|| ReadGraphError::parse(line, "unterminated markup"))?;
+            let body = &rest[1..end - 1];
+            pos += end;
+            if let Some(name) = body.strip_prefix('/') {
+                events.push((XmlEvent::End(local_name(name.trim())), line));
+                continue;
+            }
+            let (body, empty) = match body.strip_suffix('/') {
+                Some(b) => (b, true),
+                None => (body, false),
+            };
+            let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+            let mut attrs = Vec::new();
+            let mut a = body[name_end..].trim_start();
+            while !a.is_empty() {
+                let bad = || ReadGraphError::parse(line, "malformed attribute");
+                let eq = a.find('=').ok_or_else(bad)?;
+                let key = a[..eq].trim();
+                let value = a[eq + 1..].trim_start();
+                let quote = value.chars().next().filter(|&q| q == '"' || q == '\'').ok_or_else(bad)?;
+                let close = value[1..].find(quote).ok_or_else(bad)? + 1;
+                attrs.push((local_name(key), xml_unescape(&value[1..close])));
+                a = value[close + 1..].trim_start();
+            }
+            events.push((XmlEvent::Start(local_name(&body[..name_end]), attrs, empty), line));
+        }
+    }
+    Ok(events)
+}
+
+/// Read a GraphML document. Node data receives the node's `id` and its `<data>` values
+/// named by their key's `attr.name` (with key defaults filled in); edges are added in
+/// document order regardless of `edgedefault`. Nested graphs and hyperedges are not
+/// supported.
+pub fn read_graphml<N, E>(
+    mut input: impl BufRead,
+    node: impl FnMut(&str, &[(String, String)]) -> Result<N, String>,
+    edge: impl FnMut(&[(String, String)]) -> Result<E, String>,
+) -> Result<Graph<N, E>, ReadGraphError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Owner {
+        None,
+        Key,
+        Node(usize),
+        Edge(usize),
+    }
+    let mut src = String::new();
+    input.read_to_string(&mut src)?;
+    // key id -> (domain, attribute name, default)
+    let mut keys: HashMap<String, (String, String, Option<String>)> = HashMap::new();
+    let mut key_order: Vec<String> = Vec::new();
+    let mut found = Collected::new();
+    let mut pending_edges: Vec<(String, String, Attributes, usize)> = Vec::new();
+    let mut owner = Owner::None;
+    let mut data_key: Option<String> = None;
+    let mut text = String::new();
+    let mut graphs = 0;
+    for (event, line) in lex_xml(&src)? {
+        let attr = |attrs: &[(&str, String)], name: &str| attrs.iter().find(|(k, _)| *k == name).map(|(_, v)| v.clone());
+        match event {
+            XmlEvent::Start(name, attrs, empty) => {
+                let required = |name: &str| attr(&attrs, name).ok_or_else(|| ReadGraphError::parse(line, format!("missing '{}' attribute", name)));
+                match name {
+                    "graph" => {
+                        graphs += 1;
+                        if graphs > 1 {
+                            return Err(ReadGraphError::parse(line, "nested or multiple graphs are not supported"));
+                        }
+                    }
+                    "key" => {
+                        let id = required("id")?;
+                        let domain = attr(&attrs, "for").unwrap_or_else(|| "all".to_string());
+                        let attr_name = attr(&attrs, "attr.name").unwrap_or_else(|| id.clone());
+                        keys.insert(id.clone(), (domain, attr_name, None));
+                        key_order.push(id.clone());
+                        if !empty {
+                            owner = Owner::Key;
+                            data_key = Some(id);
+                        }
+                    }
+                    "default" => text.clear(),
+                    "node" => {
+                        let id = required("id")?;
+                        if found.index.contains_key(&id) {
+                            return Err(ReadGraphError::parse(line, format!("duplicate node '{}'", id)));
+                        }
+                        let i = found.node(&id, &[], line);
+                        owner = if empty { Owner::None } else { Owner::Node(i) };
+                    }
+                    "edge" => {
+                        pending_edges.push((required("source")?, required("target")?, Attributes::new(), line));
+                        owner = if empty { Owner::None } else { Owner::Edge(pending_edges.len() - 1) };
+                    }
+                    "data" => {
+                        data_key = Some(required("key")?);
+                        text.clear();
+                    }
+                    "hyperedge" => return Err(ReadGraphError::parse(line, "hyperedges are not supported")),
+                    _ => {}
+                }
+            }
+            XmlEvent::Text(t) => text.push_str(&t),
+            XmlEvent::End(name) => match name {
+                "default" if owner == Owner::Key => {
+                    let id = data_key.as_ref().unwrap();
+                    keys.get_mut(id).unwrap().2 = Some(text.clone());
+                }
+                "data" => {
+                    let key = data_key.take().unwrap_or_default();
+                    let attr_name = keys.get(&key).map_or(key.as_str(), |k| k.1.as_str()).to_string();
+                    match owner {
+                        Owner::Node(i) => set_attribute(&mut found.nodes[i].1, &attr_name, &text),
+                        Owner::Edge(i) => set_attribute(&mut pending_edges[i].2, &attr_name, &text),
+                        Owner::None | Owner::Key => {}
+                    }
+                }
+                "key" | "node" | "edge" => owner = Owner::None,
+                _ => {}
+            },
+        }
+    }
+    let fill_defaults = |attrs: &mut Attributes, domain: &str| {
+        for id in &key_order {
+            let (d, name, default) = &keys[id];
+            if let Some(default) = default {
+                if (d == domain || d == "all") && attribute(attrs, name).is_none() {
+                    attrs.push((name.clone(), default.clone()));
+                }
+            }
+        }
+    };
+    for n in &mut found.nodes {
+        fill_defaults(&mut n.1, "node");
+    }
+    for (source, target, mut attrs, line) in pending_edges {
+        let end = |name: &str| found.index.get(name).copied().ok_or_else(|| ReadGraphError::parse(line, format!("unknown node '{}'", name)));
+        let (a, b) = (end(&source)?, end(&target)?);
+        fill_defaults(&mut attrs, "edge");
+        found.edges.push((a, b, attrs, line));
+    }
+    found.build(node, edge)
+}
+
+// ---------------------------------------------------------------------------------------
+// Edge lists
+// ---------------------------------------------------------------------------------------
+
+/// Layout of a delimited edge list: one edge per line, `source target [fields...]`. The
+/// default is whitespace-separated without a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EdgeListFormat {
+    /// Field separator; `None` splits on runs of whitespace.
+    pub delimiter: Option<char>,
+    /// Skip the first line when reading.
+    pub header: bool,
+}
+
+impl EdgeListFormat {
+    /// Comma-separated with a header row.
+    pub fn csv() -> Self {
+        Self { delimiter: Some(','), header: true }
+    }
+}
+
+/// Split a line into fields; double-quoted fields may contain the delimiter and `""`.
+fn split_fields(line: &str, delimiter: Option<char>) -> Result<Vec<String>, String> {
+    let is_sep = |c: char| match delimiter {
+        Some(d) => c == d,
+        None => c.is_whitespace(),
+    };
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        if delimiter.is_none() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+        }
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    None => return Err("unterminated quoted field".to_string()),
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                }
+            }
+            if chars.peek().is_some_and(|&c| !is_sep(c)) {
+                return Err("text after a quoted field".to_string());
+            }
+        } else {
+            while let Some(c) = chars.next_if(|&c| !is_sep(c)) {
+                field.push(c);
+            }
+            if delimiter.is_some() {
+                field = field.trim().to_string();
+            }
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            break;
+        }
+    }
+    Ok(fields)
+}
+
+fn quote_field(field: &str, delimiter: Option<char>) -> String {
+    let needs_quotes = field.is_empty() && delimiter.is_none()
+        || field.contains(|c: char| c == '"' || c == '\n' || delimiter.map_or(c.is_whitespace(), |d| c == d));
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write one line per edge: the names of its endpoints followed by `edge_fields`.
+/// Isolated nodes do not appear; write any header row before calling.
+pub fn write_edge_list<N, E>(
+    g: &Graph<N, E>,
+    out: &mut impl Write,
+    format: EdgeListFormat,
+    node_name: impl Fn(NodeId, &N) -> String,
+    edge_fields: impl Fn(&E) -> Vec<String>,
+) -> io::Result<()> {
+    let sep = format.delimiter.map_or(" ".to_string(), String::from);
+    let names: Vec<Option<String>> =
+        (0..g.node_bound()).map(|i| g.node(NodeId(i)).map(|n| quote_field(&node_name(NodeId(i), &n.data), format.delimiter))).collect();
+    for (_, e) in g.edges() {
+        let mut fields = vec![names[e.from.0].clone().unwrap(), names[e.to.0].clone().unwrap()];
+        fields.extend(edge_fields(&e.data).iter().map(|f| quote_field(f, format.delimiter)));
+        writeln!(out, "{}", fields.join(&sep))?;
+    }
+    Ok(())
+}
+
+/// Read an edge list. Each line names a source and target node (created on first
+/// mention, in order) and passes any remaining fields to `edge`. Blank lines and lines
+/// starting with `#` or `%` are skipped.
+pub fn read_edge_list<N, E>(
+    input: impl BufRead,
+    format: EdgeListFormat,
+    mut node: impl FnMut(&str) -> Result<N, String>,
+    mut edge: impl FnMut(&[String]) -> Result<E, String>,
+) -> Result<Graph<N, E>, ReadGraphError> {
+    let mut g = Graph::new();
+    let mut ids: HashMap<String, NodeId> = HashMap::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let number = i + 1;
+        let trimmed = line.trim();
+        if (format.header && i == 0) || trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('%') {
+            continue;
+        }
+        let fields = split_fields(&line, format.delimiter).map_err(|m| ReadGraphError::parse(number, m))?;
+        if fields.len() < 2 {
+            return Err(ReadGraphError::parse(number, "expected a source and a target"));
+        }
+        let mut end = |name: &String| -> Result<NodeId, ReadGraphError> {
+            if let Some(&id) = ids.get(name) {
+                return Ok(id);
+            }
+            let id = g.add_node(node(name).map_err(|m| ReadGraphError::parse(number, m))?);
+            ids.insert(name.clone(), id);
+            Ok(id)
+        };
+        let (a, b) = (end(&fields[0])?, end(&fields[1])?);
+        let data = edge(&fields[2..]).map_err(|m| ReadGraphError::parse(number, m))?;
+        g.add_edge(a, b, data).expect("endpoints were just added");
+    }
+    Ok(g)
+}
+
+// ---------------------------------------------------------------------------------------
+// Matrix Market
+// ---------------------------------------------------------------------------------------
+
+/// Value type of a Matrix Market file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixField {
+    Real,
+    Integer,
+    /// Structure only: entries have no value (read as `1.0`).
+    Pattern,
+}
+
+/// Write the adjacency matrix of `g` in Matrix Market coordinate format, one entry per
+/// edge (parallel edges give repeated entries). Rows and columns number the live nodes
+/// from 1 in `node_ids` order.
+pub fn write_matrix_market<N, E>(
+    g: &Graph<N, E>,
+    out: &mut impl Write,
+    field: MatrixField,
+    value: impl Fn(&E) -> f64,
+) -> io::Result<()> {
+    let mut index = vec![0; g.node_bound()];
+    for (i, v) in g.node_ids().enumerate() {
+        index[v.0] = i + 1;
+    }
+    let name = match field {
+        MatrixField::Real => "real",
+        MatrixField::Integer => "integer",
+        MatrixField::Pattern => "pattern",
+    };
+    writeln!(out, "%%MatrixMarket matrix coordinate {} general", name)?;
+    writeln!(out, "{} {} {}", g.node_count(), g.node_count(), g.edge_count())?;
+    for (_, e) in g.edges() {
+        let (i, j) = (index[e.from.0], index[e.to.0]);
+        match field {
+            MatrixField::Real => writeln!(out, "{} {} {}", i, j, value(&e.data))?,
+            MatrixField::Integer => writeln!(out, "{} {} {}", i, j, value(&e.data) as i64)?,
+            MatrixField::Pattern => writeln!(out, "{} {}", i, j)?,
+        }
+    }
+    Ok(())
+}
+
+/// Read a square Matrix Market matrix as a graph with one node per row (given its
+/// 0-based index) and an edge `i -> j` per entry. Coordinate and dense array files with
+/// real, integer or pattern values are accepted; symmetric and skew-symmetric storage
+/// adds the mirrored edges, and dense zeros are not edges.
+pub fn read_matrix_market<N, E>(
+    input: impl BufRead,
+    mut node: impl FnMut(usize) -> N,
+    mut edge: impl FnMut(f64) -> E,
+) -> Result<Graph<N, E>, ReadGraphError> {
+    let mut lines = input.lines().enumerate().map(|(i, l)| l.map(|l| (i + 1, l)));
+    let (_, header) = lines.next().ok_or_else(|| ReadGraphError::parse(1, "empty input"))??;
+    let words: Vec<String> = header.split_whitespace().map(str::to_ascii_lowercase).collect();
+    if words.len() != 5 || words[0] != "%%matrixmarket" || words[1] != "matrix" {
+        return Err(ReadGraphError::parse(1, "expected a '%%MatrixMarket matrix' header"));
+    }
+    let dense = match words[2].as_str() {
+        "coordinate" => false,
+        "array" => true,
+        f => return Err(ReadGraphError::parse(1, format!("unsupported format '{}'", f))),
+    };
+    let field = match words[3].as_str() {
+        "real" => MatrixField::Real,
+        "integer" => MatrixField::Integer,
+        "pattern" if !dense => MatrixField::Pattern,
+        f => return Err(ReadGraphError::parse(1, format!("unsupported field '{}'", f))),
+    };
+    let mirror = match words[4].as_str() {
+        "general" => None,
+        "symmetric" => Some(1.0),
+        "skew-symmetric" => Some(-1.0),
+        s => return Err(ReadGraphError::parse(1, format!("unsupported symmetry '{}'", s))),
+    };
+    let mut data = lines.filter(|l| l.as_ref().map_or(true, |(_, l)| !l.trim().is_empty() && !l.starts_with('%')));
+    fn parse_err<T>(line: usize) -> impl Fn(T) -> ReadGraphError {
+        move |_| ReadGraphError::parse(line, "invalid number")
+    }
+    let (line, size) = data.next().ok_or_else(|| ReadGraphError::parse(1, "missing size line"))??;
+    let size: Vec<usize> = size.split_whitespace().map(str::parse).collect::<Result<_, _>>().map_err(parse_err(line))?;
+    if size.len() != if dense { 2 } else { 3 } {
+        return Err(ReadGraphError::parse(line, "malformed size line"));
+    }
+    if size[0] != size[1] {
+        return Err(ReadGraphError::parse(line, "adjacency matrix must be square"));
+    }
+    let n = size[0];
+    let mut g = Graph::new();
+    let ids: Vec<NodeId> = (0..n).map(|i| g.add_node(node(i))).collect();
+    let parse_value = |line: usize, s: Option<&str>| -> Result<f64, ReadGraphError> {
+        let s = s.ok_or_else(|| ReadGraphError::parse(line, "missing value"))?;
+        match field {
+            MatrixField::Integer => s.parse::<i64>().map(|v| v as f64).map_err(parse_err(line)),
+            _ => s.parse::<f64>().map_err(parse_err(line)),
+        }
+    };
+    let mut add = |g: &mut Graph<N, E>, i: usize, j: usize, v: f64| {
+        g.add_edge(ids[i], ids[j], edge(v)).expect("nodes exist");
+        if let Some(sign) = mirror.filter(|_| i != j) {
+            g.add_edge(ids[j], ids[i], edge(sign * v)).expect("nodes exist");
+        }
+    };
+    if dense {
+        // column-major; symmetric storage lists only the lower triangle
+        for j in 0..n {
+            let first = match mirror {
+                None => 0,
+                Some(s) if s > 0.0 => j,
+                Some(_) => j + 1,
+            };
+            for i in first..n {
+                let (line, text) = data.next().ok_or_else(|| ReadGraphError::parse(line, "missing entries"))??;
+                let v = parse_value(line, text.split_whitespace().next())?;
+                if v != 0.0 {
+                    add(&mut g, i, j, v);
+                }
+            }
+        }
+    } else {
+        for _ in 0..size[2] {
+            let (line, text) = data.next().ok_or_else(|| ReadGraphError::parse(line, "missing entries"))??;
+            let mut parts = text.split_whitespace();
+            let mut index = || -> Result<usize, ReadGraphError> {
+                let k: usize = parts.next().ok_or_else(|| ReadGraphError::parse(line, "missing index"))?.parse().map_err(parse_err(line))?;
+                if k == 0 || k > n {
+                    return Err(ReadGraphError::parse(line, "index out of range"));
+                }
+                Ok(k - 1)
+            };
+            let (i, j) = (index()?, index()?);
+            let v = if field == MatrixField::Pattern { 1.0 } else { parse_value(line, parts.next())? };
+            add(&mut g, i, j, v);
+        }
+    }
+    Ok(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph_from_edges;
+
+    /// `(from, to, data)` of every edge, in id order.
+    fn edge_list<N, E: Clone>(g: &Graph<N, E>) -> Vec<(usize, usize, E)> {
+        g.edges().map(|(_, e)| (e.from.0, e.to.0, e.data.clone())).collect()
+    }
+
+    /// Graph on `names.len()` nodes with string edge labels; node names are passed to the
+    /// writers by id.
+    fn labelled(names: &[&str], edges: &[(usize, usize, &str)]) -> Graph<(), String> {
+        let edges: Vec<(usize, usize, String)> = edges.iter().map(|&(u, v, w)| (u, v, w.to_string())).collect();
+        graph_from_edges(names.len(), &edges)
+    }
+
+    fn read_dot_labels(src: &str) -> Result<Graph<String, String>, ReadGraphError> {
+        read_dot(
+            src.as_bytes(),
+            |_, a| Ok(attribute(a, "label").unwrap_or_default().to_string()),
+            |a| Ok(attribute(a, "label").unwrap_or_default().to_string()),
+        )
+    }
+
+    fn error_line<T>(result: Result<T, ReadGraphError>) -> usize {
+        match result {
+            Err(ReadGraphError::Parse { line, .. }) => line,
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn dot_ids_are_quoted_and_escaped_when_needed() {
+        assert_eq!(dot_id("abc_1"), "abc_1");
+        assert_eq!(dot_id("-1.5"), "-1.5");
+        assert_eq!(dot_id("1abc"), "\"1abc\"");
+        assert_eq!(dot_id(""), "\"\"");
+        assert_eq!(dot_id("Graph"), "\"Graph\"");
+        assert_eq!(dot_id("a\\b\"c"), "\"a\\\\b\\\"c\"");
+    }
+
+    #[test]
+    fn dot_round_trip_with_awkward_labels() {
+        let names = ["node", "EDGE", "strict", "a\\b", "say \"hi\"", "x -> y; }", "back\\\"slash\\", ""];
+        let edges: Vec<(usize, usize, &str)> =
+            (0..names.len()).map(|i| (i, (i + 1) % names.len(), names[(i + 3) % names.len()])).collect();
+        let g = labelled(&names, &edges);
+        let mut out = Vec::new();
+        write_dot(&g, &mut out, |v, _| vec![("label".into(), names[v.0].into())], |s| vec![("label".into(), s.clone())])
+            .unwrap();
+        let back = read_dot_labels(std::str::from_utf8(&out).unwrap()).unwrap();
+        let nodes: Vec<&str> = back.node_ids().map(|v| back.node(v).unwrap().data.as_str()).collect();
+        assert_eq!(nodes, names);
+        assert_eq!(edge_list(&back), edge_list(&g));
+    }
+
+    #[test]
+    fn dot_reader_handles_the_common_syntax() {
+        let src = "strict graph G {\n\
+                   // comment\n\
+                   node [label=\"default\"]; /* block\n comment */\n\
+                   a [label=\"first\" + \" part\"];\n\
+                   a -- b -- c [label=chain];\n\
+                   # preprocessor-style line\n\
+                   c:port -- \"a\" [label=\"two\\\nlines\\n\"];\n\
+                   }";
+        let g = read_dot_labels(src).unwrap();
+        let nodes: Vec<&str> = g.node_ids().map(|v| g.node(v).unwrap().data.as_str()).collect();
+        assert_eq!(nodes, ["first part", "default", "default"]);
+        let expected = [(0, 1, "chain"), (1, 2, "chain"), (2, 0, "twolines\\n")];
+        assert_eq!(edge_list(&g), expected.map(|(u, v, s)| (u, v, s.to_string())));
+    }
+
+    #[test]
+    fn dot_errors_report_their_line() {
+        assert_eq!(error_line(read_dot_labels("digraph {\n a -> b;\n a -> ;\n}")), 3);
+        assert_eq!(error_line(read_dot_labels("\n\ngraf {}")), 3);
+        assert_eq!(error_line(read_dot_labels("digraph {\n a [label=\"open\n}")), 2);
+        let rejected = read_dot("digraph {\n\n x;\n}".as_bytes(), |_, _| Err::<(), _>("no".into()), |_| Ok(()));
+        assert_eq!(error_line(rejected), 3);
+    }
+
+    #[test]
+    fn graphml_round_trip() {
+        let names = ["a<b", "c&d", "q\"uote'", ">", ""];
+        let g = labelled(&names, &[(0, 1, "x > y"), (1, 2, "&amp;"), (2, 2, "<tag/>"), (4, 3, "")]);
+        let mut out = Vec::new();
+        write_graphml(&g, &mut out, |v, _| vec![("name".into(), names[v.0].into())], |s| vec![("w".into(), s.clone())])
+            .unwrap();
+        let back = read_graphml(
+            &out[..],
+            |_, a| Ok(attribute(a, "name").unwrap_or_default().to_string()),
+            |a| Ok(attribute(a, "w").unwrap_or_default().to_string()),
+        )
+        .unwrap();
+        let nodes: Vec<&str> = back.node_ids().map(|v| back.node(v).unwrap().data.as_str()).collect();
+        assert_eq!(nodes, names);
+        assert_eq!(edge_list(&back), edge_list(&g));
+    }
+
+    #[test]
+    fn graphml_attribute_values_may_contain_angle_brackets() {
+        let src = r#"<?xml version="1.0"?>
+<!-- a comment -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color"><default>red</default></key>
+  <key id="d1" for="edge" attr.name="rel"/>
+  <graph edgedefault="undirected">
+    <node id="x>y"/>
+    <node id='a"b>c'><data key="d0"><![CDATA[<blue>]]></data></node>
+    <edge source="x>y" target='a"b>c'><data key="d1">a &lt; b</data></edge>
+  </graph>
+</graphml>"#;
+        let g = read_graphml(
+            src.as_bytes(),
+            |id, a| Ok(format!("{}:{}", id, attribute(a, "color").unwrap())),
+            |a| Ok(attribute(a, "rel").unwrap().to_string()),
+        )
+        .unwrap();
+        let nodes: Vec<&str> = g.node_ids().map(|v| g.node(v).unwrap().data.as_str()).collect();
+        assert_eq!(nodes, ["x>y:red", "a\"b>c:<blue>"]);
+        assert_eq!(edge_list(&g), [(0, 1, "a < b".to_string())]);
+    }
+
+    #[test]
+    fn graphml_errors_report_their_line() {
+        let read = |src: &str| read_graphml(src.as_bytes(), |_, _| Ok(()), |_| Ok(()));
+        let mut src = String::from("<graphml>\n<graph>\n");
+        for i in 0..500 {
+            src.push_str(&format!("<node id=\"{}\"/>\n", i));
+        }
+        assert!(read(&format!("{}</graph>\n</graphml>", src)).is_ok());
+        assert_eq!(error_line(read(&format!("{}<edge source=\"0\" target=\"nope\"/>\n</graph></graphml>", src))), 503);
+        assert_eq!(error_line(read("<graphml>\n<graph>\n<node id=\"a\" oops/>\n</graph></graphml>")), 3);
+        assert_eq!(error_line(read("<graphml>\n<graph>\n\n<node id=\"a>")), 4);
+    }
+
+    #[test]
+    fn edge_list_round_trips() {
+        let names = ["a", "b c", "d,e", "\"q\"", "f"];
+        let g = labelled(&names, &[(0, 1, "1"), (1, 2, "x y"), (2, 3, ""), (3, 0, "a,b")]);
+        for format in [EdgeListFormat::default(), EdgeListFormat::csv()] {
+            let mut out = Vec::new();
+            if format.header {
+                writeln!(out, "source,target,label").unwrap();
+            }
+            write_edge_list(&g, &mut out, format, |v, _| names[v.0].to_string(), |s| vec![s.clone()]).unwrap();
+            let back = read_edge_list(&out[..], format, |s| Ok(s.to_string()), |f| Ok(f[0].clone())).unwrap();
+            let nodes: Vec<&str> = back.node_ids().map(|v| back.node(v).unwrap().data.as_str()).collect();
+            // the isolated node is not written
+            assert_eq!(nodes, ["a", "b c", "d,e", "\"q\""]);
+            assert_eq!(edge_list(&back), edge_list(&g));
+        }
+    }
+
+    #[test]
+    fn edge_list_skips_comments_and_reports_bad_lines() {
+        let read = |src: &str| {
+            read_edge_list(src.as_bytes(), EdgeListFormat::default(), |s| Ok(s.to_string()), |f| Ok(f.len()))
+        };
+        let g = read("# comment\n% also\n\n1 2\n2\t3 w extra\n").unwrap();
+        assert_eq!(edge_list(&g), [(0, 1, 0), (1, 2, 2)]);
+        assert_eq!(error_line(read("1 2\n\nlonely\n")), 3);
+        assert_eq!(error_line(read("1 2\n\"open 3\n")), 2);
+    }
+
+    #[test]
+    fn matrix_market_round_trip() {
+        let mut g = graph_from_edges(4, &[(0, 1, 2.5), (3, 3, -1.0), (0, 1, 4.0)]);
+        g.remove_node(NodeId(2)).unwrap();
+        for (field, expected) in [
+            (MatrixField::Real, [2.5, -1.0, 4.0]),
+            (MatrixField::Integer, [2.0, -1.0, 4.0]),
+            (MatrixField::Pattern, [1.0, 1.0, 1.0]),
+        ] {
+            let mut out = Vec::new();
+            write_matrix_market(&g, &mut out, field, |&w| w).unwrap();
+            let back = read_matrix_market(&out[..], |i| i, |w| w).unwrap();
+            assert_eq!(back.node_count(), 3);
+            assert_eq!(edge_list(&back), [(0, 1, expected[0]), (2, 2, expected[1]), (0, 1, expected[2])]);
+        }
+    }
+
+    #[test]
+    fn matrix_market_symmetric_and_dense_storage() {
+        let read = |src: &str| read_matrix_market(src.as_bytes(), |i| i, |w| w);
+        let sym = read("%%MatrixMarket matrix coordinate real symmetric\n% c\n3 3 2\n2 1 5\n3 3 1\n").unwrap();
+        assert_eq!(edge_list(&sym), [(1, 0, 5.0), (0, 1, 5.0), (2, 2, 1.0)]);
+        let skew = read("%%MatrixMarket matrix array integer skew-symmetric\n2 2\n7\n").unwrap();
+        assert_eq!(edge_list(&skew), [(1, 0, 7.0), (0, 1, -7.0)]);
+        let dense = read("%%MatrixMarket matrix array real general\n2 2\n0\n1.5\n0\n2\n").unwrap();
+        assert_eq!(edge_list(&dense), [(1, 0, 1.5), (1, 1, 2.0)]);
+        assert_eq!(error_line(read("%%MatrixMarket matrix coordinate real general\n2 3 0\n")), 2);
+        assert_eq!(error_line(read("%%MatrixMarket matrix coordinate complex general\n")), 1);
+        assert_eq!(error_line(read("%%MatrixMarket matrix coordinate real general\n2 2 1\n\n1 x 3\n")), 4);
+    }
+}
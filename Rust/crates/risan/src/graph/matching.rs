@@ -0,0 +1,429 @@
+//! Bipartite matching: maximum cardinality (Hopcroft–Karp) and minimum-weight assignment
+//! (Hungarian algorithm). Edges are treated as undirected.
+
+use std::collections::VecDeque;
+
+use super::connectivity::bipartition;
+use super::shortest_path::SignedWeight;
+use super::visit::{edges_once, EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use super::{EdgeId, NodeId};
+
+/// A set of edges no two of which share a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matching {
+    /// `mate[v]` is the node matched with `v`, indexed by `NodeId`.
+    pub mate: Vec<Option<NodeId>>,
+    pub edges: Vec<EdgeId>,
+}
+
+impl Matching {
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn mate(&self, v: NodeId) -> Option<NodeId> {
+        self.mate.get(v.0).copied().flatten()
+    }
+}
+
+/// Hopcroft–Karp maximum matching between `left` and the rest of the graph, `O(E sqrt V)`.
+/// Edges with both ends in `left`, or both outside it, are ignored.
+pub fn hopcroft_karp<G>(g: G, left: &[NodeId]) -> Matching
+where
+    G: IntoEdgeReferences + GraphProp + NodeIndexable,
+{
+    const UNREACHED: usize = usize::MAX;
+    let n = g.node_bound();
+    let mut is_left = vec![false; n];
+    for &u in left {
+        is_left[u.0] = true;
+    }
+    let mut adj: Vec<Vec<(NodeId, EdgeId)>> = vec![Vec::new(); n];
+    for e in edges_once(g) {
+        match (is_left[e.source().0], is_left[e.target().0]) {
+            (true, false) => adj[e.source().0].push((e.target(), e.id())),
+            (false, true) => adj[e.target().0].push((e.source(), e.id())),
+            _ => {}
+        }
+    }
+    let mut mate: Vec<Option<NodeId>> = vec![None; n];
+    let mut via: Vec<Option<EdgeId>> = vec![None; n];
+    let mut dist = vec![UNREACHED; n];
+    loop {
+        // layer the left nodes by alternating-path distance from the free ones
+        let mut queue = VecDeque::new();
+        for &u in left {
+            dist[u.0] = if mate[u.0].is_none() { queue.push_back(u); 0 } else { UNREACHED };
+        }
+        let mut found = false;
+        while let Some(x) = queue.pop_front() {
+            for &(v, _) in &adj[x.0] {
+                match mate[v.0] {
+                    None => found = true,
+                    Some(w) if dist[w.0] == UNREACHED => {
+                        dist[w.0] = dist[x.0] + 1;
+                        queue.push_back(w);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !found {
+            break;
+        }
+        // augment along layered paths with an iterative DFS; next[x] is the arc in use
+        let mut next = vec![0; n];
+        for &root in left {
+            if mate[root.0].is_some() {
+                continue;
+            }
+            let mut stack = vec![root];
+            while let Some(&x) = stack.last() {
+                let Some(&(v, _)) = adj[x.0].get(next[x.0]) else {
+                    dist[x.0] = UNREACHED;
+                    stack.pop();
+                    if let Some(&p) = stack.last() {
+                        next[p.0] += 1;
+                    }
+                    continue;
+                };
+                match mate[v.0] {
+                    None => {
+                        for &x in &stack {
+                            let (v, id) = adj[x.0][next[x.0]];
+                            mate[x.0] = Some(v);
+                            mate[v.0] = Some(x);
+                            via[x.0] = Some(id);
+                        }
+                        break;
+                    }
+                    Some(w) if dist[w.0] == dist[x.0] + 1 => stack.push(w),
+                    _ => next[x.0] += 1,
+                }
+            }
+        }
+    }
+    let mut edges: Vec<EdgeId> = left.iter().filter_map(|u| via[u.0]).collect();
+    edges.sort();
+    Matching { mate, edges }
+}
+
+/// Maximum matching of a bipartite graph, or `None` if the graph is not bipartite.
+pub fn maximum_bipartite_matching<G>(g: G) -> Option<Matching>
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+{
+    let side = bipartition(g)?;
+    let left: Vec<NodeId> = g.node_identifiers().filter(|v| !side[v.0]).collect();
+    Some(hopcroft_karp(g, &left))
+}
+
+/// An optimal assignment of rows to columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment<W> {
+    pub cost: W,
+    /// Column assigned to each row; `None` only when there are more rows than columns.
+    pub row_to_col: Vec<Option<usize>>,
+}
+
+/// Hungarian algorithm for a rectangular cost matrix, `O(n^2 m)`: assigns every row
+/// (or every column, if there are fewer) to a distinct column (row) at minimum total cost.
+/// The cost type must be signed since the dual potentials go negative.
+pub fn hungarian<W: SignedWeight>(costs: &[Vec<W>]) -> Assignment<W> {
+    let costs: Vec<Vec<Option<W>>> = costs.iter().map(|row| row.iter().map(|&c| Some(c)).collect()).collect();
+    hungarian_partial(&costs).expect("a complete matrix always has an assignment")
+}
+
+/// The Hungarian algorithm where `None` marks a forbidden pairing. Returns `None` if no
+/// assignment covers the smaller side.
+pub fn hungarian_partial<W: SignedWeight>(costs: &[Vec<Option<W>>]) -> Option<Assignment<W>> {
+    let rows = costs.len();
+    let cols = costs.first().map_or(0, Vec::len);
+    if rows > cols {
+        let transposed: Vec<Vec<Option<W>>> = (0..cols).map(|j| (0..rows).map(|i| costs[i][j]).collect()).collect();
+        let t = hungarian_partial(&transposed)?;
+        let mut row_to_col = vec![None; rows];
+        for (j, i) in t.row_to_col.iter().enumerate() {
+            row_to_col[i.unwrap()] = Some(j);
+        }
+        return Some(Assignment { cost: t.cost, row_to_col });
+    }
+    // potentials u (rows) and v (columns), 1-based with column 0 as a sentinel;
+    // owner[j] is the row assigned to column j, 0 for none
+    let (n, m) = (rows, cols);
+    let mut u = vec![W::zero(); n + 1];
+    let mut v = vec![W::zero(); m + 1];
+    let mut owner = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+    for i in 1..=n {
+        owner[0] = i;
+        let mut j0 = 0;
+        let mut min_slack: Vec<Option<W>> = vec![None; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = owner[j0];
+            let mut delta: Option<W> = None;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                if let Some(c) = costs[i0 - 1][j - 1] {
+                    let slack = c - u[i0] - v[j];
+                    if min_slack[j].is_none_or(|s| slack < s) {
+                        min_slack[j] = Some(slack);
+                        way[j] = j0;
+                    }
+                }
+                if let Some(s) = min_slack[j] {
+                    if delta.is_none_or(|d| s < d) {
+                        delta = Some(s);
+                        j1 = j;
+                    }
+                }
+            }
+            let delta = delta?;
+            for j in 0..=m {
+                if used[j] {
+                    u[owner[j]] = u[owner[j]] + delta;
+                    v[j] = v[j] - delta;
+                } else if let Some(s) = min_slack[j] {
+                    min_slack[j] = Some(s - delta);
+                }
+            }
+            j0 = j1;
+            if owner[j0] == 0 {
+                break;
+            }
+        }
+        while j0 != 0 {
+            let j1 = way[j0];
+            owner[j0] = owner[j1];
+            j0 = j1;
+        }
+    }
+    let mut row_to_col = vec![None; n];
+    let mut cost = W::zero();
+    for j in 1..=m {
+        if owner[j] != 0 {
+            row_to_col[owner[j] - 1] = Some(j - 1);
+            cost = cost + costs[owner[j] - 1][j - 1].unwrap();
+        }
+    }
+    Some(Assignment { cost, row_to_col })
+}
+
+/// Minimum-weight matching covering the smaller of `left` and `right`, using the edges
+/// between them (the cheapest of any parallel edges). Returns the total weight and the
+/// chosen edges, or `None` if no such matching exists.
+pub fn min_weight_assignment<G, W>(
+    g: G,
+    left: &[NodeId],
+    right: &[NodeId],
+    weight: impl Fn(&G::EdgeWeight) -> W,
+) -> Option<(W, Vec<EdgeId>)>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    W: SignedWeight,
+{
+    let mut row = vec![None; g.node_bound()];
+    let mut col = vec![None; g.node_bound()];
+    for (i, u) in left.iter().enumerate() {
+        row[u.0] = Some(i);
+    }
+    for (j, v) in right.iter().enumerate() {
+        col[v.0] = Some(j);
+    }
+    let mut best: Vec<Vec<Option<(W, EdgeId)>>> = vec![vec![None; right.len()]; left.len()];
+    for e in g.edge_references() {
+        let (u, v) = (e.source().0, e.target().0);
+        let pair = match (row[u], col[v], row[v], col[u]) {
+            (Some(i), Some(j), ..) | (_, _, Some(i), Some(j)) => (i, j),
+            _ => continue,
+        };
+        let w = weight(e.weight());
+        let cell = &mut best[pair.0][pair.1];
+        if cell.is_none_or(|(b, _)| w < b) {
+            *cell = Some((w, e.id()));
+        }
+    }
+    let costs: Vec<Vec<Option<W>>> = best.iter().map(|r| r.iter().map(|c| c.map(|(w, _)| w)).collect()).collect();
+    let assignment = hungarian_partial(&costs)?;
+    let mut edges: Vec<EdgeId> =
+        assignment.row_to_col.iter().enumerate().filter_map(|(i, j)| Some(best[i][(*j)?]?.1)).collect();
+    edges.sort();
+    Some((assignment.cost, edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::flow::dinic;
+    use crate::graph::{graph_from_edges, CsrGraph, Reversed, Undirected};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Cheapest assignment of every row of a matrix with `rows <= cols`, by trying all.
+    fn brute_force(costs: &[Vec<Option<i64>>], row: usize, used: &mut Vec<bool>) -> Option<i64> {
+        if row == costs.len() {
+            return Some(0);
+        }
+        (0..used.len())
+            .filter_map(|j| {
+                let c = costs[row][j]?;
+                if used[j] {
+                    return None;
+                }
+                used[j] = true;
+                let rest = brute_force(costs, row + 1, used);
+                used[j] = false;
+                Some(c + rest?)
+            })
+            .min()
+    }
+
+    fn check_assignment(costs: &[Vec<Option<i64>>], a: &Assignment<i64>) {
+        let mut used = vec![false; costs[0].len()];
+        let mut total = 0;
+        for (i, j) in a.row_to_col.iter().enumerate() {
+            if let Some(j) = *j {
+                assert!(!std::mem::replace(&mut used[j], true));
+                total += costs[i][j].unwrap();
+            }
+        }
+        assert_eq!(total, a.cost);
+        assert_eq!(a.row_to_col.iter().flatten().count(), costs.len().min(costs[0].len()));
+    }
+
+    #[test]
+    fn hungarian_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for (rows, cols) in [(1, 1), (3, 3), (4, 6), (6, 4), (6, 6)] {
+            for _ in 0..20 {
+                let costs: Vec<Vec<i64>> =
+                    (0..rows).map(|_| (0..cols).map(|_| rng.gen_range(-20..50)).collect()).collect();
+                let a = hungarian(&costs);
+                let partial: Vec<Vec<Option<i64>>> =
+                    costs.iter().map(|r| r.iter().map(|&c| Some(c)).collect()).collect();
+                check_assignment(&partial, &a);
+                let transposed: Vec<Vec<Option<i64>>> =
+                    (0..cols).map(|j| (0..rows).map(|i| partial[i][j]).collect()).collect();
+                let smaller = if rows <= cols { &partial } else { &transposed };
+                assert_eq!(Some(a.cost), brute_force(smaller, 0, &mut vec![false; rows.max(cols)]));
+            }
+        }
+    }
+
+    #[test]
+    fn forbidden_pairs() {
+        let mut rng = StdRng::seed_from_u64(46);
+        for _ in 0..100 {
+            let costs: Vec<Vec<Option<i64>>> =
+                (0..5).map(|_| (0..5).map(|_| rng.gen_bool(0.6).then(|| rng.gen_range(0..20))).collect()).collect();
+            let expected = brute_force(&costs, 0, &mut vec![false; 5]);
+            let found = hungarian_partial(&costs);
+            assert_eq!(found.as_ref().map(|a| a.cost), expected);
+            if let Some(a) = found {
+                check_assignment(&costs, &a);
+            }
+        }
+        assert_eq!(hungarian::<f64>(&[]).cost, 0.0);
+    }
+
+    #[test]
+    fn hopcroft_karp_matches_max_flow() {
+        let mut rng = StdRng::seed_from_u64(47);
+        for _ in 0..50 {
+            // left 0..6, right 6..13, plus a source 13 and sink 14 for the flow check
+            let edges: Vec<(usize, usize, u32)> = (0..14)
+                .map(|_| {
+                    let (l, r) = (rng.gen_range(0..6), rng.gen_range(6..13));
+                    if rng.gen_bool(0.5) { (l, r, 1) } else { (r, l, 1) }
+                })
+                .collect();
+            let g = graph_from_edges(15, &edges);
+            let v: Vec<NodeId> = g.node_ids().collect();
+            let pairs: Vec<EdgeId> = g.edge_ids().collect();
+            let m = hopcroft_karp(&g, &v[..6]);
+            for &e in &m.edges {
+                let (a, b) = g.edge_endpoints(e).unwrap();
+                assert_eq!((m.mate(a), m.mate(b)), (Some(b), Some(a)));
+            }
+            let matched: Vec<NodeId> = g.node_ids().filter(|&x| m.mate(x).is_some()).collect();
+            assert_eq!(matched.len(), 2 * m.len());
+
+            let mut h = g.clone();
+            for &e in &pairs {
+                let (a, b) = h.edge_endpoints(e).unwrap();
+                let (l, r) = if a.0 < 6 { (a, b) } else { (b, a) };
+                h.remove_edge(e).unwrap();
+                h.add_edge(l, r, 1).unwrap();
+            }
+            for i in 0..6 {
+                h.add_edge(v[13], v[i], 1).unwrap();
+            }
+            for i in 6..13 {
+                h.add_edge(v[i], v[14], 1).unwrap();
+            }
+            assert_eq!(dinic(&h, v[13], v[14], |&c| c).value as usize, m.len());
+        }
+    }
+
+    #[test]
+    fn matchings_on_csr_graphs_and_views() {
+        let mut rng = StdRng::seed_from_u64(480);
+        for _ in 0..30 {
+            let edges: Vec<(usize, usize, i64)> =
+                (0..12).map(|_| (rng.gen_range(0..5), rng.gen_range(5..10), rng.gen_range(-5..10))).collect();
+            let g = graph_from_edges(10, &edges);
+            let csr = CsrGraph::from_graph(&g);
+            let left: Vec<NodeId> = (0..5).map(NodeId).collect();
+            let right: Vec<NodeId> = (5..10).map(NodeId).collect();
+            let expected = hopcroft_karp(&g, &left);
+            let matchings = [
+                hopcroft_karp(&csr, &left),
+                hopcroft_karp(Undirected(&g), &left),
+                hopcroft_karp(Reversed(&g), &left),
+            ];
+            for m in matchings {
+                assert_eq!(m.len(), expected.len());
+            }
+            // the view lists each edge once, so the matched ids are the graph's own
+            let m = hopcroft_karp(Undirected(&g), &left);
+            for &e in &m.edges {
+                let (a, b) = g.edge_endpoints(e).unwrap();
+                assert_eq!(m.mate(a), Some(b));
+            }
+            assert_eq!(maximum_bipartite_matching(&csr).unwrap().len(), maximum_bipartite_matching(&g).unwrap().len());
+            let cost = |h| min_weight_assignment(h, &left, &right, |&w| w).map(|(c, _)| c);
+            assert_eq!(min_weight_assignment(&csr, &left, &right, |&w| w).map(|(c, _)| c), cost(&g));
+            assert_eq!(min_weight_assignment(Undirected(&g), &left, &right, |&w| w).map(|(c, _)| c), cost(&g));
+        }
+    }
+
+    #[test]
+    fn bipartite_matching_needs_a_bipartite_graph() {
+        let mut g = graph_from_edges(4, &[(0, 1, ()), (1, 2, ()), (2, 3, ())]);
+        let m = maximum_bipartite_matching(&g).unwrap();
+        assert_eq!(m.len(), 2);
+        assert!(!m.is_empty());
+        g.add_edge(NodeId(2), NodeId(0), ()).unwrap();
+        assert_eq!(maximum_bipartite_matching(&g), None);
+    }
+
+    #[test]
+    fn min_weight_assignment_uses_the_cheapest_parallel_edge() {
+        let g = graph_from_edges(4, &[(0, 2, 5), (2, 0, -1), (0, 3, 1), (1, 2, 2), (0, 1, -100)]);
+        let v: Vec<NodeId> = g.node_ids().collect();
+        let (left, right) = ([v[0], v[1]], [v[2], v[3]]);
+        // covering both rows forces 0-3 and 1-2
+        assert_eq!(min_weight_assignment(&g, &left, &right, |&w| w), Some((3, vec![EdgeId(2), EdgeId(3)])));
+        assert_eq!(min_weight_assignment(&g, &left, &right[..1], |&w| w), Some((-1, vec![EdgeId(1)])));
+        assert_eq!(min_weight_assignment(&g, &[v[1]], &[v[3]], |&w| w), None);
+    }
+}
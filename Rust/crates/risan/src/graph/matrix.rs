@@ -0,0 +1,359 @@
+//! Matrix views of graphs: adjacency, degree, Laplacian, normalized Laplacian and
+//! random-walk transition matrices in compressed sparse row form, plus a dense symmetric
+//! eigensolver for the spectral algorithms.
+//!
+//! Row and column `i` belong to the `i`-th node of `node_identifiers()`, which for a
+//! `Graph` without removed nodes is `NodeId(i)`. Edges are read with `edges(v)` from every
+//! node, so a directed graph gives a directed adjacency matrix (`a[i][j]` sums the weights
+//! of edges `i -> j`) and an `Undirected` view gives a symmetric one.
+
+use super::visit::{EdgeRef, IntoEdges, IntoNodeIdentifiers, NodeIndexable};
+use super::NodeId;
+
+/// A real matrix in compressed sparse row form. Entries of row `i` are
+/// `offsets[i]..offsets[i + 1]`, sorted by column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    rows: usize,
+    cols: usize,
+    offsets: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SparseMatrix {
+    /// Build from `(row, column, value)` triplets; duplicates are summed. Panics if an
+    /// index is out of range.
+    pub fn from_triplets(rows: usize, cols: usize, triplets: impl IntoIterator<Item = (usize, usize, f64)>) -> Self {
+        let mut entries: Vec<(usize, usize, f64)> = triplets.into_iter().collect();
+        assert!(entries.iter().all(|&(i, j, _)| i < rows && j < cols), "sparse matrix index out of range");
+        entries.sort_by_key(|&(i, j, _)| (i, j));
+        let mut offsets = vec![0; rows + 1];
+        let mut indices: Vec<usize> = Vec::with_capacity(entries.len());
+        let mut values: Vec<f64> = Vec::with_capacity(entries.len());
+        let mut last = None;
+        for (i, j, x) in entries {
+            if last == Some((i, j)) {
+                *values.last_mut().unwrap() += x;
+                continue;
+            }
+            last = Some((i, j));
+            offsets[i + 1] += 1;
+            indices.push(j);
+            values.push(x);
+        }
+        for i in 0..rows {
+            offsets[i + 1] += offsets[i];
+        }
+        Self { rows, cols, offsets, indices, values }
+    }
+
+    /// The `n x n` identity.
+    pub fn identity(n: usize) -> Self {
+        Self::from_triplets(n, n, (0..n).map(|i| (i, i, 1.0)))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Entry `(i, j)`, zero if not stored.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        self.row(i).find(|&(k, _)| k == j).map_or(0.0, |(_, x)| x)
+    }
+
+    /// Stored entries of row `i` as `(column, value)`.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.offsets[i]..self.offsets[i + 1];
+        self.indices[range.clone()].iter().copied().zip(self.values[range].iter().copied())
+    }
+
+    /// Every stored entry as `(row, column, value)`, row by row.
+    pub fn triplets(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.rows).flat_map(move |i| self.row(i).map(move |(j, x)| (i, j, x)))
+    }
+
+    /// `A x`.
+    pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(x.len(), self.cols, "vector length must match the column count");
+        (0..self.rows).map(|i| self.row(i).map(|(j, a)| a * x[j]).sum()).collect()
+    }
+
+    /// `A^T x`, without forming the transpose.
+    pub fn transpose_mul_vec(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(x.len(), self.rows, "vector length must match the row count");
+        let mut y = vec![0.0; self.cols];
+        for (i, j, a) in self.triplets() {
+            y[j] += a * x[i];
+        }
+        y
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_triplets(self.cols, self.rows, self.triplets().map(|(i, j, x)| (j, i, x)))
+    }
+
+    /// Row-major dense copy.
+    pub fn to_dense(&self) -> Vec<Vec<f64>> {
+        let mut dense = vec![vec![0.0; self.cols]; self.rows];
+        for (i, j, x) in self.triplets() {
+            dense[i][j] += x;
+        }
+        dense
+    }
+}
+
+/// The nodes in matrix order, and for each node id its row (`usize::MAX` if absent).
+pub(crate) fn matrix_index<G: IntoNodeIdentifiers + NodeIndexable>(g: G) -> (Vec<NodeId>, Vec<usize>) {
+    let order: Vec<NodeId> = g.node_identifiers().collect();
+    let mut row = vec![usize::MAX; g.node_bound()];
+    for (i, v) in order.iter().enumerate() {
+        row[v.0] = i;
+    }
+    (order, row)
+}
+
+/// Spread a vector in matrix order over node ids; absent nodes get `fill`.
+pub(crate) fn by_node_id(order: &[NodeId], bound: usize, values: &[f64], fill: f64) -> Vec<f64> {
+    let mut out = vec![fill; bound];
+    for (v, &x) in order.iter().zip(values) {
+        out[v.0] = x;
+    }
+    out
+}
+
+/// Weighted adjacency matrix: entry `(i, j)` sums the weights of edges from node `i` to
+/// node `j`.
+pub fn adjacency_matrix<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> SparseMatrix
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, row) = matrix_index(g);
+    let edges = order.iter().flat_map(|&v| g.edges(v));
+    let triplets = edges.map(|e| (row[e.source().0], row[e.target().0], weight(e.weight())));
+    SparseMatrix::from_triplets(order.len(), order.len(), triplets)
+}
+
+/// Weighted out-degree of each node in matrix order: the row sums of the adjacency matrix.
+pub(crate) fn degrees(a: &SparseMatrix) -> Vec<f64> {
+    (0..a.rows()).map(|i| a.row(i).map(|(_, x)| x).sum()).collect()
+}
+
+/// Diagonal matrix of weighted out-degrees.
+pub fn degree_matrix<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> SparseMatrix
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let d = degrees(&adjacency_matrix(g, weight));
+    SparseMatrix::from_triplets(d.len(), d.len(), d.iter().enumerate().map(|(i, &x)| (i, i, x)))
+}
+
+/// Laplacian `L = D - A`. Symmetric and positive semidefinite for undirected graphs with
+/// non-negative weights; the multiplicity of eigenvalue 0 is the number of components.
+pub fn laplacian_matrix<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> SparseMatrix
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let a = adjacency_matrix(g, weight);
+    let d = degrees(&a);
+    let diagonal = d.iter().enumerate().map(|(i, &x)| (i, i, x));
+    SparseMatrix::from_triplets(a.rows(), a.cols(), diagonal.chain(a.triplets().map(|(i, j, x)| (i, j, -x))))
+}
+
+/// Symmetric normalized Laplacian `I - D^-1/2 A D^-1/2`, with eigenvalues in `[0, 2]` for
+/// undirected graphs. Rows and columns of nodes with zero degree are zero.
+pub fn normalized_laplacian_matrix<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> SparseMatrix
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let a = adjacency_matrix(g, weight);
+    let scale: Vec<f64> = degrees(&a).iter().map(|&d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 }).collect();
+    let diagonal = (0..a.rows()).filter(|&i| scale[i] > 0.0).map(|i| (i, i, 1.0));
+    let off = a.triplets().map(|(i, j, x)| (i, j, -x * scale[i] * scale[j]));
+    SparseMatrix::from_triplets(a.rows(), a.cols(), diagonal.chain(off))
+}
+
+/// Random-walk transition matrix `D^-1 A`: entry `(i, j)` is the probability of stepping
+/// from node `i` to node `j` along an edge chosen in proportion to its weight. Rows of
+/// nodes without outgoing weight are zero.
+pub fn transition_matrix<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> SparseMatrix
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let a = adjacency_matrix(g, weight);
+    let d = degrees(&a);
+    let entries = a.triplets().filter(|&(i, _, _)| d[i] != 0.0).map(|(i, j, x)| (i, j, x / d[i]));
+    SparseMatrix::from_triplets(a.rows(), a.cols(), entries)
+}
+
+/// Eigenvalues and eigenvectors of a symmetric matrix by cyclic Jacobi rotations. Returns
+/// the eigenvalues in increasing order and the matching unit eigenvectors (`vectors[k]`
+/// belongs to `values[k]`). `O(n^3)` per sweep; only the lower triangle need be exact.
+pub fn symmetric_eigen(a: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut m: Vec<Vec<f64>> =
+        (0..n).map(|i| (0..n).map(|j| if j <= i { a[i][j] } else { a[j][i] }).collect()).collect();
+    // columns of v accumulate the rotations
+    let mut v: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    let scale: f64 = m.iter().flatten().map(|x| x * x).sum::<f64>().sqrt();
+    for _sweep in 0..100 {
+        let off: f64 = (0..n).flat_map(|i| (0..i).map(move |j| (i, j))).map(|(i, j)| m[i][j] * m[i][j]).sum();
+        if off.sqrt() <= 1e-14 * scale || off == 0.0 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if m[p][q] == 0.0 {
+                    continue;
+                }
+                let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                // m <- J^T m J, then v <- v J
+                for row in m.iter_mut().chain(v.iter_mut()) {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = c * x - s * y;
+                    row[q] = s * x + c * y;
+                }
+                let (upper, lower) = m.split_at_mut(q);
+                for (x, y) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    (*x, *y) = (c * *x - s * *y, s * *x + c * *y);
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| m[i][i].total_cmp(&m[j][j]));
+    let values = order.iter().map(|&k| m[k][k]).collect();
+    let vectors = order.iter().map(|&k| v.iter().map(|row| row[k]).collect()).collect();
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, Undirected};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn sparse_matrix_sums_duplicates_and_multiplies() {
+        let mut rng = StdRng::seed_from_u64(49);
+        let (rows, cols) = (5, 4);
+        let triplets: Vec<(usize, usize, f64)> =
+            (0..25).map(|_| (rng.gen_range(0..rows), rng.gen_range(0..cols), rng.gen_range(-3..4) as f64)).collect();
+        let m = SparseMatrix::from_triplets(rows, cols, triplets.iter().copied());
+        let mut dense = vec![vec![0.0; cols]; rows];
+        for &(i, j, x) in &triplets {
+            dense[i][j] += x;
+        }
+        assert_eq!(m.to_dense(), dense);
+        assert!(m.nnz() <= triplets.len());
+        assert!((0..rows).all(|i| (0..cols).all(|j| m.get(i, j) == dense[i][j])));
+        assert!((0..rows).all(|i| m.row(i).map(|(j, _)| j).collect::<Vec<_>>().is_sorted()));
+        let x: Vec<f64> = (0..cols).map(|j| j as f64 - 1.5).collect();
+        let y: Vec<f64> = (0..rows).map(|i| i as f64 * 0.5).collect();
+        let ax: Vec<f64> = dense.iter().map(|r| r.iter().zip(&x).map(|(a, b)| a * b).sum()).collect();
+        let aty: Vec<f64> = (0..cols).map(|j| (0..rows).map(|i| dense[i][j] * y[i]).sum()).collect();
+        assert_eq!(m.mul_vec(&x), ax);
+        assert_eq!(m.transpose_mul_vec(&y), aty);
+        assert_eq!(m.transpose().mul_vec(&y), aty);
+        assert_eq!(SparseMatrix::identity(3).mul_vec(&[1.0, 2.0, 3.0]), [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "sparse matrix index out of range")]
+    fn sparse_matrix_rejects_out_of_range_entries() {
+        SparseMatrix::from_triplets(2, 2, [(0, 2, 1.0)]);
+    }
+
+    #[test]
+    fn graph_matrices_skip_removed_nodes() {
+        let mut g = graph_from_edges(4, &[(0, 1, 2.0), (0, 1, 1.0), (1, 3, 4.0), (3, 3, 0.5), (2, 0, 9.0)]);
+        g.remove_node(NodeId(2)).unwrap();
+        let a = adjacency_matrix(&g, |&w| w);
+        assert_eq!(a.to_dense(), [[0.0, 3.0, 0.0], [0.0, 0.0, 4.0], [0.0, 0.0, 0.5]]);
+        assert_eq!(degree_matrix(&g, |&w| w).to_dense(), [[3.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 0.5]]);
+        let p = transition_matrix(&g, |&w| w);
+        assert_eq!(p.to_dense(), [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+        let empty = graph_from_edges(2, &[(0, 1, 1.0)]);
+        assert_eq!(transition_matrix(&empty, |&w| w).to_dense(), [[0.0, 1.0], [0.0, 0.0]]);
+    }
+
+    #[test]
+    fn laplacians_of_undirected_graphs() {
+        let mut rng = StdRng::seed_from_u64(490);
+        for _ in 0..20 {
+            let edges: Vec<(usize, usize, f64)> =
+                (0..10).map(|_| (rng.gen_range(0..7), rng.gen_range(0..7), rng.gen_range(1..5) as f64)).collect();
+            let g = graph_from_edges(8, &edges);
+            let u = Undirected(&g);
+            let a = adjacency_matrix(u, |&w| w).to_dense();
+            let l = laplacian_matrix(u, |&w| w).to_dense();
+            let nl = normalized_laplacian_matrix(u, |&w| w).to_dense();
+            let d: Vec<f64> = a.iter().map(|r| r.iter().sum()).collect();
+            for i in 0..8 {
+                assert!(close(l[i].iter().sum::<f64>(), 0.0));
+                for j in 0..8 {
+                    assert_eq!(a[i][j], a[j][i]);
+                    assert_eq!(l[i][j], if i == j { d[i] - a[i][i] } else { -a[i][j] });
+                    let expected = if d[i] == 0.0 || d[j] == 0.0 {
+                        0.0
+                    } else {
+                        f64::from(u8::from(i == j)) - a[i][j] / (d[i] * d[j]).sqrt()
+                    };
+                    assert!(close(nl[i][j], expected));
+                }
+            }
+            // node 7 is isolated
+            assert!(nl[7].iter().all(|&x| x == 0.0));
+            let (values, _) = symmetric_eigen(&nl);
+            assert!(values.iter().all(|&x| x > -1e-9 && x < 2.0 + 1e-9));
+            let (values, _) = symmetric_eigen(&l);
+            assert!(values[0].abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn symmetric_eigen_decomposes() {
+        let mut rng = StdRng::seed_from_u64(4900);
+        for n in [0, 1, 2, 5, 9] {
+            let lower: Vec<Vec<f64>> = (0..n).map(|i| (0..=i).map(|_| rng.gen_range(-5.0..5.0)).collect()).collect();
+            let a: Vec<Vec<f64>> =
+                (0..n).map(|i| (0..n).map(|j| if j <= i { lower[i][j] } else { lower[j][i] }).collect()).collect();
+            let (values, vectors) = symmetric_eigen(&a);
+            assert!(values.is_sorted());
+            for (k, v) in vectors.iter().enumerate() {
+                for i in 0..n {
+                    let av: f64 = (0..n).map(|j| a[i][j] * v[j]).sum();
+                    assert!((av - values[k] * v[i]).abs() < 1e-8);
+                }
+                for (l, w) in vectors.iter().enumerate() {
+                    let dot: f64 = v.iter().zip(w).map(|(x, y)| x * y).sum();
+                    assert!(close(dot, if k == l { 1.0 } else { 0.0 }));
+                }
+            }
+        }
+        // the path on n nodes has Laplacian eigenvalues 2 - 2 cos(k pi / n)
+        let n = 6;
+        let path = graph_from_edges(n, &(0..n - 1).map(|i| (i, i + 1, 1.0)).collect::<Vec<_>>());
+        let (values, _) = symmetric_eigen(&laplacian_matrix(Undirected(&path), |&w| w).to_dense());
+        for (k, x) in values.iter().enumerate() {
+            assert!(close(*x, 2.0 - 2.0 * (k as f64 * std::f64::consts::PI / n as f64).cos()));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Minimum spanning forests (Kruskal, Prim), treating edges as undirected.
+
+use std::collections::BinaryHeap;
+
+use super::shortest_path::{Entry, Weight};
+use super::union_find::UnionFind;
+use super::visit::{edges_once, EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use super::EdgeId;
+
+/// A minimum spanning tree of each connected component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningForest<W> {
+    pub edges: Vec<EdgeId>,
+    pub total_weight: W,
+}
+
+/// Kruskal's algorithm: edges by increasing weight, skipping those that close a cycle.
+/// `O(E log E)`.
+pub fn kruskal<G, W>(g: G, weight: impl Fn(&G::EdgeWeight) -> W) -> SpanningForest<W>
+where
+    G: IntoEdgeReferences + GraphProp + NodeIndexable,
+    W: Weight,
+{
+    let mut candidates: Vec<(W, G::EdgeRef)> = edges_once(g).map(|e| (weight(e.weight()), e)).collect();
+    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut uf = UnionFind::new(g.node_bound());
+    let mut forest = SpanningForest { edges: Vec::new(), total_weight: W::zero() };
+    for (w, e) in candidates {
+        if uf.union(e.source().0, e.target().0) {
+            forest.edges.push(e.id());
+            forest.total_weight = forest.total_weight + w;
+        }
+    }
+    forest
+}
+
+/// Prim's algorithm grown from each unvisited node in turn, with a binary heap.
+/// `O(E log V)`.
+pub fn prim<G, W>(g: G, weight: impl Fn(&G::EdgeWeight) -> W) -> SpanningForest<W>
+where
+    G: IntoEdgeReferences + GraphProp + IntoNodeIdentifiers + NodeIndexable,
+    W: Weight,
+{
+    let n = g.node_bound();
+    // incident edges in either direction, with the opposite endpoint and the weight
+    let mut adj = vec![Vec::new(); n];
+    for e in edges_once(g) {
+        let w = weight(e.weight());
+        adj[e.source().0].push((e.id(), e.target(), w));
+        adj[e.target().0].push((e.id(), e.source(), w));
+    }
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<Option<(W, EdgeId)>> = vec![None; n];
+    let mut forest = SpanningForest { edges: Vec::new(), total_weight: W::zero() };
+    for root in g.node_identifiers() {
+        if in_tree[root.0] {
+            continue;
+        }
+        let mut heap = BinaryHeap::from([Entry { cost: W::zero(), node: root }]);
+        while let Some(Entry { node, .. }) = heap.pop() {
+            if std::mem::replace(&mut in_tree[node.0], true) {
+                continue;
+            }
+            if let Some((w, id)) = best[node.0] {
+                forest.edges.push(id);
+                forest.total_weight = forest.total_weight + w;
+            }
+            for &(id, other, w) in &adj[node.0] {
+                if !in_tree[other.0] && best[other.0].is_none_or(|(b, _)| w < b) {
+                    best[other.0] = Some((w, id));
+                    heap.push(Entry { cost: w, node: other });
+                }
+            }
+        }
+    }
+    forest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, weakly_connected_components, Graph};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_graph(rng: &mut StdRng, n: usize, m: usize) -> Graph<(), i64> {
+        let edges: Vec<(usize, usize, i64)> =
+            (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-5..20))).collect();
+        graph_from_edges(n, &edges)
+    }
+
+    /// Lightest spanning forest by trying every edge subset of the right size.
+    fn brute_force(g: &Graph<(), i64>) -> i64 {
+        let edges: Vec<(usize, usize, i64)> = g.edges().map(|(_, e)| (e.from.0, e.to.0, e.data)).collect();
+        let size = g.node_count() - weakly_connected_components(g).len();
+        (0u32..1 << edges.len())
+            .filter(|mask| mask.count_ones() as usize == size)
+            .filter_map(|mask| {
+                let mut uf = UnionFind::new(g.node_bound());
+                let chosen = edges.iter().enumerate().filter(|(i, _)| mask >> i & 1 == 1);
+                let mut total = 0;
+                for (_, &(u, v, w)) in chosen {
+                    if !uf.union(u, v) {
+                        return None;
+                    }
+                    total += w;
+                }
+                Some(total)
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn kruskal_and_prim_find_minimum_forests() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for _ in 0..40 {
+            let g = random_graph(&mut rng, 7, 11);
+            let (k, p) = (kruskal(&g, |&w| w), prim(&g, |&w| w));
+            let expected = brute_force(&g);
+            assert_eq!(k.total_weight, expected);
+            assert_eq!(p.total_weight, expected);
+            let size = g.node_count() - weakly_connected_components(&g).len();
+            assert_eq!((k.edges.len(), p.edges.len()), (size, size));
+            for forest in [&k, &p] {
+                let mut uf = UnionFind::new(g.node_bound());
+                assert!(forest.edges.iter().all(|&e| {
+                    let (u, v) = g.edge_endpoints(e).unwrap();
+                    uf.union(u.0, v.0)
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn forest_of_a_disconnected_graph() {
+        let g = graph_from_edges(5, &[(0, 1, 1.5), (1, 0, 0.5), (2, 2, 0.1), (3, 4, 2.0)]);
+        for forest in [kruskal(&g, |&w| w), prim(&g, |&w| w)] {
+            assert_eq!(forest.total_weight, 2.5);
+            let mut edges = forest.edges;
+            edges.sort();
+            assert_eq!(edges, [EdgeId(1), EdgeId(3)]);
+        }
+    }
+}
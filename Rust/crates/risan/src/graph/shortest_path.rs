@@ -0,0 +1,472 @@
+//! Single-source and all-pairs shortest paths. Edge weights come from a closure over
+//! the edge data; results are indexed by `NodeId` and carry predecessor trees.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+use num_traits::Zero;
+
+use super::visit::{EdgeRef, IntoEdgeReferences, IntoEdges, IntoNodeIdentifiers, NodeIndexable};
+use super::NodeId;
+
+/// Edge weight usable by the shortest-path algorithms: integers or floats.
+pub trait Weight: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Zero + fmt::Debug {}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Zero + fmt::Debug> Weight for T {}
+
+/// A `Weight` that can go negative, for algorithms whose intermediate values (potentials,
+/// reverse arcs) do even when the inputs do not: signed integers or floats.
+pub trait SignedWeight: Weight + Neg<Output = Self> {}
+
+impl<T: Weight + Neg<Output = T>> SignedWeight for T {}
+
+/// A cycle of negative total weight, listed in edge order (the last node links back
+/// to the first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle {
+    pub cycle: Vec<NodeId>,
+}
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "negative cycle through {} nodes", self.cycle.len())
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// Walk a predecessor array back from `target`, returning the path from `source`.
+pub fn reconstruct_path(pred: &[Option<NodeId>], source: NodeId, target: NodeId) -> Option<Vec<NodeId>> {
+    let mut path = vec![target];
+    let mut cur = target;
+    while cur != source {
+        cur = pred[cur.0]?;
+        path.push(cur);
+        if path.len() > pred.len() {
+            return None;
+        }
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Distances from one source, with the shortest-path tree as predecessors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPaths<W> {
+    pub source: NodeId,
+    /// `None` for unreachable nodes.
+    pub dist: Vec<Option<W>>,
+    pub pred: Vec<Option<NodeId>>,
+}
+
+impl<W: Weight> ShortestPaths<W> {
+    fn new(source: NodeId, n: usize) -> Self {
+        let mut dist = vec![None; n];
+        dist[source.0] = Some(W::zero());
+        Self { source, dist, pred: vec![None; n] }
+    }
+
+    pub fn distance(&self, target: NodeId) -> Option<W> {
+        self.dist.get(target.0).copied().flatten()
+    }
+
+    /// Nodes on a shortest path from the source to `target`, both included.
+    pub fn path_to(&self, target: NodeId) -> Option<Vec<NodeId>> {
+        self.distance(target)?;
+        reconstruct_path(&self.pred, self.source, target)
+    }
+}
+
+/// Distances between every pair of nodes, with one predecessor array per source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllPairsPaths<W> {
+    /// `dist[u][v]`, `None` when `v` is unreachable from `u`.
+    pub dist: Vec<Vec<Option<W>>>,
+    /// `pred[u][v]` is the node before `v` on a shortest path from `u`.
+    pub pred: Vec<Vec<Option<NodeId>>>,
+}
+
+impl<W: Weight> AllPairsPaths<W> {
+    pub fn distance(&self, from: NodeId, to: NodeId) -> Option<W> {
+        self.dist.get(from.0)?.get(to.0).copied().flatten()
+    }
+
+    pub fn path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        self.distance(from, to)?;
+        reconstruct_path(&self.pred[from.0], from, to)
+    }
+}
+
+/// Min-heap entry ordered by cost; incomparable costs (NaN) compare equal.
+pub(crate) struct Entry<W> {
+    pub(crate) cost: W,
+    pub(crate) node: NodeId,
+}
+
+impl<W: PartialOrd> PartialEq for Entry<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: PartialOrd> Eq for Entry<W> {}
+
+impl<W: PartialOrd> PartialOrd for Entry<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: PartialOrd> Ord for Entry<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+fn less<W: PartialOrd>(a: W, b: Option<W>) -> bool {
+    b.is_none_or(|b| a < b)
+}
+
+fn dijkstra_by<G, W>(g: G, source: NodeId, weight: impl Fn(G::EdgeRef) -> W) -> ShortestPaths<W>
+where
+    G: IntoEdges + NodeIndexable,
+    W: Weight,
+{
+    let mut sp = ShortestPaths::new(source, g.node_bound());
+    let mut done = vec![false; g.node_bound()];
+    let mut heap = BinaryHeap::from([Entry { cost: W::zero(), node: source }]);
+    while let Some(Entry { cost, node }) = heap.pop() {
+        if std::mem::replace(&mut done[node.0], true) {
+            continue;
+        }
+        for e in g.edges(node) {
+            let (to, next) = (e.target(), cost + weight(e));
+            if !done[to.0] && less(next, sp.dist[to.0]) {
+                sp.dist[to.0] = Some(next);
+                sp.pred[to.0] = Some(node);
+                heap.push(Entry { cost: next, node: to });
+            }
+        }
+    }
+    sp
+}
+
+/// Dijkstra's algorithm with a binary heap, `O((V + E) log V)`. Weights must be non-negative.
+pub fn dijkstra<G, W>(g: G, source: NodeId, weight: impl Fn(&G::EdgeWeight) -> W) -> ShortestPaths<W>
+where
+    G: IntoEdges + NodeIndexable,
+    W: Weight,
+{
+    dijkstra_by(g, source, |e| weight(e.weight()))
+}
+
+/// Run Bellman–Ford rounds from the current `dist`, returning a node whose distance still
+/// improves after `node_bound` rounds (so it lies on or behind a negative cycle).
+fn relax_rounds<G, W>(
+    g: G,
+    dist: &mut [Option<W>],
+    pred: &mut [Option<NodeId>],
+    weight: &impl Fn(&G::EdgeWeight) -> W,
+) -> Option<NodeId>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    W: Weight,
+{
+    let rounds = g.node_bound();
+    for round in 0..=rounds {
+        let mut changed = None;
+        for e in g.edge_references() {
+            let (from, to) = (e.source(), e.target());
+            if let Some(d) = dist[from.0] {
+                let next = d + weight(e.weight());
+                if less(next, dist[to.0]) {
+                    dist[to.0] = Some(next);
+                    pred[to.0] = Some(from);
+                    changed = Some(to);
+                }
+            }
+        }
+        match changed {
+            None => return None,
+            Some(v) if round == rounds => return Some(v),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Follow predecessors from a node that keeps relaxing until inside the cycle, then
+/// collect the cycle in edge order.
+fn extract_cycle(pred: &[Option<NodeId>], start: NodeId) -> NegativeCycle {
+    let mut x = start;
+    for _ in 0..pred.len() {
+        x = pred[x.0].expect("relaxed node has a predecessor");
+    }
+    let mut cycle = vec![x];
+    let mut y = pred[x.0].unwrap();
+    while y != x {
+        cycle.push(y);
+        y = pred[y.0].unwrap();
+    }
+    cycle.reverse();
+    NegativeCycle { cycle }
+}
+
+/// Bellman–Ford, `O(VE)`. Handles negative weights and reports a negative cycle
+/// reachable from `source`.
+pub fn bellman_ford<G, W>(g: G, source: NodeId, weight: impl Fn(&G::EdgeWeight) -> W) -> Result<ShortestPaths<W>, NegativeCycle>
+where
+    G: IntoEdgeReferences + NodeIndexable,
+    W: Weight,
+{
+    let mut sp = ShortestPaths::new(source, g.node_bound());
+    match relax_rounds(g, &mut sp.dist, &mut sp.pred, &weight) {
+        Some(v) => Err(extract_cycle(&sp.pred, v)),
+        None => Ok(sp),
+    }
+}
+
+/// A* search from `source` to `target`, guided by `heuristic`, an estimate of the remaining
+/// distance. The result is optimal when the heuristic never overestimates. Returns the
+/// cost and the path.
+pub fn astar<G, W>(
+    g: G,
+    source: NodeId,
+    target: NodeId,
+    weight: impl Fn(&G::EdgeWeight) -> W,
+    heuristic: impl Fn(NodeId) -> W,
+) -> Option<(W, Vec<NodeId>)>
+where
+    G: IntoEdges + NodeIndexable,
+    W: Weight,
+{
+    let mut best: Vec<Option<W>> = vec![None; g.node_bound()];
+    let mut pred = vec![None; g.node_bound()];
+    best[source.0] = Some(W::zero());
+    let mut heap = BinaryHeap::from([Entry { cost: heuristic(source), node: source }]);
+    while let Some(Entry { cost, node }) = heap.pop() {
+        let g_cost = best[node.0].unwrap();
+        if node == target {
+            return Some((g_cost, reconstruct_path(&pred, source, target)?));
+        }
+        // stale entry: a cheaper route to `node` was queued after this one
+        if cost > g_cost + heuristic(node) {
+            continue;
+        }
+        for e in g.edges(node) {
+            let (to, next) = (e.target(), g_cost + weight(e.weight()));
+            if less(next, best[to.0]) {
+                best[to.0] = Some(next);
+                pred[to.0] = Some(node);
+                heap.push(Entry { cost: next + heuristic(to), node: to });
+            }
+        }
+    }
+    None
+}
+
+/// Floyd–Warshall all-pairs shortest paths, `O(V^3)`. Fails on any negative cycle.
+pub fn floyd_warshall<G, W>(g: G, weight: impl Fn(&G::EdgeWeight) -> W) -> Result<AllPairsPaths<W>, NegativeCycle>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+    W: Weight,
+{
+    let n = g.node_bound();
+    let mut dist: Vec<Vec<Option<W>>> = vec![vec![None; n]; n];
+    let mut pred = vec![vec![None; n]; n];
+    for v in g.node_identifiers() {
+        dist[v.0][v.0] = Some(W::zero());
+    }
+    for e in g.edge_references() {
+        let (from, to, w) = (e.source(), e.target(), weight(e.weight()));
+        if less(w, dist[from.0][to.0]) {
+            dist[from.0][to.0] = Some(w);
+            pred[from.0][to.0] = Some(from);
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            let Some(dik) = dist[i][k] else { continue };
+            for j in 0..n {
+                if let Some(dkj) = dist[k][j] {
+                    if less(dik + dkj, dist[i][j]) {
+                        dist[i][j] = Some(dik + dkj);
+                        pred[i][j] = pred[k][j];
+                    }
+                }
+            }
+            if dist[i][i].is_some_and(|d| d < W::zero()) {
+                return Err(extract_cycle(&pred[i], NodeId(i)));
+            }
+        }
+    }
+    Ok(AllPairsPaths { dist, pred })
+}
+
+/// Johnson's all-pairs algorithm, `O(VE log V)`: Bellman–Ford potentials from a virtual
+/// source make every weight non-negative, then Dijkstra runs from each node. Better than
+/// Floyd–Warshall on sparse graphs.
+pub fn johnson<G, W>(g: G, weight: impl Fn(&G::EdgeWeight) -> W) -> Result<AllPairsPaths<W>, NegativeCycle>
+where
+    G: IntoEdges + IntoEdgeReferences + IntoNodeIdentifiers + NodeIndexable,
+    W: Weight,
+{
+    let n = g.node_bound();
+    // a virtual source with zero-weight edges to every node starts all potentials at 0
+    let mut h = vec![Some(W::zero()); n];
+    let mut h_pred = vec![None; n];
+    if let Some(v) = relax_rounds(g, &mut h, &mut h_pred, &weight) {
+        return Err(extract_cycle(&h_pred, v));
+    }
+    let h: Vec<W> = h.into_iter().map(|x| x.unwrap()).collect();
+    let mut dist = vec![vec![None; n]; n];
+    let mut pred = vec![vec![None; n]; n];
+    for s in g.node_identifiers() {
+        let sp = dijkstra_by(g, s, |e| weight(e.weight()) + h[e.source().0] - h[e.target().0]);
+        dist[s.0] = sp.dist.iter().enumerate().map(|(v, d)| d.map(|d| d - h[s.0] + h[v])).collect();
+        pred[s.0] = sp.pred;
+    }
+    Ok(AllPairsPaths { dist, pred })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, Graph};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_graph(rng: &mut StdRng, n: usize, m: usize, weights: std::ops::Range<i64>) -> Graph<(), i64> {
+        let edges: Vec<(usize, usize, i64)> =
+            (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(weights.clone()))).collect();
+        graph_from_edges(n, &edges)
+    }
+
+    /// Total weight along `path`, taking the lightest parallel edge at each step.
+    fn path_weight(g: &Graph<(), i64>, path: &[NodeId]) -> i64 {
+        path.windows(2).map(|p| g.edges_between(p[0], p[1]).map(|e| *g.get_edge(e).unwrap()).min().unwrap()).sum()
+    }
+
+    #[test]
+    fn small_graph_distances_and_paths() {
+        let g = graph_from_edges(5, &[(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (2, 3, 5)]);
+        let sp = dijkstra(&g, NodeId(0), |&w| w);
+        assert_eq!(sp.dist, [Some(0), Some(3), Some(1), Some(4), None]);
+        assert_eq!(sp.path_to(NodeId(3)), Some(vec![NodeId(0), NodeId(2), NodeId(1), NodeId(3)]));
+        assert_eq!(sp.path_to(NodeId(4)), None);
+        assert_eq!(sp.path_to(NodeId(0)), Some(vec![NodeId(0)]));
+        assert_eq!(bellman_ford(&g, NodeId(0), |&w| w).unwrap().dist, sp.dist);
+        assert_eq!(astar(&g, NodeId(0), NodeId(3), |&w| w, |_| 0), Some((4, sp.path_to(NodeId(3)).unwrap())));
+        assert_eq!(astar(&g, NodeId(3), NodeId(0), |&w| w, |_| 0), None);
+    }
+
+    #[test]
+    fn algorithms_agree_on_random_graphs() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..30 {
+            let g = random_graph(&mut rng, 12, 40, 0..20);
+            let fw = floyd_warshall(&g, |&w| w).unwrap();
+            let jo = johnson(&g, |&w| w).unwrap();
+            assert_eq!(fw.dist, jo.dist);
+            for s in g.node_ids() {
+                let d = dijkstra(&g, s, |&w| w);
+                let bf = bellman_ford(&g, s, |&w| w).unwrap();
+                assert_eq!(d.dist, fw.dist[s.0]);
+                assert_eq!(bf.dist, fw.dist[s.0]);
+                for t in g.node_ids() {
+                    if let Some(dist) = fw.distance(s, t) {
+                        assert_eq!(path_weight(&g, &fw.path(s, t).unwrap()), dist);
+                        assert_eq!(path_weight(&g, &jo.path(s, t).unwrap()), dist);
+                        assert_eq!(path_weight(&g, &d.path_to(t).unwrap()), dist);
+                        assert_eq!(astar(&g, s, t, |&w| w, |_| 0).map(|(c, _)| c), Some(dist));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_weights_without_cycles() {
+        let mut rng = StdRng::seed_from_u64(43);
+        for _ in 0..30 {
+            // edges only go forward, so there is no cycle at all
+            let edges: Vec<(usize, usize, i64)> = (0..30)
+                .map(|_| {
+                    let (u, v) = (rng.gen_range(0..9), rng.gen_range(0..9));
+                    (u.min(v), u.max(v) + 1, rng.gen_range(-10..10))
+                })
+                .collect();
+            let g = graph_from_edges(10, &edges);
+            let fw = floyd_warshall(&g, |&w| w).unwrap();
+            assert_eq!(johnson(&g, |&w| w).unwrap().dist, fw.dist);
+            for s in g.node_ids() {
+                let bf = bellman_ford(&g, s, |&w| w).unwrap();
+                assert_eq!(bf.dist, fw.dist[s.0]);
+                for t in g.node_ids() {
+                    if let Some(path) = bf.path_to(t) {
+                        assert_eq!(path_weight(&g, &path), bf.distance(t).unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_cycles_are_reported() {
+        // 1 -> 2 -> 3 -> 1 weighs -1; node 4 hangs off the cycle
+        let g = graph_from_edges(5, &[(0, 1, 1), (1, 2, 2), (2, 3, -4), (3, 1, 1), (3, 4, 1)]);
+        let cycle_weight = |c: &NegativeCycle| {
+            let mut closed = c.cycle.clone();
+            closed.push(c.cycle[0]);
+            path_weight(&g, &closed)
+        };
+        let bf = bellman_ford(&g, NodeId(0), |&w| w).unwrap_err();
+        assert_eq!(bf.cycle.len(), 3);
+        assert!(cycle_weight(&bf) < 0);
+        assert!(cycle_weight(&floyd_warshall(&g, |&w| w).unwrap_err()) < 0);
+        assert!(cycle_weight(&johnson(&g, |&w| w).unwrap_err()) < 0);
+        // unreachable from node 4, so Bellman-Ford from there succeeds
+        assert!(bellman_ford(&g, NodeId(4), |&w| w).is_ok());
+    }
+
+    #[test]
+    fn astar_on_a_grid() {
+        let side = 8;
+        let mut edges = Vec::new();
+        for r in 0..side {
+            for c in 0..side {
+                let v = r * side + c;
+                // a wall in column 4 with a gap in the last row
+                let blocked = |c: usize, r: usize| c == 4 && r + 1 < side;
+                if c + 1 < side && !blocked(c, r) && !blocked(c + 1, r) {
+                    edges.extend([(v, v + 1, 1), (v + 1, v, 1)]);
+                }
+                if r + 1 < side && !blocked(c, r) {
+                    edges.extend([(v, v + side, 1), (v + side, v, 1)]);
+                }
+            }
+        }
+        let g = graph_from_edges(side * side, &edges);
+        let target = NodeId(side - 1);
+        let manhattan = |v: NodeId| ((v.0 / side) as i64) + ((side - 1) as i64 - (v.0 % side) as i64).abs();
+        let (cost, path) = astar(&g, NodeId(0), target, |&w| w, manhattan).unwrap();
+        assert_eq!(Some(cost), dijkstra(&g, NodeId(0), |&w| w).distance(target));
+        assert_eq!(cost, 2 * (side as i64 - 1) + side as i64 - 1);
+        assert_eq!(path_weight(&g, &path), cost);
+    }
+
+    #[test]
+    fn float_weights_and_removed_nodes() {
+        let mut g = graph_from_edges(4, &[(0, 1, 0.5), (1, 3, 0.25), (0, 2, 0.1), (2, 3, 0.1)]);
+        let v: Vec<NodeId> = g.node_ids().collect();
+        g.remove_node(v[2]).unwrap();
+        let sp = dijkstra(&g, v[0], |&w| w);
+        assert_eq!(sp.distance(v[3]), Some(0.75));
+        assert_eq!(sp.distance(v[2]), None);
+        let all = johnson(&g, |&w| w).unwrap();
+        assert_eq!(all.distance(v[0], v[3]), Some(0.75));
+        assert_eq!(all.path(v[0], v[3]), Some(vec![v[0], v[1], v[3]]));
+    }
+}
@@ -0,0 +1,177 @@
+//! Spectral methods on undirected graphs: algebraic connectivity, the Fiedler vector and
+//! spectral clustering. They assume a symmetric adjacency matrix, so wrap a directed
+//! `Graph` in `Undirected`. Eigenpairs come from the dense solver, `O(n^3)` in the number
+//! of nodes.
+
+use super::matrix::{by_node_id, laplacian_matrix, matrix_index, normalized_laplacian_matrix, symmetric_eigen};
+use super::visit::{IntoEdges, IntoNodeIdentifiers, NodeIndexable};
+
+/// Second-smallest Laplacian eigenvalue: zero exactly when the graph is disconnected, and
+/// larger for graphs that are harder to cut. Zero for graphs with fewer than two nodes.
+pub fn algebraic_connectivity<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> f64
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (values, _) = symmetric_eigen(&laplacian_matrix(g, weight).to_dense());
+    values.get(1).map_or(0.0, |&x| x.max(0.0))
+}
+
+/// Unit eigenvector of the second-smallest Laplacian eigenvalue, indexed by node (zero for
+/// removed nodes). The signs of its entries split the graph along a sparse cut; the overall
+/// sign is fixed so the first non-zero entry is positive. `None` for fewer than two nodes.
+pub fn fiedler_vector<G>(g: G, weight: impl Fn(&G::EdgeWeight) -> f64) -> Option<Vec<f64>>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    let (order, _) = matrix_index(g);
+    let (_, mut vectors) = symmetric_eigen(&laplacian_matrix(g, weight).to_dense());
+    if vectors.len() < 2 {
+        return None;
+    }
+    let mut fiedler = vectors.swap_remove(1);
+    if fiedler.iter().find(|x| x.abs() > 1e-12).is_some_and(|&x| x < 0.0) {
+        fiedler.iter_mut().for_each(|x| *x = -*x);
+    }
+    Some(by_node_id(&order, g.node_bound(), &fiedler, 0.0))
+}
+
+/// Spectral clustering into `k` groups (Ng, Jordan and Weiss): the rows of the `k` lowest
+/// eigenvectors of the normalized Laplacian, scaled to unit length, are grouped by k-means
+/// with farthest-point seeding. Returns a cluster label in `0..k` per node (zero for
+/// removed nodes). Panics if `k` is zero.
+pub fn spectral_clustering<G>(g: G, k: usize, weight: impl Fn(&G::EdgeWeight) -> f64) -> Vec<usize>
+where
+    G: IntoEdges + IntoNodeIdentifiers + NodeIndexable,
+{
+    assert!(k > 0, "spectral clustering needs at least one cluster");
+    let (order, _) = matrix_index(g);
+    let n = order.len();
+    let k = k.min(n);
+    let (_, vectors) = symmetric_eigen(&normalized_laplacian_matrix(g, weight).to_dense());
+    let points: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let row: Vec<f64> = vectors[..k].iter().map(|v| v[i]).collect();
+            let norm = row.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 1e-12 { row.iter().map(|x| x / norm).collect() } else { row }
+        })
+        .collect();
+    let labels = k_means(&points, k);
+    let mut out = vec![0; g.node_bound()];
+    for (v, label) in order.iter().zip(labels) {
+        out[v.0] = label;
+    }
+    out
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Lloyd's algorithm seeded with the first point and then repeatedly the point farthest
+/// from every chosen centre, so the result is deterministic.
+fn k_means(points: &[Vec<f64>], k: usize) -> Vec<usize> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let mut centres = vec![points[0].clone()];
+    let mut nearest: Vec<f64> = points.iter().map(|p| squared_distance(p, &points[0])).collect();
+    while centres.len() < k {
+        let far = (0..points.len()).max_by(|&i, &j| nearest[i].total_cmp(&nearest[j])).unwrap();
+        centres.push(points[far].clone());
+        for (d, p) in nearest.iter_mut().zip(points) {
+            *d = d.min(squared_distance(p, &points[far]));
+        }
+    }
+    let mut labels = vec![usize::MAX; points.len()];
+    for _ in 0..100 {
+        let mut changed = false;
+        for (label, p) in labels.iter_mut().zip(points) {
+            let distance = |c: &usize| squared_distance(p, &centres[*c]);
+            let best = (0..k).min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap();
+            changed |= std::mem::replace(label, best) != best;
+        }
+        if !changed {
+            break;
+        }
+        for (c, centre) in centres.iter_mut().enumerate() {
+            let members: Vec<&Vec<f64>> =
+                points.iter().zip(&labels).filter(|&(_, &l)| l == c).map(|(p, _)| p).collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (d, x) in centre.iter_mut().enumerate() {
+                *x = members.iter().map(|p| p[d]).sum::<f64>() / members.len() as f64;
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_from_edges, Graph, NodeId, Undirected};
+
+    /// Cliques on consecutive node ranges of the given sizes, each joined to the next by
+    /// one edge.
+    fn chained_cliques(sizes: &[usize]) -> Graph<(), f64> {
+        let mut edges = Vec::new();
+        let mut start = 0;
+        for (k, &size) in sizes.iter().enumerate() {
+            edges.extend((start..start + size).flat_map(|i| (start..i).map(move |j| (i, j, 1.0))));
+            if k + 1 < sizes.len() {
+                edges.push((start + size - 1, start + size, 1.0));
+            }
+            start += size;
+        }
+        graph_from_edges(start, &edges)
+    }
+
+    #[test]
+    fn algebraic_connectivity_of_known_graphs() {
+        let complete = graph_from_edges(5, &(0..5).flat_map(|i| (0..i).map(move |j| (i, j, 1.0))).collect::<Vec<_>>());
+        assert!((algebraic_connectivity(Undirected(&complete), |&w| w) - 5.0).abs() < 1e-9);
+        let path = graph_from_edges(4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+        let expected = 2.0 - 2.0 * (std::f64::consts::PI / 4.0).cos();
+        assert!((algebraic_connectivity(Undirected(&path), |&w| w) - expected).abs() < 1e-9);
+        let split = graph_from_edges(4, &[(0, 1, 1.0), (2, 3, 1.0)]);
+        assert!(algebraic_connectivity(Undirected(&split), |&w| w).abs() < 1e-9);
+        assert_eq!(algebraic_connectivity(&graph_from_edges(1, &[]), |&w| w), 0.0);
+    }
+
+    #[test]
+    fn fiedler_vector_separates_two_clusters() {
+        let mut g = chained_cliques(&[4, 5]);
+        let spare = g.add_node(());
+        g.add_edge(spare, NodeId(0), 1.0).unwrap();
+        g.remove_node(spare).unwrap();
+        let f = fiedler_vector(Undirected(&g), |&w| w).unwrap();
+        assert_eq!(f.len(), 10);
+        assert_eq!(f[9], 0.0);
+        assert!(f[..4].iter().all(|&x| x > 0.0));
+        assert!(f[4..9].iter().all(|&x| x < 0.0));
+        assert!((f.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(f.iter().sum::<f64>().abs() < 1e-9);
+        assert_eq!(fiedler_vector(&graph_from_edges(1, &[]), |&w| w), None);
+    }
+
+    #[test]
+    fn spectral_clustering_finds_cliques() {
+        let g = chained_cliques(&[4, 5, 3]);
+        let labels = spectral_clustering(Undirected(&g), 3, |&w| w);
+        let groups = [&labels[..4], &labels[4..9], &labels[9..]];
+        assert!(groups.iter().all(|c| c.iter().all(|&l| l == c[0])));
+        let mut firsts: Vec<usize> = groups.iter().map(|c| c[0]).collect();
+        firsts.sort();
+        assert_eq!(firsts, [0, 1, 2]);
+        // more clusters than nodes are capped
+        assert_eq!(spectral_clustering(Undirected(&graph_from_edges(2, &[(0, 1, 1.0)])), 5, |&w| w).len(), 2);
+        assert!(spectral_clustering(&graph_from_edges(0, &[]), 2, |&w| w).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one cluster")]
+    fn spectral_clustering_needs_a_cluster() {
+        spectral_clustering(&graph_from_edges(3, &[]), 0, |&w| w);
+    }
+}
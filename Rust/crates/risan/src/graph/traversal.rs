@@ -0,0 +1,396 @@
+//! Breadth- and depth-first traversal: node iterators, event-driven searches with
+//! pruning, topological sorting and directed cycle detection.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use super::visit::{IntoNeighbors, IntoNodeIdentifiers, NodeCount, NodeIndexable};
+use super::NodeId;
+
+/// What a visitor wants the search to do after an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    /// Do not explore past this node or edge.
+    Prune,
+    /// Stop the whole search.
+    Break,
+}
+
+/// Events reported by `depth_first_search`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsEvent {
+    Discover(NodeId),
+    /// Edge to an undiscovered node, which the search descends into.
+    TreeEdge(NodeId, NodeId),
+    /// Edge to an ancestor on the current search path; its presence means a cycle.
+    BackEdge(NodeId, NodeId),
+    /// Edge to a node that is already finished.
+    CrossForwardEdge(NodeId, NodeId),
+    /// All descendants of the node have been explored.
+    Finish(NodeId),
+}
+
+/// Events reported by `breadth_first_search`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BfsEvent {
+    Discover(NodeId),
+    TreeEdge(NodeId, NodeId),
+    NonTreeEdge(NodeId, NodeId),
+    /// All outgoing edges of the node have been examined.
+    Finish(NodeId),
+}
+
+/// Breadth-first iterator over the nodes reachable from a start node.
+pub struct Bfs<G> {
+    graph: G,
+    queue: VecDeque<NodeId>,
+    discovered: Vec<bool>,
+}
+
+impl<G: IntoNeighbors + NodeIndexable> Bfs<G> {
+    pub fn new(graph: G, start: NodeId) -> Self {
+        let mut discovered = vec![false; graph.node_bound()];
+        discovered[start.0] = true;
+        Self { graph, queue: VecDeque::from([start]), discovered }
+    }
+}
+
+impl<G: IntoNeighbors> Iterator for Bfs<G> {
+    type Item = NodeId;
+    fn next(&mut self) -> Option<NodeId> {
+        let node = self.queue.pop_front()?;
+        for v in self.graph.neighbors(node) {
+            if !std::mem::replace(&mut self.discovered[v.0], true) {
+                self.queue.push_back(v);
+            }
+        }
+        Some(node)
+    }
+}
+
+/// Depth-first iterator yielding the nodes reachable from a start node in preorder,
+/// following outgoing edges in insertion order.
+pub struct Dfs<G> {
+    graph: G,
+    stack: Vec<NodeId>,
+    discovered: Vec<bool>,
+}
+
+impl<G: IntoNeighbors + NodeIndexable> Dfs<G> {
+    pub fn new(graph: G, start: NodeId) -> Self {
+        Self { graph, stack: vec![start], discovered: vec![false; graph.node_bound()] }
+    }
+}
+
+impl<G: IntoNeighbors> Iterator for Dfs<G> {
+    type Item = NodeId;
+    fn next(&mut self) -> Option<NodeId> {
+        while let Some(node) = self.stack.pop() {
+            if std::mem::replace(&mut self.discovered[node.0], true) {
+                continue;
+            }
+            let unseen: Vec<NodeId> = self.graph.neighbors(node).filter(|n| !self.discovered[n.0]).collect();
+            self.stack.extend(unseen.into_iter().rev());
+            return Some(node);
+        }
+        None
+    }
+}
+
+/// Iterative depth-first search from each of `starts` in turn (skipping nodes already
+/// visited), reporting every event to `visitor`. Returns `Control::Break` if the visitor
+/// stopped the search.
+pub fn depth_first_search<G: IntoNeighbors + NodeIndexable>(
+    g: G,
+    starts: impl IntoIterator<Item = NodeId>,
+    mut visitor: impl FnMut(DfsEvent) -> Control,
+) -> Control {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Color {
+        White,
+        Gray,
+        Black,
+    }
+    let mut color = vec![Color::White; g.node_bound()];
+    // (node, its remaining successors)
+    let mut stack = Vec::new();
+    for start in starts {
+        if color[start.0] != Color::White {
+            continue;
+        }
+        color[start.0] = Color::Gray;
+        match visitor(DfsEvent::Discover(start)) {
+            Control::Break => return Control::Break,
+            Control::Prune => {
+                color[start.0] = Color::Black;
+                if visitor(DfsEvent::Finish(start)) == Control::Break {
+                    return Control::Break;
+                }
+                continue;
+            }
+            Control::Continue => stack.push((start, g.neighbors(start))),
+        }
+        while let Some((u, successors)) = stack.last_mut() {
+            let u = *u;
+            let Some(v) = successors.next() else {
+                stack.pop();
+                color[u.0] = Color::Black;
+                if visitor(DfsEvent::Finish(u)) == Control::Break {
+                    return Control::Break;
+                }
+                continue;
+            };
+            let event = match color[v.0] {
+                Color::White => DfsEvent::TreeEdge(u, v),
+                Color::Gray => DfsEvent::BackEdge(u, v),
+                Color::Black => DfsEvent::CrossForwardEdge(u, v),
+            };
+            match visitor(event) {
+                Control::Break => return Control::Break,
+                Control::Prune => continue,
+                Control::Continue => {}
+            }
+            if color[v.0] == Color::White {
+                color[v.0] = Color::Gray;
+                match visitor(DfsEvent::Discover(v)) {
+                    Control::Break => return Control::Break,
+                    Control::Prune => {
+                        color[v.0] = Color::Black;
+                        if visitor(DfsEvent::Finish(v)) == Control::Break {
+                            return Control::Break;
+                        }
+                    }
+                    Control::Continue => stack.push((v, g.neighbors(v))),
+                }
+            }
+        }
+    }
+    Control::Continue
+}
+
+/// Breadth-first search from all of `starts` at once, reporting every event to `visitor`.
+/// Returns `Control::Break` if the visitor stopped the search.
+pub fn breadth_first_search<G: IntoNeighbors + NodeIndexable>(
+    g: G,
+    starts: impl IntoIterator<Item = NodeId>,
+    mut visitor: impl FnMut(BfsEvent) -> Control,
+) -> Control {
+    let mut discovered = vec![false; g.node_bound()];
+    let mut queue = VecDeque::new();
+    for s in starts {
+        if std::mem::replace(&mut discovered[s.0], true) {
+            continue;
+        }
+        match visitor(BfsEvent::Discover(s)) {
+            Control::Break => return Control::Break,
+            Control::Prune => {}
+            Control::Continue => queue.push_back(s),
+        }
+    }
+    while let Some(u) = queue.pop_front() {
+        for v in g.neighbors(u) {
+            if discovered[v.0] {
+                if visitor(BfsEvent::NonTreeEdge(u, v)) == Control::Break {
+                    return Control::Break;
+                }
+                continue;
+            }
+            match visitor(BfsEvent::TreeEdge(u, v)) {
+                Control::Break => return Control::Break,
+                Control::Prune => continue,
+                Control::Continue => {}
+            }
+            discovered[v.0] = true;
+            match visitor(BfsEvent::Discover(v)) {
+                Control::Break => return Control::Break,
+                Control::Prune => {}
+                Control::Continue => queue.push_back(v),
+            }
+        }
+        if visitor(BfsEvent::Finish(u)) == Control::Break {
+            return Control::Break;
+        }
+    }
+    Control::Continue
+}
+
+/// A directed cycle, listed in edge order (the last node links back to the first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub cycle: Vec<NodeId>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph has a cycle through {:?}", self.cycle)
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Nodes ordered so every edge goes from an earlier to a later node, or a cycle
+/// witnessing that no such order exists.
+pub fn topological_sort<G>(g: G) -> Result<Vec<NodeId>, Cycle>
+where
+    G: IntoNeighbors + IntoNodeIdentifiers + NodeIndexable + NodeCount,
+{
+    let mut order = Vec::with_capacity(g.node_count());
+    let mut path = Vec::new();
+    let mut cycle = None;
+    depth_first_search(g, g.node_identifiers(), |event| {
+        match event {
+            DfsEvent::Discover(v) => path.push(v),
+            DfsEvent::Finish(v) => {
+                path.pop();
+                order.push(v);
+            }
+            DfsEvent::BackEdge(_, v) => {
+                let start = path.iter().rposition(|&x| x == v).unwrap();
+                cycle = Some(Cycle { cycle: path[start..].to_vec() });
+                return Control::Break;
+            }
+            _ => {}
+        }
+        Control::Continue
+    });
+    match cycle {
+        Some(c) => Err(c),
+        None => {
+            order.reverse();
+            Ok(order)
+        }
+    }
+}
+
+/// Some directed cycle, if there is one (self-loops count).
+pub fn find_cycle<G>(g: G) -> Option<Vec<NodeId>>
+where
+    G: IntoNeighbors + IntoNodeIdentifiers + NodeIndexable + NodeCount,
+{
+    topological_sort(g).err().map(|c| c.cycle)
+}
+
+pub fn is_cyclic_directed<G: IntoNeighbors + IntoNodeIdentifiers + NodeIndexable>(g: G) -> bool {
+    depth_first_search(g, g.node_identifiers(), |event| match event {
+        DfsEvent::BackEdge(..) => Control::Break,
+        _ => Control::Continue,
+    }) == Control::Break
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph_from_edges;
+
+    fn ids(v: &[usize]) -> Vec<NodeId> {
+        v.iter().map(|&i| NodeId(i)).collect()
+    }
+
+    #[test]
+    fn iterators_visit_reachable_nodes_in_order() {
+        //   0 -> 1 -> 3
+        //   0 -> 2 -> 3 -> 4,  5 isolated
+        let g = graph_from_edges(6, &[(0, 1, ()), (0, 2, ()), (1, 3, ()), (2, 3, ()), (3, 4, ())]);
+        assert_eq!(Bfs::new(&g, NodeId(0)).collect::<Vec<_>>(), ids(&[0, 1, 2, 3, 4]));
+        assert_eq!(Dfs::new(&g, NodeId(0)).collect::<Vec<_>>(), ids(&[0, 1, 3, 4, 2]));
+        assert_eq!(Bfs::new(&g, NodeId(5)).collect::<Vec<_>>(), ids(&[5]));
+        assert_eq!(Dfs::new(&g, NodeId(2)).collect::<Vec<_>>(), ids(&[2, 3, 4]));
+    }
+
+    #[test]
+    fn dfs_classifies_edges() {
+        let g = graph_from_edges(4, &[(0, 1, ()), (1, 2, ()), (2, 0, ()), (0, 3, ()), (3, 2, ())]);
+        let mut events = Vec::new();
+        depth_first_search(&g, [NodeId(0)], |e| {
+            events.push(e);
+            Control::Continue
+        });
+        use DfsEvent::*;
+        let n = NodeId;
+        assert_eq!(
+            events,
+            [
+                Discover(n(0)),
+                TreeEdge(n(0), n(1)),
+                Discover(n(1)),
+                TreeEdge(n(1), n(2)),
+                Discover(n(2)),
+                BackEdge(n(2), n(0)),
+                Finish(n(2)),
+                Finish(n(1)),
+                TreeEdge(n(0), n(3)),
+                Discover(n(3)),
+                CrossForwardEdge(n(3), n(2)),
+                Finish(n(3)),
+                Finish(n(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn visitors_prune_and_break() {
+        let g = graph_from_edges(5, &[(0, 1, ()), (1, 2, ()), (0, 3, ()), (3, 4, ())]);
+        let mut seen = Vec::new();
+        depth_first_search(&g, [NodeId(0)], |e| match e {
+            DfsEvent::Discover(v) => {
+                seen.push(v);
+                if v == NodeId(1) { Control::Prune } else { Control::Continue }
+            }
+            _ => Control::Continue,
+        });
+        assert_eq!(seen, ids(&[0, 1, 3, 4]));
+
+        let mut seen = Vec::new();
+        let control = breadth_first_search(&g, [NodeId(0)], |e| match e {
+            BfsEvent::Discover(v) => {
+                seen.push(v);
+                if v == NodeId(3) { Control::Break } else { Control::Continue }
+            }
+            BfsEvent::TreeEdge(_, NodeId(1)) => Control::Prune,
+            _ => Control::Continue,
+        });
+        assert_eq!(control, Control::Break);
+        assert_eq!(seen, ids(&[0, 3]));
+    }
+
+    #[test]
+    fn bfs_from_several_starts_reports_layers() {
+        let g = graph_from_edges(6, &[(0, 1, ()), (1, 2, ()), (2, 3, ()), (5, 4, ()), (4, 3, ())]);
+        let mut depth = [usize::MAX; 6];
+        depth[0] = 0;
+        depth[5] = 0;
+        let mut non_tree = Vec::new();
+        breadth_first_search(&g, ids(&[0, 5]), |e| {
+            match e {
+                BfsEvent::TreeEdge(u, v) => depth[v.0] = depth[u.0] + 1,
+                BfsEvent::NonTreeEdge(u, v) => non_tree.push((u, v)),
+                _ => {}
+            }
+            Control::Continue
+        });
+        assert_eq!(depth, [0, 1, 2, 2, 1, 0]);
+        assert_eq!(non_tree, [(NodeId(2), NodeId(3))]);
+    }
+
+    #[test]
+    fn topological_order_and_cycles() {
+        let g = graph_from_edges(6, &[(5, 2, ()), (5, 0, ()), (4, 0, ()), (4, 1, ()), (2, 3, ()), (3, 1, ())]);
+        let order = topological_sort(&g).unwrap();
+        let mut position = [0; 6];
+        for (i, v) in order.iter().enumerate() {
+            position[v.0] = i;
+        }
+        for (_, e) in g.edges() {
+            assert!(position[e.from.0] < position[e.to.0]);
+        }
+        assert!(!is_cyclic_directed(&g));
+        assert_eq!(find_cycle(&g), None);
+
+        let g = graph_from_edges(4, &[(0, 1, ()), (1, 2, ()), (2, 3, ()), (3, 1, ())]);
+        let cycle = topological_sort(&g).unwrap_err().cycle;
+        assert_eq!(cycle, ids(&[1, 2, 3]));
+        assert!(is_cyclic_directed(&g));
+        assert_eq!(find_cycle(&graph_from_edges(2, &[(1, 1, ())])), Some(ids(&[1])));
+    }
+}
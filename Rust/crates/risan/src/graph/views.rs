@@ -0,0 +1,598 @@
+//! Graph adaptors that change how an underlying graph is seen without copying it:
+//! reversed and undirected views, node and edge filters, and implicit graphs whose
+//! successors come from a closure.
+
+use std::collections::HashMap;
+
+use super::visit::{
+    Direction, EdgeRef, GraphBase, GraphProp, IntoEdgeReferences, IntoEdges, IntoEdgesDirected, IntoNeighbors,
+    IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount, NodeIndexable,
+};
+use super::{EdgeId, NodeId};
+
+/// An edge seen from the other end: `source` and `target` swap when `reversed` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct OrientedEdge<R> {
+    pub edge: R,
+    pub reversed: bool,
+}
+
+impl<R: EdgeRef> EdgeRef for OrientedEdge<R> {
+    type Weight = R::Weight;
+    fn id(&self) -> EdgeId {
+        self.edge.id()
+    }
+    fn source(&self) -> NodeId {
+        if self.reversed { self.edge.target() } else { self.edge.source() }
+    }
+    fn target(&self) -> NodeId {
+        if self.reversed { self.edge.source() } else { self.edge.target() }
+    }
+    fn weight(&self) -> &R::Weight {
+        self.edge.weight()
+    }
+}
+
+/// Forward the node-level traits of a wrapper to its `.0` field.
+macro_rules! forward_node_traits {
+    ($view:ident) => {
+        impl<G: GraphBase> GraphBase for $view<G> {
+            type NodeWeight = G::NodeWeight;
+            type EdgeWeight = G::EdgeWeight;
+        }
+
+        impl<G: NodeIndexable> NodeIndexable for $view<G> {
+            fn node_bound(&self) -> usize {
+                self.0.node_bound()
+            }
+        }
+
+        impl<G: NodeCount> NodeCount for $view<G> {
+            fn node_count(&self) -> usize {
+                self.0.node_count()
+            }
+        }
+
+        impl<G: IntoNodeIdentifiers> IntoNodeIdentifiers for $view<G> {
+            fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+                self.0.node_identifiers()
+            }
+        }
+    };
+}
+
+/// The graph with every edge reversed: successors become predecessors.
+#[derive(Debug, Clone, Copy)]
+pub struct Reversed<G>(pub G);
+
+forward_node_traits!(Reversed);
+
+impl<G: GraphProp> GraphProp for Reversed<G> {
+    fn is_directed(&self) -> bool {
+        self.0.is_directed()
+    }
+}
+
+impl<G: IntoNeighborsDirected> IntoNeighbors for Reversed<G> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        self.0.neighbors_directed(v, Direction::Incoming)
+    }
+}
+
+impl<G: IntoNeighborsDirected> IntoNeighborsDirected for Reversed<G> {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId> {
+        self.0.neighbors_directed(v, dir.opposite())
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoEdges for Reversed<G> {
+    type EdgeRef = OrientedEdge<G::EdgeRef>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = Self::EdgeRef> {
+        self.0.edges_directed(v, Direction::Incoming).map(|edge| OrientedEdge { edge, reversed: true })
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoEdgesDirected for Reversed<G> {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = Self::EdgeRef> {
+        self.0.edges_directed(v, dir.opposite()).map(|edge| OrientedEdge { edge, reversed: true })
+    }
+}
+
+impl<G: IntoEdgeReferences> IntoEdgeReferences for Reversed<G> {
+    type EdgeRef = OrientedEdge<G::EdgeRef>;
+    fn edge_references(self) -> impl Iterator<Item = Self::EdgeRef> {
+        self.0.edge_references().map(|edge| OrientedEdge { edge, reversed: true })
+    }
+}
+
+/// The graph with edge directions ignored. Every edge is reported from both endpoints,
+/// oriented away from the node asked about; self-loops appear once.
+#[derive(Debug, Clone, Copy)]
+pub struct Undirected<G>(pub G);
+
+forward_node_traits!(Undirected);
+
+impl<G: GraphBase> GraphProp for Undirected<G> {
+    fn is_directed(&self) -> bool {
+        false
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoNeighbors for Undirected<G> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        self.edges(v).map(|e| e.target())
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoNeighborsDirected for Undirected<G> {
+    fn neighbors_directed(self, v: NodeId, _dir: Direction) -> impl Iterator<Item = NodeId> {
+        self.neighbors(v)
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoEdges for Undirected<G> {
+    type EdgeRef = OrientedEdge<G::EdgeRef>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = Self::EdgeRef> {
+        let outgoing = self.0.edges_directed(v, Direction::Outgoing).map(|edge| OrientedEdge { edge, reversed: false });
+        let incoming = self
+            .0
+            .edges_directed(v, Direction::Incoming)
+            .filter(|e| e.source() != e.target())
+            .map(|edge| OrientedEdge { edge, reversed: true });
+        outgoing.chain(incoming)
+    }
+}
+
+impl<G: IntoEdgesDirected + IntoNeighborsDirected> IntoEdgesDirected for Undirected<G> {
+    fn edges_directed(self, v: NodeId, _dir: Direction) -> impl Iterator<Item = Self::EdgeRef> {
+        self.edges(v)
+    }
+}
+
+/// Each edge in both orientations, self-loops once.
+impl<G: IntoEdgeReferences> IntoEdgeReferences for Undirected<G> {
+    type EdgeRef = OrientedEdge<G::EdgeRef>;
+    fn edge_references(self) -> impl Iterator<Item = Self::EdgeRef> {
+        self.0.edge_references().flat_map(|edge| {
+            let back = (edge.source() != edge.target()).then_some(OrientedEdge { edge, reversed: true });
+            std::iter::once(OrientedEdge { edge, reversed: false }).chain(back)
+        })
+    }
+}
+
+/// The subgraph induced by the nodes for which `filter` returns true. Node ids and
+/// `node_bound` are those of the underlying graph.
+#[derive(Debug, Clone, Copy)]
+pub struct NodeFiltered<G, F>(pub G, pub F);
+
+impl<G, F: Fn(NodeId) -> bool> NodeFiltered<G, F> {
+    pub fn new(graph: G, filter: F) -> Self {
+        NodeFiltered(graph, filter)
+    }
+}
+
+impl<G: GraphBase, F> GraphBase for NodeFiltered<G, F> {
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G: GraphProp, F> GraphProp for NodeFiltered<G, F> {
+    fn is_directed(&self) -> bool {
+        self.0.is_directed()
+    }
+}
+
+impl<G: NodeIndexable, F> NodeIndexable for &NodeFiltered<G, F> {
+    fn node_bound(&self) -> usize {
+        self.0.node_bound()
+    }
+}
+
+impl<G: IntoNodeIdentifiers, F: Fn(NodeId) -> bool> NodeCount for &NodeFiltered<G, F> {
+    fn node_count(&self) -> usize {
+        self.node_identifiers().count()
+    }
+}
+
+impl<G: IntoNodeIdentifiers, F: Fn(NodeId) -> bool> IntoNodeIdentifiers for &NodeFiltered<G, F> {
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+        self.0.node_identifiers().filter(move |&v| (self.1)(v))
+    }
+}
+
+impl<G: IntoNeighbors, F: Fn(NodeId) -> bool> IntoNeighbors for &NodeFiltered<G, F> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        let keep = (self.1)(v);
+        self.0.neighbors(v).filter(move |&w| keep && (self.1)(w))
+    }
+}
+
+impl<G: IntoNeighborsDirected, F: Fn(NodeId) -> bool> IntoNeighborsDirected for &NodeFiltered<G, F> {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId> {
+        let keep = (self.1)(v);
+        self.0.neighbors_directed(v, dir).filter(move |&w| keep && (self.1)(w))
+    }
+}
+
+impl<G: IntoEdges, F: Fn(NodeId) -> bool> IntoEdges for &NodeFiltered<G, F> {
+    type EdgeRef = G::EdgeRef;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = G::EdgeRef> {
+        let keep = (self.1)(v);
+        self.0.edges(v).filter(move |e| keep && (self.1)(e.target()))
+    }
+}
+
+impl<G: IntoEdgesDirected, F: Fn(NodeId) -> bool> IntoEdgesDirected for &NodeFiltered<G, F> {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = G::EdgeRef> {
+        let keep = (self.1)(v);
+        self.0.edges_directed(v, dir).filter(move |e| keep && (self.1)(e.source()) && (self.1)(e.target()))
+    }
+}
+
+impl<G: IntoEdgeReferences, F: Fn(NodeId) -> bool> IntoEdgeReferences for &NodeFiltered<G, F> {
+    type EdgeRef = G::EdgeRef;
+    fn edge_references(self) -> impl Iterator<Item = G::EdgeRef> {
+        self.0.edge_references().filter(move |e| (self.1)(e.source()) && (self.1)(e.target()))
+    }
+}
+
+/// The graph keeping only the edges for which `filter(id, data)` returns true, like
+/// `Graph::retain_edges` without modifying the graph.
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeFiltered<G, F>(pub G, pub F);
+
+impl<G: GraphBase, F: Fn(EdgeId, &G::EdgeWeight) -> bool> EdgeFiltered<G, F> {
+    pub fn new(graph: G, filter: F) -> Self {
+        EdgeFiltered(graph, filter)
+    }
+}
+
+impl<G: GraphBase, F> GraphBase for EdgeFiltered<G, F> {
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G: GraphProp, F> GraphProp for EdgeFiltered<G, F> {
+    fn is_directed(&self) -> bool {
+        self.0.is_directed()
+    }
+}
+
+impl<G: NodeIndexable, F> NodeIndexable for &EdgeFiltered<G, F> {
+    fn node_bound(&self) -> usize {
+        self.0.node_bound()
+    }
+}
+
+impl<G: NodeCount, F> NodeCount for &EdgeFiltered<G, F> {
+    fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+}
+
+impl<G: IntoNodeIdentifiers, F> IntoNodeIdentifiers for &EdgeFiltered<G, F> {
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+        self.0.node_identifiers()
+    }
+}
+
+impl<G: IntoEdges, F: Fn(EdgeId, &G::EdgeWeight) -> bool> IntoNeighbors for &EdgeFiltered<G, F> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        self.edges(v).map(|e| e.target())
+    }
+}
+
+impl<G: IntoEdges, F: Fn(EdgeId, &G::EdgeWeight) -> bool> IntoEdges for &EdgeFiltered<G, F> {
+    type EdgeRef = G::EdgeRef;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = G::EdgeRef> {
+        self.0.edges(v).filter(move |e| (self.1)(e.id(), e.weight()))
+    }
+}
+
+impl<G: IntoEdgesDirected, F: Fn(EdgeId, &G::EdgeWeight) -> bool> IntoNeighborsDirected for &EdgeFiltered<G, F> {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId> {
+        self.edges_directed(v, dir).map(move |e| if dir == Direction::Outgoing { e.target() } else { e.source() })
+    }
+}
+
+impl<G: IntoEdgesDirected, F: Fn(EdgeId, &G::EdgeWeight) -> bool> IntoEdgesDirected for &EdgeFiltered<G, F> {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = G::EdgeRef> {
+        self.0.edges_directed(v, dir).filter(move |e| (self.1)(e.id(), e.weight()))
+    }
+}
+
+impl<G: IntoEdgeReferences, F: Fn(EdgeId, &G::EdgeWeight) -> bool> IntoEdgeReferences for &EdgeFiltered<G, F> {
+    type EdgeRef = G::EdgeRef;
+    fn edge_references(self) -> impl Iterator<Item = G::EdgeRef> {
+        self.0.edge_references().filter(move |e| (self.1)(e.id(), e.weight()))
+    }
+}
+
+/// Edge of an `ImplicitGraph`, carrying its weight by value. With `n` nodes the id is
+/// `(k * n + u) * n + v` for the `k`-th edge from `u` to `v` (counting parallel edges from
+/// 0), or between `u <= v` when the graph is undirected, so both ends of an undirected
+/// edge report the same id.
+#[derive(Debug, Clone, Copy)]
+pub struct ImplicitEdge<W> {
+    pub id: EdgeId,
+    pub source: NodeId,
+    pub target: NodeId,
+    pub weight: W,
+}
+
+impl<W: Copy> EdgeRef for ImplicitEdge<W> {
+    type Weight = W;
+    fn id(&self) -> EdgeId {
+        self.id
+    }
+    fn source(&self) -> NodeId {
+        self.source
+    }
+    fn target(&self) -> NodeId {
+        self.target
+    }
+    fn weight(&self) -> &W {
+        &self.weight
+    }
+}
+
+/// A graph on nodes `0..node_count` whose edges are produced on demand: `successors(v)`
+/// yields `(target, weight)` pairs. Useful for grids, state spaces and other graphs that
+/// are cheaper to compute than to store. Incoming edges are unknown, so the `*Directed`
+/// traits and the adaptors needing them are unavailable.
+#[derive(Debug, Clone, Copy)]
+pub struct ImplicitGraph<F> {
+    node_count: usize,
+    directed: bool,
+    successors: F,
+}
+
+impl<F, I, W> ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    pub fn new(node_count: usize, successors: F) -> Self {
+        Self { node_count, directed: true, successors }
+    }
+
+    /// An undirected graph: `successors` must be symmetric, listing every edge from both ends
+    /// (self-loops once) and parallel edges in the same order from either end.
+    pub fn undirected(node_count: usize, successors: F) -> Self {
+        Self { node_count, directed: false, successors }
+    }
+}
+
+impl<F, I, W> GraphBase for ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    type NodeWeight = ();
+    type EdgeWeight = W;
+}
+
+impl<F, I, W> GraphProp for ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+}
+
+impl<F, I, W> NodeIndexable for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    fn node_bound(&self) -> usize {
+        self.node_count
+    }
+}
+
+impl<F, I, W> NodeCount for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+}
+
+impl<F, I, W> IntoNodeIdentifiers for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+        (0..self.node_count).map(NodeId)
+    }
+}
+
+impl<F, I, W> IntoNeighbors for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        (self.successors)(v).into_iter().map(|(w, _)| w)
+    }
+}
+
+impl<F, I, W: Copy> IntoEdges for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    type EdgeRef = ImplicitEdge<W>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = ImplicitEdge<W>> {
+        let n = self.node_count;
+        // parallel edges seen so far to each target
+        let mut seen: HashMap<NodeId, usize> = HashMap::new();
+        (self.successors)(v).into_iter().map(move |(target, weight)| {
+            let k = seen.entry(target).or_insert(0);
+            let (a, b) = if self.directed { (v, target) } else { (v.min(target), v.max(target)) };
+            let id = EdgeId((*k * n + a.0) * n + b.0);
+            *k += 1;
+            ImplicitEdge { id, source: v, target, weight }
+        })
+    }
+}
+
+impl<F, I, W: Copy> IntoEdgeReferences for &ImplicitGraph<F>
+where
+    F: Fn(NodeId) -> I,
+    I: IntoIterator<Item = (NodeId, W)>,
+{
+    type EdgeRef = ImplicitEdge<W>;
+    fn edge_references(self) -> impl Iterator<Item = ImplicitEdge<W>> {
+        (0..self.node_count).flat_map(move |v| self.edges(NodeId(v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{
+        bellman_ford, bridges, degree_centrality, dijkstra, floyd_warshall, graph_from_edges, is_cyclic_undirected,
+        johnson, kruskal,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_edges(rng: &mut StdRng, n: usize, m: usize) -> Vec<(usize, usize, i64)> {
+        (0..m).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..10))).collect()
+    }
+
+    #[test]
+    fn undirected_view_is_undirected_for_every_algorithm() {
+        let g = graph_from_edges(2, &[(0, 1, 1)]);
+        let u = Undirected(&g);
+        assert_eq!(dijkstra(u, NodeId(1), |&w| w).dist[0], Some(1));
+        assert_eq!(bellman_ford(u, NodeId(1), |&w| w).unwrap().dist[0], Some(1));
+        assert_eq!(floyd_warshall(u, |&w| w).unwrap().dist[1][0], Some(1));
+        assert_eq!(johnson(u, |&w| w).unwrap().dist[1][0], Some(1));
+
+        let mut rng = StdRng::seed_from_u64(48);
+        for _ in 0..30 {
+            let edges = random_edges(&mut rng, 8, 12);
+            let g = graph_from_edges(8, &edges);
+            let both: Vec<_> = edges.iter().flat_map(|&(a, b, w)| [(a, b, w), (b, a, w)]).collect();
+            let sym = graph_from_edges(8, &both);
+            let u = Undirected(&g);
+            assert_eq!(floyd_warshall(u, |&w| w).unwrap().dist, floyd_warshall(&sym, |&w| w).unwrap().dist);
+            assert_eq!(johnson(u, |&w| w).unwrap().dist, johnson(&sym, |&w| w).unwrap().dist);
+            for s in g.node_ids() {
+                let expected = dijkstra(&sym, s, |&w| w).dist;
+                assert_eq!(dijkstra(u, s, |&w| w).dist, expected);
+                assert_eq!(bellman_ford(u, s, |&w| w).unwrap().dist, expected);
+            }
+            // the undirected algorithms see each edge once, as on the graph itself
+            assert_eq!(bridges(u), bridges(&g));
+            assert_eq!(is_cyclic_undirected(u), is_cyclic_undirected(&g));
+            assert_eq!(degree_centrality(u), degree_centrality(&g));
+            assert_eq!(kruskal(u, |&w| w), kruskal(&g, |&w| w));
+        }
+    }
+
+    #[test]
+    fn reversed_view_matches_the_reversed_graph() {
+        let mut rng = StdRng::seed_from_u64(480);
+        for _ in 0..20 {
+            let edges = random_edges(&mut rng, 8, 16);
+            let g = graph_from_edges(8, &edges);
+            let flipped: Vec<_> = edges.iter().map(|&(a, b, w)| (b, a, w)).collect();
+            let rev = graph_from_edges(8, &flipped);
+            assert_eq!(floyd_warshall(Reversed(&g), |&w| w).unwrap().dist, floyd_warshall(&rev, |&w| w).unwrap().dist);
+            for s in g.node_ids() {
+                assert_eq!(dijkstra(Reversed(&g), s, |&w| w).dist, dijkstra(&rev, s, |&w| w).dist);
+            }
+        }
+    }
+
+    #[test]
+    fn filtered_views_match_filtered_copies() {
+        let mut rng = StdRng::seed_from_u64(4800);
+        for _ in 0..20 {
+            let g = graph_from_edges(9, &random_edges(&mut rng, 9, 20));
+            let keep_node = |v: NodeId| v.0 % 3 != 2;
+            let nodes = NodeFiltered::new(&g, keep_node);
+            let mut copy = g.clone();
+            copy.retain_nodes(|v, _| keep_node(v));
+            assert_eq!(floyd_warshall(&nodes, |&w| w).unwrap().dist, floyd_warshall(&copy, |&w| w).unwrap().dist);
+            assert_eq!(bridges(&nodes), bridges(&copy));
+
+            let keep_edge = |id: EdgeId, w: &i64| !id.0.is_multiple_of(4) && *w < 7;
+            let edges = EdgeFiltered::new(&g, keep_edge);
+            let mut copy = g.clone();
+            copy.retain_edges(keep_edge);
+            for s in g.node_ids() {
+                assert_eq!(dijkstra(&edges, s, |&w| w).dist, dijkstra(&copy, s, |&w| w).dist);
+            }
+            assert_eq!(kruskal(&edges, |&w| w), kruskal(&copy, |&w| w));
+        }
+    }
+
+    #[test]
+    fn implicit_grid_distances() {
+        let (rows, cols) = (5, 7);
+        let grid = ImplicitGraph::undirected(rows * cols, |v: NodeId| {
+            let (r, c) = (v.0 / cols, v.0 % cols);
+            let mut next = Vec::new();
+            if r > 0 {
+                next.push((NodeId(v.0 - cols), 1));
+            }
+            if r + 1 < rows {
+                next.push((NodeId(v.0 + cols), 1));
+            }
+            if c > 0 {
+                next.push((NodeId(v.0 - 1), 1));
+            }
+            if c + 1 < cols {
+                next.push((NodeId(v.0 + 1), 1));
+            }
+            next
+        });
+        let dist = dijkstra(&grid, NodeId(0), |&w| w).dist;
+        assert!((0..rows * cols).all(|v| dist[v] == Some((v / cols + v % cols) as i64)));
+        assert_eq!(bellman_ford(&grid, NodeId(0), |&w| w).unwrap().dist, dist);
+        assert!(bridges(&grid).is_empty());
+        assert!(is_cyclic_undirected(&grid));
+        let tree = kruskal(&grid, |&w| w);
+        assert_eq!((tree.edges.len(), tree.total_weight), (rows * cols - 1, (rows * cols - 1) as i64));
+    }
+
+    #[test]
+    fn implicit_undirected_edges_share_one_id() {
+        let adjacency =
+            |lists: Vec<Vec<usize>>| move |v: NodeId| lists[v.0].iter().map(|&w| (NodeId(w), 1)).collect::<Vec<_>>();
+        let path = ImplicitGraph::undirected(3, adjacency(vec![vec![1], vec![0, 2], vec![1]]));
+        assert_eq!(bridges(&path).len(), 2);
+        assert!(!is_cyclic_undirected(&path));
+        let from_0: Vec<EdgeId> = path.edges(NodeId(0)).map(|e| e.id()).collect();
+        let from_1: Vec<EdgeId> = path.edges(NodeId(1)).map(|e| e.id()).collect();
+        assert_eq!(from_0[0], from_1[0]);
+        assert_ne!(from_1[0], from_1[1]);
+
+        // parallel edges 0 = 1 and a self-loop at 2, hanging off 1
+        let multi = ImplicitGraph::undirected(3, adjacency(vec![vec![1, 1], vec![0, 2, 0], vec![1, 2]]));
+        let ids: Vec<EdgeId> = multi.edge_references().map(|e| e.id()).collect();
+        let mut distinct = ids.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!((ids.len(), distinct.len()), (7, 4));
+        assert_eq!(bridges(&multi).len(), 1);
+        assert!(is_cyclic_undirected(&multi));
+        assert_eq!(kruskal(&multi, |&w| w).edges.len(), 2);
+
+        let directed = ImplicitGraph::new(3, adjacency(vec![vec![1, 1], vec![0, 2], vec![1]]));
+        let ids: Vec<EdgeId> = directed.edge_references().map(|e| e.id()).collect();
+        let mut distinct = ids.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), ids.len());
+    }
+}
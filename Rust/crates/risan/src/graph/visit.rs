@@ -0,0 +1,208 @@
+//! Graph traits shared by the representations and views, so one algorithm implementation
+//! serves `Graph`, `CsrGraph`, the adaptors in `views` and implicit graphs alike.
+//!
+//! As in petgraph, the traits are implemented for references (`&Graph<N, E>`): the
+//! `Into*` methods take `self` by value and return iterators borrowing the graph, and
+//! algorithms are generic over a `Copy` graph handle `G`.
+
+use super::{EdgeId, Graph, NodeId};
+
+/// Node and edge data types of a graph.
+pub trait GraphBase {
+    type NodeWeight;
+    type EdgeWeight;
+}
+
+impl<G: GraphBase> GraphBase for &G {
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+/// A view of one edge: its id, endpoints and data.
+pub trait EdgeRef: Copy {
+    type Weight;
+    fn id(&self) -> EdgeId;
+    fn source(&self) -> NodeId;
+    fn target(&self) -> NodeId;
+    fn weight(&self) -> &Self::Weight;
+}
+
+/// Borrowed edge of a stored graph.
+#[derive(Debug)]
+pub struct EdgeReference<'a, E> {
+    pub(crate) id: EdgeId,
+    pub(crate) source: NodeId,
+    pub(crate) target: NodeId,
+    pub(crate) weight: &'a E,
+}
+
+impl<E> Clone for EdgeReference<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for EdgeReference<'_, E> {}
+
+impl<E> EdgeRef for EdgeReference<'_, E> {
+    type Weight = E;
+    fn id(&self) -> EdgeId {
+        self.id
+    }
+    fn source(&self) -> NodeId {
+        self.source
+    }
+    fn target(&self) -> NodeId {
+        self.target
+    }
+    fn weight(&self) -> &E {
+        self.weight
+    }
+}
+
+/// Whether edges are directed. Undirected graphs report each edge from both endpoints.
+pub trait GraphProp: GraphBase {
+    fn is_directed(&self) -> bool;
+}
+
+impl<G: GraphProp> GraphProp for &G {
+    fn is_directed(&self) -> bool {
+        (**self).is_directed()
+    }
+}
+
+/// Edge direction relative to a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Outgoing => Direction::Incoming,
+            Direction::Incoming => Direction::Outgoing,
+        }
+    }
+}
+
+/// Node ids are indices below `node_bound`, so per-node data fits in a `Vec`.
+pub trait NodeIndexable: GraphBase {
+    fn node_bound(&self) -> usize;
+}
+
+/// Number of nodes in the graph.
+pub trait NodeCount: GraphBase {
+    fn node_count(&self) -> usize;
+}
+
+/// Iteration over every node.
+pub trait IntoNodeIdentifiers: GraphBase + Copy {
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId>;
+}
+
+/// Iteration over the successors of a node, one per outgoing edge.
+pub trait IntoNeighbors: GraphBase + Copy {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId>;
+}
+
+/// Iteration over the outgoing edges of a node.
+pub trait IntoEdges: IntoNeighbors {
+    type EdgeRef: EdgeRef<Weight = Self::EdgeWeight>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = Self::EdgeRef>;
+}
+
+/// Successors or predecessors of a node.
+pub trait IntoNeighborsDirected: IntoNeighbors {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId>;
+}
+
+/// Outgoing or incoming edges of a node. Edges keep their orientation: incoming edges
+/// have `v` as their target.
+pub trait IntoEdgesDirected: IntoEdges {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = Self::EdgeRef>;
+}
+
+/// Iteration over every edge. Undirected graphs yield each edge in both orientations
+/// (self-loops once), matching what `IntoEdges` reports from each endpoint.
+pub trait IntoEdgeReferences: GraphBase + Copy {
+    type EdgeRef: EdgeRef<Weight = Self::EdgeWeight>;
+    fn edge_references(self) -> impl Iterator<Item = Self::EdgeRef>;
+}
+
+/// Every edge exactly once, for algorithms that treat edges as unordered pairs: of the two
+/// orientations an undirected graph reports, the one leaving the smaller endpoint is kept.
+pub(crate) fn edges_once<G: IntoEdgeReferences + GraphProp>(g: G) -> impl Iterator<Item = G::EdgeRef> {
+    let directed = g.is_directed();
+    g.edge_references().filter(move |e| directed || e.source() <= e.target())
+}
+
+impl<N, E> GraphBase for Graph<N, E> {
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<N, E> GraphProp for Graph<N, E> {
+    fn is_directed(&self) -> bool {
+        true
+    }
+}
+
+impl<N, E> NodeIndexable for &Graph<N, E> {
+    fn node_bound(&self) -> usize {
+        Graph::node_bound(self)
+    }
+}
+
+impl<N, E> NodeCount for &Graph<N, E> {
+    fn node_count(&self) -> usize {
+        Graph::node_count(self)
+    }
+}
+
+impl<N, E> IntoNodeIdentifiers for &Graph<N, E> {
+    fn node_identifiers(self) -> impl Iterator<Item = NodeId> {
+        self.node_ids()
+    }
+}
+
+impl<N, E> IntoNeighbors for &Graph<N, E> {
+    fn neighbors(self, v: NodeId) -> impl Iterator<Item = NodeId> {
+        self.edges_from(v).map(|(_, e)| e.to)
+    }
+}
+
+impl<'a, N, E> IntoEdges for &'a Graph<N, E> {
+    type EdgeRef = EdgeReference<'a, E>;
+    fn edges(self, v: NodeId) -> impl Iterator<Item = EdgeReference<'a, E>> {
+        self.edges_from(v).map(|(id, e)| EdgeReference { id, source: e.from, target: e.to, weight: &e.data })
+    }
+}
+
+impl<'a, N, E> IntoEdgeReferences for &'a Graph<N, E> {
+    type EdgeRef = EdgeReference<'a, E>;
+    fn edge_references(self) -> impl Iterator<Item = EdgeReference<'a, E>> {
+        Graph::edges(self).map(|(id, e)| EdgeReference { id, source: e.from, target: e.to, weight: &e.data })
+    }
+}
+
+impl<N, E> IntoNeighborsDirected for &Graph<N, E> {
+    fn neighbors_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = NodeId> {
+        let edges = IntoEdgesDirected::edges_directed(self, v, dir);
+        edges.map(move |e| if dir == Direction::Outgoing { e.target } else { e.source })
+    }
+}
+
+impl<N, E> IntoEdgesDirected for &Graph<N, E> {
+    fn edges_directed(self, v: NodeId, dir: Direction) -> impl Iterator<Item = Self::EdgeRef> {
+        let ids = match dir {
+            Direction::Outgoing => self.outgoing(v),
+            Direction::Incoming => self.incoming(v),
+        };
+        ids.iter().map(move |&id| {
+            let e = self.edge(id).unwrap();
+            EdgeReference { id, source: e.from, target: e.to, weight: &e.data }
+        })
+    }
+}
@@ -1,8 +1,19 @@
 pub mod modular;
+pub mod dyn_modular;
+pub mod number_theory;
+pub mod bigint;
+pub mod ntt;
+pub mod poly;
+pub mod gf;
 pub mod graph;
+mod rng;
 
 pub use modular::Modular;
-pub use graph::{Graph, NodeId, EdgeId};
+pub use dyn_modular::{DynModular, ModulusContext, Reduction};
+pub use bigint::{BigInt, BigUint};
+pub use poly::Poly;
+pub use gf::GF;
+pub use graph::{Graph, GraphError, NodeId, EdgeId};
 
 pub fn hello() {
     println!("Hello from Risan!");
@@ -1,38 +1,215 @@
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Integer modulo `M`, stored as the canonical representative in `0..M`.
+///
+/// Arithmetic goes through `i128` intermediates, so any modulus up to `i64::MAX` is
+/// safe. Using a non-positive `M` is a compile-time error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Modular<const M: i64>(pub i64);
 
 impl<const M: i64> Modular<M> {
+    const MODULUS_IS_POSITIVE: () = assert!(M > 0, "Modular<M> requires M > 0");
+
     pub fn new(val: i64) -> Self {
-        let rem = val % M;
-        Self(if rem < 0 { rem + M } else { rem })
+        Self::reduce(val as i128)
+    }
+
+    fn reduce(val: i128) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::MODULUS_IS_POSITIVE;
+        Self(val.rem_euclid(M as i128) as i64)
     }
-    
+
     pub fn value(&self) -> i64 {
         self.0
     }
+
+    pub fn modulus() -> i64 {
+        M
+    }
+
+    /// `self^exp` by repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut acc = Self::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    /// Multiplicative inverse via the extended Euclidean algorithm, or `None` when
+    /// `gcd(self, M) != 1`.
+    pub fn inverse(self) -> Option<Self> {
+        let (mut r0, mut r1) = (M as i128, self.0 as i128);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        (r0 == 1).then(|| Self::reduce(t0))
+    }
+
+    /// Legendre symbol `(self / M)` for an odd prime `M`: 0, 1 for quadratic
+    /// residues and -1 otherwise (Euler's criterion).
+    ///
+    /// Panics if the criterion yields anything else, which proves `M` composite (not
+    /// every composite is caught; use `jacobi` for those).
+    pub fn legendre(self) -> i32 {
+        match self.pow(((M - 1) / 2) as u64).0 {
+            0 => 0,
+            1 => 1,
+            x if x == M - 1 => -1,
+            _ => panic!("Legendre symbol needs a prime modulus, {} is composite", M),
+        }
+    }
+
+    /// Jacobi symbol `(self / M)` for odd `M`; equals the Legendre symbol when `M` is prime.
+    pub fn jacobi(self) -> i32 {
+        jacobi(self.0, M)
+    }
+
+    /// A square root modulo a prime `M` (Tonelli–Shanks), or `None` for non-residues.
+    /// The other root is its negation.
+    ///
+    /// For composite `M` the algorithm's invariants fail; every loop is bounded and the
+    /// result is checked, so this returns either a genuine root or `None`, which may
+    /// miss roots that exist. The search for a non-residue stops at Bach's bound
+    /// `2 ln^2 M`, below which (assuming GRH) every prime has one, so composites without
+    /// a usable non-residue give `None` after `O(log^2 M)` candidates rather than `O(M)`.
+    pub fn sqrt(self) -> Option<Self> {
+        if self.0 == 0 || M == 2 {
+            return Some(self);
+        }
+        let root = self.tonelli_shanks()?;
+        (root * root == self).then_some(root)
+    }
+
+    fn tonelli_shanks(self) -> Option<Self> {
+        let one = Self::new(1);
+        if self.pow(((M - 1) / 2) as u64) != one {
+            return None;
+        }
+        // M - 1 = q * 2^s with q odd
+        let s = (M - 1).trailing_zeros();
+        let q = ((M - 1) >> s) as u64;
+        if s == 1 {
+            return Some(self.pow((M as u64 + 1) / 4));
+        }
+        // a non-residue: z^((M-1)/2) = -1
+        let bound = (2.0 * (M as f64).ln().powi(2)) as i64 + 1;
+        let z = (2..M.min(bound + 1)).map(Self::new).find(|z| z.pow(((M - 1) / 2) as u64) == -one)?;
+        let mut m = s;
+        let mut c = z.pow(q);
+        let mut t = self.pow(q);
+        let mut r = self.pow(q.div_ceil(2));
+        while t != one {
+            // least i < m with t^(2^i) = 1; for prime M it always exists
+            let mut i = 0;
+            let mut t2 = t;
+            while t2 != one {
+                t2 *= t2;
+                i += 1;
+                if i == m {
+                    return None;
+                }
+            }
+            let b = c.pow(1 << (m - i - 1));
+            m = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+        Some(r)
+    }
+}
+
+/// Jacobi symbol `(a / n)` for odd positive `n`, by quadratic reciprocity.
+pub fn jacobi(a: i64, n: i64) -> i32 {
+    assert!(n > 0 && n % 2 == 1, "Jacobi symbol needs an odd positive modulus");
+    let mut a = a.rem_euclid(n);
+    let mut n = n;
+    let mut result = 1;
+    while a != 0 {
+        while a % 2 == 0 {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 { result } else { 0 }
 }
 
 impl<const M: i64> Add for Modular<M> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        Self::new(self.0 + rhs.0)
+        Self::reduce(self.0 as i128 + rhs.0 as i128)
     }
 }
 
 impl<const M: i64> Sub for Modular<M> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
-        Self::new(self.0 - rhs.0)
+        Self::reduce(self.0 as i128 - rhs.0 as i128)
     }
 }
 
 impl<const M: i64> Mul for Modular<M> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
-        Self::new(self.0 * rhs.0)
+        Self::reduce(self.0 as i128 * rhs.0 as i128)
+    }
+}
+
+/// Multiplication by the inverse. Panics if `rhs` is not invertible modulo `M`.
+impl<const M: i64> Div for Modular<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse().expect("division by a non-invertible element")
+    }
+}
+
+impl<const M: i64> Neg for Modular<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::reduce(-(self.0 as i128))
+    }
+}
+
+impl<const M: i64> AddAssign for Modular<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: i64> SubAssign for Modular<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: i64> MulAssign for Modular<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: i64> DivAssign for Modular<M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
     }
 }
 
@@ -41,3 +218,97 @@ impl<const M: i64> fmt::Display for Modular<M> {
         write!(f, "{}", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_near_i64_max() {
+        const P: i64 = 9_223_372_036_854_775_783; // largest prime below 2^63
+        let a = Modular::<P>::new(P - 1);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!((a + a).value(), P - 2);
+        assert_eq!((-a).value(), 1);
+        assert_eq!(Modular::<P>::new(-5).value(), P - 5);
+        assert_eq!(Modular::<P>::new(3).pow(P as u64 - 1).value(), 1);
+    }
+
+    #[test]
+    fn inverse_and_division() {
+        let a = Modular::<1_000_000_007>::new(123_456_789);
+        assert_eq!((a * a.inverse().unwrap()).value(), 1);
+        assert_eq!((a / a).value(), 1);
+        assert_eq!(Modular::<12>::new(8).inverse(), None);
+        assert_eq!(Modular::<12>::new(5).inverse(), Some(Modular::new(5)));
+    }
+
+    #[test]
+    fn legendre_matches_jacobi_for_primes() {
+        for v in 0..23 {
+            let x = Modular::<23>::new(v);
+            assert_eq!(x.legendre(), x.jacobi());
+        }
+        assert_eq!(jacobi(2, 15), 1);
+        assert_eq!(jacobi(7, 15), -1);
+        assert_eq!(jacobi(5, 15), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "composite")]
+    fn legendre_rejects_detected_composites() {
+        Modular::<21>::new(2).legendre();
+    }
+
+    fn check_sqrt<const M: i64>() {
+        for v in 0..M.min(2000) {
+            let x = Modular::<M>::new(v);
+            let residue = (0..M.min(2000)).any(|y| (Modular::<M>::new(y) * Modular::<M>::new(y)) == x);
+            match x.sqrt() {
+                Some(r) => assert_eq!(r * r, x),
+                None => assert!(!residue || M > 2000, "{} has a root mod {}", v, M),
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_modulo_primes() {
+        // M - 1 = q 2^s with s = 1, 2, 4, 6 and 1
+        check_sqrt::<2>();
+        check_sqrt::<7>();
+        check_sqrt::<13>();
+        check_sqrt::<17>();
+        check_sqrt::<193>();
+        check_sqrt::<998_244_353>();
+    }
+
+    #[test]
+    fn sqrt_terminates_for_composites() {
+        fn root_or_none<const M: i64>(v: i64) {
+            if let Some(r) = Modular::<M>::new(v).sqrt() {
+                assert_eq!(r * r, Modular::new(v));
+            }
+        }
+        root_or_none::<21>(8);
+        root_or_none::<45>(19);
+        root_or_none::<65>(8);
+        root_or_none::<85>(16);
+        root_or_none::<105>(8);
+        root_or_none::<341>(2);
+        root_or_none::<561>(2);
+        root_or_none::<1105>(2);
+        root_or_none::<1729>(2);
+    }
+
+    #[test]
+    fn sqrt_gives_up_quickly_for_large_composites() {
+        // 3 (10^9 + 7), with M - 1 = 4 q: (M - 1) / 2 is even, so z^((M-1)/2) is 1 modulo 3
+        // for every unit z and no candidate is a "non-residue"; an unbounded search scans all M
+        const M: i64 = 3_000_000_021;
+        for v in [1, 4, 9, 2] {
+            if let Some(r) = Modular::<M>::new(v).sqrt() {
+                assert_eq!(r * r, Modular::new(v));
+            }
+        }
+    }
+}
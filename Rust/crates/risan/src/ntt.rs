@@ -0,0 +1,340 @@
+//! Number-theoretic transform over `Modular<P>` for NTT-friendly primes `P = c * 2^k + 1`,
+//! and convolutions built on it: cyclic, linear, and exact or arbitrary-modulus linear
+//! convolution via three primes and CRT reconstruction.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::modular::Modular;
+use crate::number_theory::{is_prime, primitive_root};
+
+static ROOTS: OnceLock<RwLock<HashMap<i64, i64>>> = OnceLock::new();
+
+/// Smallest primitive root of the prime `p`, computed on first use.
+fn cached_primitive_root(p: i64) -> i64 {
+    let cache = ROOTS.get_or_init(Default::default);
+    if let Some(&g) = cache.read().unwrap().get(&p) {
+        return g;
+    }
+    *cache.write().unwrap().entry(p).or_insert_with(|| primitive_root(p as u64).unwrap() as i64)
+}
+
+/// `119 * 2^23 + 1`, primitive root 3.
+pub const P998244353: i64 = 998_244_353;
+/// `5 * 2^25 + 1`, primitive root 3.
+pub const P167772161: i64 = 167_772_161;
+/// `7 * 2^26 + 1`, primitive root 3.
+pub const P469762049: i64 = 469_762_049;
+
+/// Whether `P` is a prime admitting a transform of length `len` (a power of two).
+pub fn supports<const P: i64>(len: usize) -> bool {
+    len.is_power_of_two() && (P - 1) % len as i64 == 0 && is_prime(P as u64)
+}
+
+/// In-place iterative Cooley–Tukey transform. The inverse includes the `1/n` scaling.
+///
+/// Panics unless `supports::<P>(a.len())`.
+pub fn ntt<const P: i64>(a: &mut [Modular<P>], invert: bool) {
+    let n = a.len();
+    assert!(supports::<P>(n), "no length-{} NTT modulo {}", n, P);
+    let g = Modular::<P>::new(cached_primitive_root(P));
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let mut w_len = g.pow(((P - 1) / len as i64) as u64);
+        if invert {
+            w_len = w_len.inverse().unwrap();
+        }
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut w = Modular::new(1);
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = *v * w;
+                *v = *u - t;
+                *u += t;
+                w *= w_len;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let n_inv = Modular::<P>::new(n as i64).inverse().unwrap();
+        for x in a.iter_mut() {
+            *x *= n_inv;
+        }
+    }
+}
+
+/// Cyclic convolution of two sequences of the same power-of-two length `n`:
+/// `c[k] = sum a[i] b[j]` over `i + j = k (mod n)`. Panics unless `supports::<P>(n)`.
+pub fn cyclic_convolution<const P: i64>(a: &[Modular<P>], b: &[Modular<P>]) -> Vec<Modular<P>> {
+    assert_eq!(a.len(), b.len(), "cyclic convolution needs equal lengths");
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x *= *y;
+    }
+    ntt(&mut fa, true);
+    fa
+}
+
+/// Longest transform modulo `P`: the largest power of two dividing `P - 1`, or 0 if `P` is
+/// not prime.
+pub fn max_len<const P: i64>() -> usize {
+    if P < 2 || !is_prime(P as u64) {
+        return 0;
+    }
+    1 << (P - 1).trailing_zeros().min(usize::BITS - 1)
+}
+
+/// Linear convolution (the product of two coefficient sequences) by NTT.
+///
+/// Products longer than `max_len::<P>()` are assembled from blocks: both operands are cut
+/// into pieces of half that length, each pair is multiplied with one transform and the
+/// results are added at their offsets. This costs `O(n m log(L) / L)` for a maximal length
+/// `L`, so it degrades to quadratic for primes with a small 2-adic part; `convolution_mod`
+/// handles arbitrary moduli at full speed.
+///
+/// Panics if `P` is not prime, or if a product longer than one term is needed and `P`
+/// admits no transform of length 2.
+pub fn convolution<const P: i64>(a: &[Modular<P>], b: &[Modular<P>]) -> Vec<Modular<P>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let out_len = a.len() + b.len() - 1;
+    let len = out_len.next_power_of_two();
+    let max = max_len::<P>();
+    assert!(max > 0, "NTT convolution needs a prime modulus, {} is not prime", P);
+    if len <= max {
+        let mut fa = a.to_vec();
+        fa.resize(len, Modular::new(0));
+        let mut fb = b.to_vec();
+        fb.resize(len, Modular::new(0));
+        let mut c = cyclic_convolution(&fa, &fb);
+        c.truncate(out_len);
+        return c;
+    }
+    assert!(max >= 2, "no length-{} NTT modulo {}, and none of length 2 to split it into", len, P);
+
+    // each block product has fewer than 2 * half = max terms
+    let half = max / 2;
+    let transformed = |v: &[Modular<P>]| {
+        v.chunks(half)
+            .map(|chunk| {
+                let mut f = chunk.to_vec();
+                f.resize(max, Modular::new(0));
+                ntt(&mut f, false);
+                f
+            })
+            .collect::<Vec<_>>()
+    };
+    let (fa, fb) = (transformed(a), transformed(b));
+    let mut out = vec![Modular::new(0); out_len];
+    let mut block = vec![Modular::new(0); max];
+    for (i, x) in fa.iter().enumerate() {
+        for (j, y) in fb.iter().enumerate() {
+            for ((c, &u), &v) in block.iter_mut().zip(x).zip(y) {
+                *c = u * v;
+            }
+            ntt(&mut block, true);
+            let offset = (i + j) * half;
+            for (o, &c) in out[offset..].iter_mut().zip(&block) {
+                *o += c;
+            }
+        }
+    }
+    out
+}
+
+fn convolution_in<const P: i64>(a: &[u64], b: &[u64]) -> Vec<Modular<P>> {
+    let lift = |v: &[u64]| v.iter().map(|&x| Modular::new((x % P as u64) as i64)).collect::<Vec<_>>();
+    convolution(&lift(a), &lift(b))
+}
+
+/// `P998244353 * P167772161 * P469762049`, about `7.87e25` or `2^86`: the modulus of the
+/// CRT reconstruction, which every exact coefficient must stay below.
+pub const CRT_MODULUS: u128 = P998244353 as u128 * P167772161 as u128 * P469762049 as u128;
+
+/// Exact linear convolution of non-negative integer sequences, computed modulo three
+/// NTT primes and recombined with Garner's algorithm.
+///
+/// Panics unless `min(len) * max(a) * max(b) < CRT_MODULUS`, which bounds every output
+/// coefficient below the CRT modulus.
+pub fn convolution_exact(a: &[u64], b: &[u64]) -> Vec<u128> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let (max_a, max_b) = (*a.iter().max().unwrap() as u128, *b.iter().max().unwrap() as u128);
+    let bound = (max_a * max_b).checked_mul(a.len().min(b.len()) as u128);
+    assert!(
+        bound.is_some_and(|x| x < CRT_MODULUS),
+        "convolution_exact: min(len) * max(a) * max(b) exceeds the CRT modulus {}",
+        CRT_MODULUS
+    );
+    let (m0, m1, m2) = (P998244353 as u128, P167772161 as u128, P469762049 as u128);
+    let r0 = convolution_in::<P998244353>(a, b);
+    let r1 = convolution_in::<P167772161>(a, b);
+    let r2 = convolution_in::<P469762049>(a, b);
+    let inv_m0_mod_m1 = Modular::<P167772161>::new(P998244353).inverse().unwrap();
+    let inv_m0m1_mod_m2 = Modular::<P469762049>::new((m0 * m1 % m2) as i64).inverse().unwrap();
+    r0.iter()
+        .zip(&r1)
+        .zip(&r2)
+        .map(|((&x0, &x1), &x2)| {
+            // x = x0 + m0 k1 + m0 m1 k2 with k1 < m1, k2 < m2
+            let k1 = (x1 - Modular::new(x0.0)) * inv_m0_mod_m1;
+            let partial = x0.0 as u128 + m0 * k1.0 as u128;
+            let k2 = (x2 - Modular::new((partial % m2) as i64)) * inv_m0m1_mod_m2;
+            partial + m0 * m1 * k2.0 as u128
+        })
+        .collect()
+}
+
+/// Linear convolution modulo an arbitrary `m >= 1`, through `convolution_exact` on the
+/// residues.
+///
+/// Panics unless `min(len) * (m - 1)^2 < CRT_MODULUS` (about `2^86`): `m` up to `2^30`
+/// allows operands of `2^26` terms, `m` near `2^40` only 65.
+pub fn convolution_mod(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+    assert!(m > 0, "convolution_mod needs a positive modulus");
+    let n = a.len().min(b.len()) as u128;
+    let bound = ((m - 1) as u128 * (m - 1) as u128).checked_mul(n);
+    assert!(
+        bound.is_some_and(|x| x < CRT_MODULUS),
+        "convolution_mod: {} terms modulo {} exceed the CRT modulus {}",
+        n,
+        m,
+        CRT_MODULUS
+    );
+    let reduce = |v: &[u64]| v.iter().map(|&x| x % m).collect::<Vec<_>>();
+    convolution_exact(&reduce(a), &reduce(b)).into_iter().map(|x| (x % m as u128) as u64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn schoolbook<const P: i64>(a: &[Modular<P>], b: &[Modular<P>]) -> Vec<Modular<P>> {
+        let mut out = vec![Modular::new(0); a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        out
+    }
+
+    fn random<const P: i64>(rng: &mut StdRng, len: usize) -> Vec<Modular<P>> {
+        (0..len).map(|_| Modular::new(rng.gen_range(0..P))).collect()
+    }
+
+    #[test]
+    fn transform_lengths() {
+        assert_eq!(max_len::<P998244353>(), 1 << 23);
+        assert_eq!(max_len::<P469762049>(), 1 << 26);
+        assert_eq!(max_len::<97>(), 32);
+        assert_eq!(max_len::<2>(), 1);
+        assert_eq!(max_len::<15>(), 0);
+        assert!(supports::<P167772161>(1 << 25) && !supports::<P167772161>(1 << 26));
+        assert!(!supports::<P998244353>(12));
+    }
+
+    #[test]
+    fn transform_round_trip_and_cyclic_convolution() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for n in [1, 2, 8, 256] {
+            let a = random::<P998244353>(&mut rng, n);
+            let mut f = a.clone();
+            ntt(&mut f, false);
+            ntt(&mut f, true);
+            assert_eq!(f, a);
+        }
+        // (1 + x) (1 + x^3) mod x^4 - 1 = 1 + x + x^3 + x^4 -> 2 + x + x^3
+        let lift = |v: &[i64]| v.iter().map(|&x| Modular::<P998244353>::new(x)).collect::<Vec<_>>();
+        assert_eq!(cyclic_convolution(&lift(&[1, 1, 0, 0]), &lift(&[1, 0, 0, 1])), lift(&[2, 1, 0, 1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "no length-12 NTT")]
+    fn rejects_unsupported_lengths() {
+        ntt(&mut [Modular::<P998244353>::new(1); 12], false);
+    }
+
+    #[test]
+    fn convolution_matches_schoolbook() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for (m, n) in [(1, 1), (1, 9), (7, 5), (100, 33), (300, 300)] {
+            let (a, b) = (random::<P998244353>(&mut rng, m), random::<P998244353>(&mut rng, n));
+            assert_eq!(convolution(&a, &b), schoolbook(&a, &b));
+        }
+        assert!(convolution::<P998244353>(&[], &[Modular::new(1)]).is_empty());
+    }
+
+    #[test]
+    fn long_products_are_split_into_blocks() {
+        // 97 = 3 * 2^5 + 1 allows transforms up to length 32
+        let mut rng = StdRng::seed_from_u64(43);
+        for (m, n) in [(17, 16), (40, 3), (100, 77), (16, 16)] {
+            let (a, b) = (random::<97>(&mut rng, m), random::<97>(&mut rng, n));
+            assert_eq!(convolution(&a, &b), schoolbook(&a, &b), "{}x{}", m, n);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "needs a prime modulus")]
+    fn convolution_rejects_composite_moduli() {
+        convolution(&[Modular::<12>::new(1)], &[Modular::new(1)]);
+    }
+
+    #[test]
+    fn exact_and_modular_convolution() {
+        let mut rng = StdRng::seed_from_u64(44);
+        let a: Vec<u64> = (0..200).map(|_| rng.gen_range(0..1 << 30)).collect();
+        let b: Vec<u64> = (0..150).map(|_| rng.gen_range(0..1 << 30)).collect();
+        let mut expected = vec![0u128; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                expected[i + j] += x as u128 * y as u128;
+            }
+        }
+        assert_eq!(convolution_exact(&a, &b), expected);
+
+        let m = 1_000_000_007u64;
+        let reduced: Vec<u64> = expected.iter().map(|&x| (x % m as u128) as u64).collect();
+        assert_eq!(convolution_mod(&a, &b, m), reduced);
+        // the largest coefficient the bound admits for single terms
+        let big = (1u64 << 43) - 1;
+        assert_eq!(convolution_mod(&[big], &[big], 1 << 43), [1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceed the CRT modulus")]
+    fn convolution_mod_checks_the_crt_bound() {
+        let m = 1u64 << 40;
+        convolution_mod(&[m - 1; 66], &[m - 1; 66], m);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the CRT modulus")]
+    fn convolution_exact_checks_the_crt_bound() {
+        convolution_exact(&[u64::MAX; 2], &[u64::MAX; 2]);
+    }
+}
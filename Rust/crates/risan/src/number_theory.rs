@@ -0,0 +1,520 @@
+//! Elementary and computational number theory on `u64`: sieves, primality,
+//! factorization, arithmetic functions, CRT, discrete logarithms and primitive roots.
+
+use std::collections::HashMap;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit in `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Extended Euclid: `(g, x, y)` with `a x + b y = g = gcd(a, b)` and `g >= 0`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut s0, mut s1) = (1i128, 0i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 < 0 {
+        (r0, s0, t0) = (-r0, -s0, -t0);
+    }
+    (r0 as i64, s0 as i64, t0 as i64)
+}
+
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+pub fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut acc = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// Inverse of `a` modulo `m`, if `gcd(a, m) == 1`.
+pub fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then(|| t0.rem_euclid(m as i128) as u64)
+}
+
+/// All primes `<= n` (sieve of Eratosthenes over odd numbers).
+pub fn primes_up_to(n: u64) -> Vec<u64> {
+    if n < 2 {
+        return Vec::new();
+    }
+    let half = (n as usize - 1) / 2; // odd numbers 3, 5, ..., index i -> 2i + 3
+    let mut composite = vec![false; half];
+    let mut i = 0;
+    while (2 * i + 3) * (2 * i + 3) <= n as usize {
+        if !composite[i] {
+            let p = 2 * i + 3;
+            let mut j = (p * p - 3) / 2;
+            while j < half {
+                composite[j] = true;
+                j += p;
+            }
+        }
+        i += 1;
+    }
+    let mut primes = vec![2];
+    primes.extend((0..half).filter(|&i| !composite[i]).map(|i| 2 * i as u64 + 3));
+    primes
+}
+
+/// Segment length used by `segmented_sieve`, sized to stay in L1/L2 cache.
+const SEGMENT: u64 = 1 << 16;
+
+/// Primes in `lo..hi`, sieving fixed-size segments with the base primes up to `sqrt(hi)`,
+/// so memory is `O(sqrt(hi) + SEGMENT)` regardless of the range length. Ranges much
+/// shorter than `sqrt(hi)` are tested number by number with `is_prime` instead, so any
+/// window of `u64` is cheap to scan; `hi` is exclusive, so `u64::MAX` (composite) is
+/// never reached.
+pub fn segmented_sieve(lo: u64, hi: u64) -> Vec<u64> {
+    let lo = lo.max(2);
+    if lo >= hi {
+        return Vec::new();
+    }
+    if hi - lo < hi.isqrt() / 1024 {
+        return (lo..hi).filter(|&n| is_prime(n)).collect();
+    }
+    sieve_segments(lo, hi)
+}
+
+fn sieve_segments(lo: u64, hi: u64) -> Vec<u64> {
+    let base = primes_up_to(hi.isqrt());
+    let mut primes = Vec::new();
+    let mut composite = vec![false; SEGMENT as usize];
+    let mut start = lo;
+    while start < hi {
+        let end = start.saturating_add(SEGMENT).min(hi);
+        let len = (end - start) as usize;
+        composite[..len].fill(false);
+        for &p in &base {
+            // p <= sqrt(hi) < 2^32, so p * p cannot overflow
+            if p * p >= end {
+                break;
+            }
+            // near u64::MAX there may be no multiple of p at or after start
+            let Some(first) = start.div_ceil(p).checked_mul(p) else { continue };
+            let mut j = first.max(p * p);
+            while j < end {
+                composite[(j - start) as usize] = true;
+                let Some(next) = j.checked_add(p) else { break };
+                j = next;
+            }
+        }
+        primes.extend((0..len).filter(|&i| !composite[i]).map(|i| start + i as u64));
+        start = end;
+    }
+    primes
+}
+
+/// Deterministic Miller–Rabin: the first twelve prime bases are exact for all `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &p in &BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for &a in &BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// A non-trivial factor of a composite `n` (Pollard's rho with Brent's cycle detection
+/// and batched gcds). Returns `n` itself for primes and 1.
+pub fn pollard_rho(n: u64) -> u64 {
+    if n < 4 || is_prime(n) {
+        return n;
+    }
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+    const BATCH: u64 = 128;
+    for c in 1u128.. {
+        let f = |x: u64| ((mul_mod(x, x, n) as u128 + c) % n as u128) as u64;
+        let (mut y, mut r, mut q, mut g) = (2u64, 1u64, 1u64, 1u64);
+        let (mut x, mut ys) = (y, y);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // the batch overshot: retrace one step at a time
+            loop {
+                ys = f(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// Prime factorization as `(prime, exponent)` pairs in increasing order; empty for 0 and 1.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    if n > 1 {
+        // strip small factors first, pollard_rho handles what is left
+        let mut m = n;
+        for p in [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+            while m.is_multiple_of(p) {
+                primes.push(p);
+                m /= p;
+            }
+        }
+        let mut stack = vec![m];
+        while let Some(m) = stack.pop() {
+            if m == 1 {
+                continue;
+            }
+            if is_prime(m) {
+                primes.push(m);
+                continue;
+            }
+            let d = pollard_rho(m);
+            stack.push(d);
+            stack.push(m / d);
+        }
+    }
+    primes.sort_unstable();
+    let mut out: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match out.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => out.push((p, 1)),
+        }
+    }
+    out
+}
+
+/// Euler's totient: the count of `1..=n` coprime to `n`.
+pub fn euler_phi(n: u64) -> u64 {
+    factorize(n).iter().fold(n, |acc, &(p, _)| acc / p * (p - 1))
+}
+
+/// Möbius function: 0 if `n` has a squared prime factor, else `(-1)^k` for `k` prime factors.
+pub fn mobius(n: u64) -> i32 {
+    assert!(n > 0, "mobius(0) is undefined");
+    let f = factorize(n);
+    if f.iter().any(|&(_, e)| e > 1) {
+        0
+    } else if f.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// All positive divisors of `n` in increasing order.
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut out = vec![1u64];
+    for (p, e) in factorize(n) {
+        let len = out.len();
+        let mut pk = 1;
+        for _ in 0..e {
+            pk *= p;
+            for i in 0..len {
+                out.push(out[i] * pk);
+            }
+        }
+    }
+    out.sort_unstable();
+    out
+}
+
+/// Divisor function `sigma_k(n)`: the sum of `d^k` over divisors `d` (`k = 0` counts them),
+/// or `None` if it overflows `u128` (e.g. `k >= 3` for `n` near `u64::MAX`).
+///
+/// Panics if `n` is zero, which every integer divides.
+pub fn divisor_sigma(n: u64, k: u32) -> Option<u128> {
+    assert!(n > 0, "divisor_sigma(0, k) is undefined");
+    factorize(n).into_iter().try_fold(1u128, |acc, (p, e)| {
+        let pk = (p as u128).checked_pow(k)?;
+        // 1 + p^k + ... + p^(ek), Horner style
+        let sum = (0..e).try_fold(1u128, |s, _| s.checked_mul(pk)?.checked_add(1))?;
+        acc.checked_mul(sum)
+    })
+}
+
+/// Chinese Remainder Theorem for arbitrary (not necessarily coprime) moduli.
+///
+/// Solves `x = r_i (mod m_i)` for all `(r_i, m_i)` and returns `(x, lcm)` with
+/// `0 <= x < lcm`, or `None` when the congruences are inconsistent or the combined
+/// modulus overflows `u64`. An empty system gives `(0, 1)`.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut m) = (0u128, 1u128);
+    for &(r, mi) in congruences {
+        assert!(mi > 0, "CRT moduli must be positive");
+        let (r, mi) = ((r % mi) as u128, mi as u128);
+        let g = gcd(m as u64, mi as u64) as u128;
+        let diff = (r as i128 - x as i128).rem_euclid(mi as i128) as u128;
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        // x + m t = r (mod mi)  =>  t = (diff / g) * (m / g)^-1 (mod mi / g)
+        let step = mi / g;
+        let t = if step == 1 {
+            0
+        } else {
+            let inv = inverse_mod((m / g % step) as u64, step as u64)? as u128;
+            (diff / g % step) * inv % step
+        };
+        let lcm = m.checked_mul(step).filter(|&l| l <= u64::MAX as u128)?;
+        x = (x + m * t) % lcm;
+        m = lcm;
+    }
+    Some((x as u64, m as u64))
+}
+
+/// Smallest `x >= 0` with `g^x = h (mod m)`, by baby-step giant-step in
+/// `O(sqrt(m))` time and memory. Requires `gcd(g, m) == 1`.
+pub fn discrete_log(g: u64, h: u64, m: u64) -> Option<u64> {
+    if m == 1 {
+        return Some(0);
+    }
+    let (g, h) = (g % m, h % m);
+    let n = (m as f64).sqrt().ceil() as u64 + 1;
+    let mut baby = HashMap::with_capacity(n as usize);
+    let mut e = 1 % m;
+    for j in 0..n {
+        baby.entry(e).or_insert(j);
+        e = mul_mod(e, g, m);
+    }
+    // e = g^n; giant steps multiply by g^-n
+    let factor = inverse_mod(e, m)?;
+    let mut gamma = h;
+    for i in 0..n {
+        if let Some(&j) = baby.get(&gamma) {
+            return Some(i * n + j);
+        }
+        gamma = mul_mod(gamma, factor, m);
+    }
+    None
+}
+
+/// Multiplicative order of `a` modulo `m`, or `None` if `gcd(a, m) != 1`.
+pub fn multiplicative_order(a: u64, m: u64) -> Option<u64> {
+    if m == 1 {
+        return Some(1);
+    }
+    if gcd(a % m, m) != 1 {
+        return None;
+    }
+    let mut order = euler_phi(m);
+    for (p, _) in factorize(order) {
+        while order.is_multiple_of(p) && pow_mod(a, order / p, m) == 1 {
+            order /= p;
+        }
+    }
+    Some(order)
+}
+
+/// Smallest primitive root modulo `m`, which exists exactly for `m = 1, 2, 4, p^k, 2 p^k`
+/// with `p` an odd prime. Modulo 1 the single residue class is written 1, matching
+/// `multiplicative_order(1, 1) == Some(1)`.
+pub fn primitive_root(m: u64) -> Option<u64> {
+    match m {
+        0 => return None,
+        1 => return Some(1),
+        2 => return Some(1),
+        4 => return Some(3),
+        _ => {}
+    }
+    let odd = if m.is_multiple_of(2) { m / 2 } else { m };
+    let f = factorize(odd);
+    if odd % 2 == 0 || f.len() != 1 {
+        return None;
+    }
+    let phi = euler_phi(m);
+    let prime_factors: Vec<u64> = factorize(phi).into_iter().map(|(p, _)| p).collect();
+    (2..m).find(|&g| gcd(g, m) == 1 && prime_factors.iter().all(|&p| pow_mod(g, phi / p, m) != 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_is_prime(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn gcd_lcm_and_inverses() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        for (a, b) in [(240, 46), (-7, 3), (0, -5), (i64::MAX, i64::MIN + 1)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert!(g >= 0);
+            assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+        assert_eq!(inverse_mod(3, 7), Some(5));
+        assert_eq!(inverse_mod(6, 9), None);
+        assert_eq!(pow_mod(u64::MAX - 1, u64::MAX, u64::MAX), u64::MAX - 1);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn sieves_agree_with_trial_division() {
+        let primes = primes_up_to(10_000);
+        assert_eq!(primes, (0..=10_000).filter(|&n| naive_is_prime(n)).collect::<Vec<_>>());
+        assert!(primes_up_to(1).is_empty());
+        assert_eq!(primes_up_to(2), vec![2]);
+        let lo = 1_000_000_000_000;
+        let window: Vec<u64> = (lo..lo + 300_000).filter(|&n| is_prime(n)).collect();
+        assert_eq!(sieve_segments(lo, lo + 300_000), window);
+        assert_eq!(segmented_sieve(lo, lo + 300_000), window);
+        assert_eq!(segmented_sieve(lo, lo + 500), window.iter().copied().filter(|&p| p < lo + 500).collect::<Vec<_>>());
+        assert_eq!(segmented_sieve(0, 30), primes_up_to(29));
+        assert!(segmented_sieve(10, 10).is_empty());
+    }
+
+    #[test]
+    fn segmented_sieve_near_u64_max() {
+        // the largest primes below 2^64
+        let top = segmented_sieve(u64::MAX - 100, u64::MAX);
+        assert_eq!(top, vec![u64::MAX - 94, u64::MAX - 82, u64::MAX - 58]);
+    }
+
+    #[test]
+    fn miller_rabin_on_hard_cases() {
+        // Carmichael numbers and strong pseudoprimes to several small bases
+        for n in [561, 1105, 1729, 2_047, 3_215_031_751, 3_825_123_056_546_413_051] {
+            assert!(!is_prime(n), "{}", n);
+        }
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(u64::MAX));
+        assert!(!is_prime(4_294_967_291 * 4_294_967_279));
+    }
+
+    #[test]
+    fn factorization() {
+        let semiprime = 4_294_967_291 * 4_294_967_279;
+        assert_eq!(factorize(semiprime), vec![(4_294_967_279, 1), (4_294_967_291, 1)]);
+        let d = pollard_rho(semiprime);
+        assert!(d > 1 && d < semiprime && semiprime % d == 0);
+        assert_eq!(factorize(u64::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65_537, 1), (6_700_417, 1)]);
+        assert_eq!(factorize(1 << 63), vec![(2, 63)]);
+        assert!(factorize(0).is_empty() && factorize(1).is_empty());
+        assert_eq!(pollard_rho(13), 13);
+    }
+
+    #[test]
+    fn arithmetic_functions() {
+        assert_eq!(euler_phi(1), 1);
+        assert_eq!(euler_phi(36), 12);
+        assert_eq!(euler_phi(4_294_967_291), 4_294_967_290);
+        assert_eq!((1..=10).map(mobius).collect::<Vec<_>>(), vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1]);
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert!(divisors(0).is_empty());
+        assert_eq!(divisor_sigma(1, 5), Some(1));
+        assert_eq!(divisor_sigma(12, 0), Some(6));
+        assert_eq!(divisor_sigma(12, 1), Some(28));
+        assert_eq!(divisor_sigma(12, 2), Some(210));
+        assert_eq!(divisor_sigma(u64::MAX, 1), Some(divisors(u64::MAX).iter().map(|&d| d as u128).sum()));
+        assert_eq!(divisor_sigma(u64::MAX, 3), None);
+        assert_eq!(divisor_sigma(2, 127), Some((1 << 127) + 1));
+        assert_eq!(divisor_sigma(2, 128), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "undefined")]
+    fn divisor_sigma_of_zero_panics() {
+        divisor_sigma(0, 1);
+    }
+
+    #[test]
+    fn crt_and_discrete_log() {
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(0, 1 << 40), (1, (1 << 40) - 1), (5, 1 << 30)]), None);
+        assert_eq!(discrete_log(2, 1, 1), Some(0));
+        assert_eq!(discrete_log(3, 13, 17), Some(4));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        let p = 1_000_000_007;
+        assert_eq!(discrete_log(5, pow_mod(5, 123_456_789, p), p), Some(123_456_789));
+    }
+
+    #[test]
+    fn orders_and_primitive_roots() {
+        assert_eq!(multiplicative_order(2, 7), Some(3));
+        assert_eq!(multiplicative_order(2, 6), None);
+        assert_eq!(multiplicative_order(1, 1), Some(1));
+        let moduli = [0, 1, 2, 4, 7, 8, 9, 18, 12, 998_244_353];
+        let roots: Vec<Option<u64>> = moduli.iter().map(|&m| primitive_root(m)).collect();
+        assert_eq!(roots, vec![None, Some(1), Some(1), Some(3), Some(3), None, Some(2), Some(5), None, Some(3)]);
+        for m in [3, 25, 50, 2 * 3_u64.pow(7)] {
+            let g = primitive_root(m).unwrap();
+            assert_eq!(multiplicative_order(g, m), Some(euler_phi(m)));
+        }
+    }
+}
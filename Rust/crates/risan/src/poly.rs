@@ -0,0 +1,688 @@
+//! Univariate polynomials over `Modular<P>` for a prime `P`: Euclidean arithmetic,
+//! irreducibility testing and factorization over GF(P).
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+use num_traits::{One, Pow};
+
+use crate::bigint::BigUint;
+use crate::modular::Modular;
+use crate::ntt;
+use crate::number_theory::factorize;
+use crate::rng::SplitMix64;
+
+/// Operand length from which multiplication goes through the NTT, when `P` allows it.
+const NTT_THRESHOLD: usize = 64;
+
+/// Polynomial with coefficients in `Modular<P>`, lowest degree first. `P` must be prime
+/// for division, gcds and factorization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Poly<const P: i64> {
+    /// No trailing zeros; the zero polynomial is empty.
+    coeffs: Vec<Modular<P>>,
+}
+
+impl<const P: i64> Poly<P> {
+    pub fn new(mut coeffs: Vec<Modular<P>>) -> Self {
+        while coeffs.last().is_some_and(|c| c.0 == 0) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    /// Polynomial from integer coefficients, lowest degree first.
+    pub fn from_ints(coeffs: &[i64]) -> Self {
+        Self::new(coeffs.iter().map(|&c| Modular::new(c)).collect())
+    }
+
+    pub fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::constant(Modular::new(1))
+    }
+
+    pub fn constant(c: Modular<P>) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// The indeterminate `x`.
+    pub fn x() -> Self {
+        Self::monomial(Modular::new(1), 1)
+    }
+
+    /// `c * x^k`.
+    pub fn monomial(c: Modular<P>, k: usize) -> Self {
+        let mut coeffs = vec![Modular::new(0); k + 1];
+        coeffs[k] = c;
+        Self::new(coeffs)
+    }
+
+    pub fn coeffs(&self) -> &[Modular<P>] {
+        &self.coeffs
+    }
+
+    /// Coefficient of `x^i`.
+    pub fn coeff(&self, i: usize) -> Modular<P> {
+        self.coeffs.get(i).copied().unwrap_or(Modular(0))
+    }
+
+    /// Degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Leading coefficient (zero for the zero polynomial).
+    pub fn leading(&self) -> Modular<P> {
+        self.coeffs.last().copied().unwrap_or(Modular(0))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coeffs.len() == 1 && self.coeffs[0].0 == 1
+    }
+
+    /// Scaled to leading coefficient 1; zero stays zero.
+    pub fn monic(&self) -> Self {
+        match self.leading().inverse() {
+            Some(inv) => self.scale(inv),
+            None => self.clone(),
+        }
+    }
+
+    pub fn scale(&self, c: Modular<P>) -> Self {
+        Self::new(self.coeffs.iter().map(|&a| a * c).collect())
+    }
+
+    /// Value at `x` by Horner's rule.
+    pub fn eval(&self, x: Modular<P>) -> Modular<P> {
+        self.coeffs.iter().rev().fold(Modular::new(0), |acc, &c| acc * x + c)
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::new(self.coeffs.iter().enumerate().skip(1).map(|(i, &c)| c * Modular::new(i as i64)).collect())
+    }
+
+    /// Antiderivative with zero constant term. Needs every degree below `P - 1`.
+    pub fn integral(&self) -> Self {
+        let mut coeffs = vec![Modular::new(0)];
+        coeffs.extend(self.coeffs.iter().enumerate().map(|(i, &c)| c / Modular::new(i as i64 + 1)));
+        Self::new(coeffs)
+    }
+
+    /// `self mod x^n`.
+    pub fn truncated(&self, n: usize) -> Self {
+        Self::new(self.coeffs[..n.min(self.coeffs.len())].to_vec())
+    }
+
+    /// `1 / self mod x^n` by Newton iteration `g <- g (2 - f g)`, doubling the precision
+    /// each step. Panics if the constant term is zero.
+    pub fn inverse_series(&self, n: usize) -> Self {
+        let c0 = self.coeff(0).inverse().expect("series inverse needs a non-zero constant term");
+        let mut g = Self::constant(c0);
+        let mut k = 1;
+        while k < n {
+            k *= 2;
+            let fg = (&self.truncated(k) * &g).truncated(k);
+            g = (&g * &(&Self::constant(Modular::new(2)) - &fg)).truncated(k);
+        }
+        g.truncated(n)
+    }
+
+    /// `log(self) mod x^n = integral(f' / f)`. Panics unless the constant term is 1.
+    pub fn log_series(&self, n: usize) -> Self {
+        assert!(self.coeff(0).0 == 1, "series logarithm needs constant term 1");
+        if n == 0 {
+            return Self::zero();
+        }
+        (&self.derivative() * &self.inverse_series(n)).truncated(n - 1).integral()
+    }
+
+    /// `exp(self) mod x^n` by Newton iteration `g <- g (1 - log g + f)`. Panics unless the
+    /// constant term is 0.
+    pub fn exp_series(&self, n: usize) -> Self {
+        assert!(self.coeff(0).0 == 0, "series exponential needs constant term 0");
+        let mut g = Self::one();
+        let mut k = 1;
+        while k < n {
+            k *= 2;
+            let step = &(&Self::one() - &g.log_series(k)) + &self.truncated(k);
+            g = (&g * &step).truncated(k);
+        }
+        g.truncated(n)
+    }
+
+    /// Quotient and remainder. Panics if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let inv = rhs.leading().inverse().expect("polynomial division by zero");
+        let n = rhs.coeffs.len();
+        if self.coeffs.len() < n {
+            return (Self::zero(), self.clone());
+        }
+        let mut r = self.coeffs.clone();
+        let mut q = vec![Modular::new(0); r.len() - n + 1];
+        for k in (0..q.len()).rev() {
+            let c = r[k + n - 1] * inv;
+            q[k] = c;
+            for (ri, &d) in r[k..k + n].iter_mut().zip(&rhs.coeffs) {
+                *ri -= c * d;
+            }
+        }
+        r.truncate(n - 1);
+        (Self::new(q), Self::new(r))
+    }
+
+    /// Monic greatest common divisor (zero only if both are zero).
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = std::mem::replace(&mut b, r);
+        }
+        a.monic()
+    }
+
+    /// Extended Euclid: `(g, s, t)` with `self * s + other * t = g`, `g` monic.
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (Self::one(), Self::zero());
+        let (mut t0, mut t1) = (Self::zero(), Self::one());
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            (r0, r1) = (r1, r);
+            let s2 = &s0 - &(&q * &s1);
+            (s0, s1) = (s1, s2);
+            let t2 = &t0 - &(&q * &t1);
+            (t0, t1) = (t1, t2);
+        }
+        match r0.leading().inverse() {
+            Some(inv) => (r0.scale(inv), s0.scale(inv), t0.scale(inv)),
+            None => (r0, s0, t0),
+        }
+    }
+
+    /// `self^exp mod modulus` by square-and-multiply.
+    pub fn pow_mod(&self, exp: &BigUint, modulus: &Self) -> Self {
+        let mut acc = &Self::one() % modulus;
+        let base = self % modulus;
+        for i in (0..exp.bits()).rev() {
+            acc = &(&acc * &acc) % modulus;
+            if exp.limbs()[(i / 64) as usize] >> (i % 64) & 1 == 1 {
+                acc = &(&acc * &base) % modulus;
+            }
+        }
+        acc
+    }
+
+    /// `x^(P^k) mod modulus`, by `k` Frobenius steps.
+    fn frobenius_power(k: usize, modulus: &Self) -> Self {
+        let p = BigUint::from(P as u64);
+        (0..k).fold(&Self::x() % modulus, |h, _| h.pow_mod(&p, modulus))
+    }
+
+    /// Rabin's test: a polynomial of degree `n >= 1` is irreducible iff it divides
+    /// `x^(P^n) - x` and is coprime to `x^(P^(n/q)) - x` for every prime `q | n`.
+    pub fn is_irreducible(&self) -> bool {
+        let n = match self.degree() {
+            Some(0) | None => return false,
+            Some(1) => return true,
+            Some(n) => n,
+        };
+        let f = self.monic();
+        let x = Self::x();
+        let coprime = factorize(n as u64)
+            .into_iter()
+            .all(|(q, _)| (&Self::frobenius_power(n / q as usize, &f) - &x).gcd(&f).is_one());
+        coprime && (&Self::frobenius_power(n, &f) - &x).is_zero()
+    }
+
+    /// A monic irreducible polynomial of degree `n >= 1`, found deterministically: candidates
+    /// are enumerated in lexicographic order of their lower coefficients, then drawn from a
+    /// fixed-seed generator if none of the first few thousand is irreducible.
+    pub fn irreducible(n: usize) -> Self {
+        assert!(n >= 1, "irreducible polynomials have degree at least 1");
+        let mut rng = SplitMix64::new(0);
+        (0u64..)
+            .map(|k| {
+                let mut coeffs: Vec<Modular<P>> = if k < 4096 {
+                    let mut k = k;
+                    (0..n)
+                        .map(|_| {
+                            let c = k % P as u64;
+                            k /= P as u64;
+                            Modular::new(c as i64)
+                        })
+                        .collect()
+                } else {
+                    (0..n).map(|_| Modular::new(rng.below(P as u64) as i64)).collect()
+                };
+                coeffs.push(Modular::new(1));
+                Self::new(coeffs)
+            })
+            .find(Self::is_irreducible)
+            .unwrap()
+    }
+
+    /// Undo `x -> x^P` for a polynomial whose derivative vanishes. Coefficients are their
+    /// own `P`-th roots in GF(P).
+    fn pth_root(&self) -> Self {
+        Self::new(self.coeffs.iter().step_by(P as usize).copied().collect())
+    }
+
+    /// Square-free decomposition of a monic polynomial: pairwise coprime square-free
+    /// factors `g` with multiplicity `m` such that `self = prod g^m`.
+    pub fn square_free_factorization(&self) -> Vec<(Self, u32)> {
+        let f = self.monic();
+        let mut out = Vec::new();
+        if f.degree().unwrap_or(0) == 0 {
+            return out;
+        }
+        let df = f.derivative();
+        let mut c = f.gcd(&df);
+        let mut w = &f / &c;
+        let mut i = 1;
+        // strips off factors whose multiplicity is not divisible by P
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let fac = &w / &y;
+            if !fac.is_one() {
+                out.push((fac, i));
+            }
+            c = &c / &y;
+            w = y;
+            i += 1;
+        }
+        // what remains is a P-th power
+        if !c.is_one() {
+            out.extend(c.pth_root().square_free_factorization().into_iter().map(|(g, m)| (g, m * P as u32)));
+        }
+        out
+    }
+
+    /// Distinct-degree factorization of a monic square-free polynomial: `(g, d)` where
+    /// `g` is the product of all irreducible factors of degree `d`.
+    pub fn distinct_degree_factorization(&self) -> Vec<(Self, usize)> {
+        let mut f = self.monic();
+        let mut out = Vec::new();
+        let x = Self::x();
+        let p = BigUint::from(P as u64);
+        let mut h = &x % &f;
+        let mut d = 1;
+        while f.degree().unwrap_or(0) >= 2 * d {
+            h = h.pow_mod(&p, &f);
+            let g = (&h - &x).gcd(&f);
+            if !g.is_one() {
+                f = &f / &g;
+                h = &h % &f;
+                out.push((g, d));
+            }
+            d += 1;
+        }
+        if f.degree().unwrap_or(0) > 0 {
+            let d = f.degree().unwrap();
+            out.push((f, d));
+        }
+        out
+    }
+
+    /// Cantor–Zassenhaus splitting of a monic square-free product of irreducibles of
+    /// degree `d` into those irreducibles. The random splitting polynomials are drawn from
+    /// a generator seeded with `seed`, so the result is reproducible.
+    pub fn equal_degree_factorization(&self, d: usize, seed: u64) -> Vec<Self> {
+        self.split_equal_degree(d, &mut SplitMix64::new(seed))
+    }
+
+    fn split_equal_degree(&self, d: usize, rng: &mut SplitMix64) -> Vec<Self> {
+        let f = self.monic();
+        let n = f.degree().unwrap_or(0);
+        if n <= d {
+            return if n == 0 { Vec::new() } else { vec![f] };
+        }
+        // (P^d - 1) / 2 for odd P; for P = 2 the trace map plays the same role
+        let half = (Pow::pow(BigUint::from(P as u64), d as u32) - BigUint::one()) >> 1;
+        loop {
+            let a = Self::new((0..n).map(|_| Modular::new(rng.below(P as u64) as i64)).collect());
+            if a.degree().unwrap_or(0) == 0 {
+                continue;
+            }
+            let mut g = a.gcd(&f);
+            if g.is_one() {
+                let b = if P == 2 {
+                    let mut t = a.clone();
+                    let mut s = a.clone();
+                    for _ in 1..d {
+                        t = &(&t * &t) % &f;
+                        s = &s + &t;
+                    }
+                    s
+                } else {
+                    &a.pow_mod(&half, &f) - &Self::one()
+                };
+                g = b.gcd(&f);
+            }
+            if g.degree().is_some_and(|k| k > 0 && k < n) {
+                let mut out = g.split_equal_degree(d, rng);
+                out.extend((&f / &g).split_equal_degree(d, rng));
+                return out;
+            }
+        }
+    }
+
+    /// Complete factorization over GF(P): the leading coefficient and the monic
+    /// irreducible factors with multiplicities, sorted by degree then coefficients.
+    pub fn factor(&self) -> (Modular<P>, Vec<(Self, u32)>) {
+        let mut rng = SplitMix64::new(0x5eed);
+        let mut out = Vec::new();
+        for (g, m) in self.square_free_factorization() {
+            for (h, d) in g.distinct_degree_factorization() {
+                out.extend(h.split_equal_degree(d, &mut rng).into_iter().map(|e| (e, m)));
+            }
+        }
+        out.sort_by(|(a, _), (b, _)| {
+            a.coeffs.len().cmp(&b.coeffs.len()).then_with(|| a.coeffs.iter().rev().map(|c| c.0).cmp(b.coeffs.iter().rev().map(|c| c.0)))
+        });
+        (self.leading(), out)
+    }
+}
+
+impl<const P: i64> From<Modular<P>> for Poly<P> {
+    fn from(c: Modular<P>) -> Self {
+        Self::constant(c)
+    }
+}
+
+impl<const P: i64> Add<&Poly<P>> for &Poly<P> {
+    type Output = Poly<P>;
+    fn add(self, rhs: &Poly<P>) -> Poly<P> {
+        let n = self.coeffs.len().max(rhs.coeffs.len());
+        Poly::new((0..n).map(|i| self.coeff(i) + rhs.coeff(i)).collect())
+    }
+}
+
+impl<const P: i64> Sub<&Poly<P>> for &Poly<P> {
+    type Output = Poly<P>;
+    fn sub(self, rhs: &Poly<P>) -> Poly<P> {
+        let n = self.coeffs.len().max(rhs.coeffs.len());
+        Poly::new((0..n).map(|i| self.coeff(i) - rhs.coeff(i)).collect())
+    }
+}
+
+impl<const P: i64> Mul<&Poly<P>> for &Poly<P> {
+    type Output = Poly<P>;
+    fn mul(self, rhs: &Poly<P>) -> Poly<P> {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly::zero();
+        }
+        let (a, b) = (&self.coeffs, &rhs.coeffs);
+        if a.len().min(b.len()) >= NTT_THRESHOLD && ntt::supports::<P>((a.len() + b.len() - 1).next_power_of_two()) {
+            return Poly::new(ntt::convolution(a, b));
+        }
+        let mut out = vec![Modular::new(0); a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        Poly::new(out)
+    }
+}
+
+/// Euclidean quotient. Panics if `rhs` is zero.
+impl<const P: i64> Div<&Poly<P>> for &Poly<P> {
+    type Output = Poly<P>;
+    fn div(self, rhs: &Poly<P>) -> Poly<P> {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<const P: i64> Rem<&Poly<P>> for &Poly<P> {
+    type Output = Poly<P>;
+    fn rem(self, rhs: &Poly<P>) -> Poly<P> {
+        self.div_rem(rhs).1
+    }
+}
+
+impl<const P: i64> Neg for &Poly<P> {
+    type Output = Poly<P>;
+    fn neg(self) -> Poly<P> {
+        Poly::new(self.coeffs.iter().map(|&c| -c).collect())
+    }
+}
+
+impl<const P: i64> Neg for Poly<P> {
+    type Output = Poly<P>;
+    fn neg(self) -> Poly<P> {
+        -&self
+    }
+}
+
+// Owned and mixed operands forward to the by-reference impls.
+macro_rules! forward_binops {
+    ($($op:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl<const P: i64> $op<Poly<P>> for Poly<P> {
+            type Output = Poly<P>;
+            fn $method(self, rhs: Poly<P>) -> Poly<P> {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl<const P: i64> $op<&Poly<P>> for Poly<P> {
+            type Output = Poly<P>;
+            fn $method(self, rhs: &Poly<P>) -> Poly<P> {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl<const P: i64> $op<Poly<P>> for &Poly<P> {
+            type Output = Poly<P>;
+            fn $method(self, rhs: Poly<P>) -> Poly<P> {
+                self.$method(&rhs)
+            }
+        }
+
+        impl<const P: i64> $assign<Poly<P>> for Poly<P> {
+            fn $assign_method(&mut self, rhs: Poly<P>) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl<const P: i64> $assign<&Poly<P>> for Poly<P> {
+            fn $assign_method(&mut self, rhs: &Poly<P>) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    )*};
+}
+
+forward_binops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+/// Writes terms from the highest degree down, e.g. `x^3 + 2x + 1`.
+impl<const P: i64> fmt::Display for Poly<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (k, c) in self.coeffs.iter().enumerate().rev() {
+            if c.0 == 0 {
+                continue;
+            }
+            if !first {
+                write!(f, " + ")?;
+            }
+            first = false;
+            match (c.0, k) {
+                (_, 0) => write!(f, "{}", c.0)?,
+                (1, 1) => write!(f, "x")?,
+                (1, _) => write!(f, "x^{}", k)?,
+                (_, 1) => write!(f, "{}x", c.0)?,
+                _ => write!(f, "{}x^{}", c.0, k)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::ntt::P998244353;
+
+    fn random<const P: i64>(rng: &mut StdRng, len: usize) -> Poly<P> {
+        Poly::new((0..len).map(|_| Modular::new(rng.gen_range(0..P))).collect())
+    }
+
+    fn schoolbook<const P: i64>(a: &Poly<P>, b: &Poly<P>) -> Poly<P> {
+        let mut out = vec![Modular::new(0); (a.coeffs.len() + b.coeffs.len()).saturating_sub(1)];
+        for (i, &x) in a.coeffs.iter().enumerate() {
+            for (j, &y) in b.coeffs.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        Poly::new(out)
+    }
+
+    #[test]
+    fn construction_and_display() {
+        let p = Poly::<7>::from_ints(&[1, -1, 0, 1, 0, 0]);
+        assert_eq!(p.degree(), Some(3));
+        assert_eq!(p.coeff(1), Modular::new(6));
+        assert_eq!(p.coeff(10), Modular::new(0));
+        assert_eq!(p.to_string(), "x^3 + 6x + 1");
+        assert_eq!(Poly::<7>::zero().degree(), None);
+        assert_eq!(Poly::<7>::zero().to_string(), "0");
+        assert_eq!(Poly::<7>::monomial(Modular::new(3), 2).to_string(), "3x^2");
+        assert_eq!(p.eval(Modular::new(2)), Modular::new(0));
+        assert_eq!(p.scale(Modular::new(2)).monic(), p);
+        assert_eq!(p.derivative().integral(), &p - &Poly::one());
+    }
+
+    #[test]
+    fn ring_laws_and_division() {
+        let mut rng = StdRng::seed_from_u64(40);
+        for _ in 0..100 {
+            let (a, b, c) = (random::<13>(&mut rng, 12), random::<13>(&mut rng, 7), random::<13>(&mut rng, 5));
+            assert_eq!(&a * &(&b + &c), &(&a * &b) + &(&a * &c));
+            assert_eq!(&(&a - &b) + &b, a);
+            if b.is_zero() {
+                continue;
+            }
+            let (q, r) = a.div_rem(&b);
+            assert!(r.degree() < b.degree());
+            assert_eq!(&(&q * &b) + &r, a);
+            let (g, s, t) = a.extended_gcd(&b);
+            assert_eq!(&(&a * &s) + &(&b * &t), g);
+            assert_eq!(g, a.gcd(&b));
+            assert!((&a % &g).is_zero() && (&b % &g).is_zero());
+        }
+    }
+
+    #[test]
+    fn ntt_multiplication_matches_schoolbook() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for (m, n) in [(64, 64), (100, 300), (63, 200), (513, 70)] {
+            let (a, b) = (random::<P998244353>(&mut rng, m), random::<P998244353>(&mut rng, n));
+            assert_eq!(&a * &b, schoolbook(&a, &b), "{}x{}", m, n);
+        }
+        // no transform of the needed length modulo 10^9 + 7
+        let (a, b) = (random::<1_000_000_007>(&mut rng, 80), random::<1_000_000_007>(&mut rng, 80));
+        assert_eq!(&a * &b, schoolbook(&a, &b));
+    }
+
+    #[test]
+    fn power_series() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut f = random::<P998244353>(&mut rng, 40);
+        f.coeffs[0] = Modular::new(1);
+        let n = 50;
+        assert!((&f * &f.inverse_series(n)).truncated(n).is_one());
+        assert_eq!(f.log_series(n).exp_series(n), f.truncated(n));
+        // exp(x) = sum x^k / k!
+        let e = Poly::<P998244353>::x().exp_series(8);
+        let mut factorial = Modular::new(1);
+        for k in 0..8 {
+            assert_eq!(e.coeff(k) * factorial, Modular::new(1));
+            factorial *= Modular::new(k as i64 + 1);
+        }
+    }
+
+    #[test]
+    fn pow_mod_matches_repeated_multiplication() {
+        let mut rng = StdRng::seed_from_u64(43);
+        let (a, m) = (random::<5>(&mut rng, 6), Poly::<5>::from_ints(&[2, 0, 1, 3, 1]));
+        let mut expected = Poly::one();
+        for e in 0..30u64 {
+            assert_eq!(a.pow_mod(&BigUint::from(e), &m), expected);
+            expected = &(&expected * &a) % &m;
+        }
+    }
+
+    #[test]
+    fn irreducibility() {
+        // monic irreducible quadratics over GF(3): (9 - 3) / 2 of them
+        let count = (0..9).filter(|k| Poly::<3>::from_ints(&[k % 3, k / 3, 1]).is_irreducible()).count();
+        assert_eq!(count, 3);
+        assert!(Poly::<2>::from_ints(&[1, 1, 0, 0, 1]).is_irreducible());
+        // x^4 + 1 splits into quadratics over every prime field
+        assert!(!Poly::<7>::from_ints(&[1, 0, 0, 0, 1]).is_irreducible());
+        assert!(!Poly::<7>::from_ints(&[3]).is_irreducible());
+        for n in 1..7 {
+            let f = Poly::<11>::irreducible(n);
+            assert_eq!(f.degree(), Some(n));
+            assert!(f.is_irreducible() && f.leading().0 == 1);
+        }
+    }
+
+    #[test]
+    fn factorization() {
+        let f1 = Poly::<5>::from_ints(&[1, 1]);
+        let f2 = Poly::<5>::from_ints(&[2, 0, 1]);
+        let f3 = Poly::<5>::irreducible(3);
+        let f4 = Poly::<5>::from_ints(&[3, 1]);
+        let power = |f: &Poly<5>, k: usize| (0..k).fold(Poly::one(), |acc, _| &acc * f);
+        let product = power(&f1, 2) * &f2 * power(&f3, 3) * power(&f4, 5);
+        let (lead, factors) = product.scale(Modular::new(3)).factor();
+        assert_eq!(lead, Modular::new(3));
+        let mut rebuilt = Poly::one();
+        for (g, m) in &factors {
+            assert!(g.is_irreducible() && g.leading().0 == 1);
+            for _ in 0..*m {
+                rebuilt = &rebuilt * g;
+            }
+        }
+        assert_eq!(rebuilt, product);
+        let shape: Vec<(usize, u32)> = factors.iter().map(|(g, m)| (g.degree().unwrap(), *m)).collect();
+        assert_eq!(shape, [(1, 2), (1, 5), (2, 1), (3, 3)]);
+
+        // x^8 - x over GF(2) is the product of all irreducibles of degree 1 and 3
+        let (_, factors) = Poly::<2>::from_ints(&[0, 1, 0, 0, 0, 0, 0, 0, 1]).factor();
+        let degrees: Vec<usize> = factors.iter().map(|(g, _)| g.degree().unwrap()).collect();
+        assert_eq!(degrees, [1, 1, 3, 3]);
+    }
+
+    #[test]
+    fn equal_degree_splitting_is_reproducible() {
+        let quadratics = [[1, 0, 1], [3, 1, 1], [2, 0, 1]].map(|c| Poly::<7>::from_ints(&c));
+        let product = quadratics.iter().fold(Poly::one(), |acc, q| &acc * q);
+        let mut split = product.equal_degree_factorization(2, 1);
+        assert_eq!(split, product.equal_degree_factorization(2, 1));
+        split.sort_by_key(|g| g.coeffs.iter().map(|c| c.0).collect::<Vec<_>>());
+        let mut expected = quadratics.to_vec();
+        expected.sort_by_key(|g| g.coeffs.iter().map(|c| c.0).collect::<Vec<_>>());
+        assert_eq!(split, expected);
+    }
+}
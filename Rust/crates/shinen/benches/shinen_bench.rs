@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use math_universe_shinen::MultiVector;
+use math_universe_shinen::{Bivector, MultiVector, MultiVectorBatch, Rotor, Vector};
 use rand::prelude::*;
 
 fn benchmark_geometric_product(c: &mut Criterion) {
@@ -20,5 +20,45 @@ fn benchmark_geometric_product(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_geometric_product);
+fn benchmark_typed_rotor(c: &mut Criterion) {
+    // Same workload using grade-specific types
+    let mut rng = rand::thread_rng();
+    let vecs: Vec<Vector> = (0..10000).map(|_| Vector::new(rng.gen(), rng.gen(), rng.gen())).collect();
+
+    let rotor = Rotor::from_plane_angle(Bivector::new(1.0, 0.0, 0.0), 0.2);
+
+    c.bench_function("Rotate 10k Vectors (typed Rotor)", |b| {
+        b.iter(|| {
+            for v in &vecs {
+                black_box(rotor.apply(*v));
+            }
+        })
+    });
+}
+
+fn benchmark_batch(c: &mut Criterion) {
+    // Same rotation as the per-element benchmark, on the SoA batch
+    let mut rng = rand::thread_rng();
+    let batch: MultiVectorBatch = (0..10000).map(|_| {
+        MultiVector::new([rng.gen(), rng.gen(), rng.gen(), rng.gen(), rng.gen(), rng.gen(), rng.gen(), rng.gen()])
+    }).collect();
+    let large: MultiVectorBatch = (0..1_000_000).map(|i| batch.get(i % batch.len()).unwrap()).collect();
+
+    let rotor = Rotor::from(MultiVector::new([1.0, 0.0, 0.0, 0.0, 0.1, 0.0, 0.0, 0.0]));
+
+    c.bench_function("Rotate 10k Vectors (batch)", |b| {
+        b.iter(|| black_box(batch.rotate(rotor)))
+    });
+    c.bench_function("Geometric product 10k (batch)", |b| {
+        b.iter(|| black_box(batch.geometric_product(&batch)))
+    });
+    c.bench_function("Rotate 1M Vectors (batch)", |b| {
+        b.iter(|| black_box(large.rotate(rotor)))
+    });
+    c.bench_function("Rotate 1M Vectors (parallel batch)", |b| {
+        b.iter(|| black_box(large.par_rotate(rotor)))
+    });
+}
+
+criterion_group!(benches, benchmark_geometric_product, benchmark_typed_rotor, benchmark_batch);
 criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Kepler problem `x'' = -x / |x|^3` integrated with leapfrog, checking energy, the
+//! angular momentum bivector `x ^ v` and the Laplace–Runge–Lenz vector.
+//!
+//! Run with `cargo run -p math_universe_shinen --example kepler`.
+
+use math_universe_shinen::integrate::leapfrog;
+use math_universe_shinen::{Bivector, Vector};
+
+fn energy(x: Vector, v: Vector) -> f64 {
+    0.5 * v.norm_squared() - 1.0 / x.norm()
+}
+
+/// Eccentricity vector `v x L - x / |x|` (with `L` the dual of `x ^ v`).
+fn lrl(x: Vector, v: Vector) -> Vector {
+    v.cross(x.cross(v)) - x.normalize()
+}
+
+fn distance(a: Bivector, b: Bivector) -> f64 {
+    (a - b).norm()
+}
+
+fn main() {
+    // eccentric orbit (e = 0.6) tilted out of the e12 plane
+    let mut x = Vector::new(0.4, 0.0, 0.0);
+    // perihelion 0.4 and semi-major axis 1: vis-viva speed sqrt((1 + e) / (1 - e)) = 2
+    let mut v = Vector::new(0.0, 1.6, 0.8).normalize() * 2.0;
+    let (e0, l0, a0) = (energy(x, v), x.wedge(v), lrl(x, v));
+    let period = 2.0 * std::f64::consts::PI * (-0.5 / e0).powf(1.5);
+    let steps = 100_000;
+    let h = 10.0 * period / steps as f64;
+
+    let (mut energy_drift, mut momentum_drift) = (0.0f64, 0.0f64);
+    for _ in 0..steps {
+        (x, v) = leapfrog(x, v, h, |x| x * (-1.0 / x.norm().powi(3)));
+        energy_drift = energy_drift.max((energy(x, v) - e0).abs() / e0.abs());
+        momentum_drift = momentum_drift.max(distance(x.wedge(v), l0));
+    }
+    let precession = (lrl(x, v) - a0).norm();
+    println!("10 orbits, eccentricity {:.3}, period {:.4}", a0.norm(), period);
+    println!("relative energy error (bounded): {:.2e}", energy_drift);
+    println!("angular momentum bivector drift: {:.2e}", momentum_drift);
+    println!("LRL vector drift (perihelion precession): {:.2e}", precession);
+    assert!(energy_drift < 1e-3 && momentum_drift < 1e-12 && precession < 1e-2);
+}
@@ -0,0 +1,26 @@
+//! Prints the derived Cl(3,0) Cayley table and product expansion, then checks the
+//! hand-written `MultiVector` product against it.
+//!
+//! Run with `cargo run -p math_universe_shinen --example product_tables`.
+
+use math_universe_shinen::blade::{verify_product, BasisLayout, CayleyTable, Signature};
+use math_universe_shinen::MultiVector;
+
+fn main() {
+    let table = CayleyTable::new(&Signature::new(3, 0, 0).metric(), BasisLayout::cl3());
+    println!("Cl(3,0) Cayley table:\n{}", table);
+
+    println!("Generated product:");
+    for (k, expr) in table.product_code("a", "b").iter().enumerate() {
+        println!("c[{}] = {};", k, expr);
+    }
+
+    let checked = verify_product(&table, |a, b| {
+        let (a, b) = (MultiVector::new(a.try_into().unwrap()), MultiVector::new(b.try_into().unwrap()));
+        (a * b).coeffs.to_vec()
+    });
+    match checked {
+        Ok(()) => println!("\nMultiVector product matches the derived table."),
+        Err(e) => panic!("MultiVector product mismatch: {}", e),
+    }
+}
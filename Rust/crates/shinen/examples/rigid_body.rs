@@ -0,0 +1,35 @@
+//! Torque-free asymmetric top integrated on rotors, checking the conserved energy and
+//! angular momentum for each Lie-group method.
+//!
+//! Run with `cargo run -p math_universe_shinen --example rigid_body`.
+
+use math_universe_shinen::integrate::{FreeRigidBody, LieMethod};
+use math_universe_shinen::{Bivector, Rotor, Vector};
+
+fn main() {
+    // spin near the unstable intermediate axis (Dzhanibekov effect)
+    let inertia = Vector::new(1.0, 2.0, 3.0);
+    let omega = Bivector::new(0.01, 0.01, 2.0);
+    let (h, steps) = (1e-3, 20_000);
+
+    for method in [LieMethod::Euler, LieMethod::CrouchGrossman3, LieMethod::Rkmk4] {
+        let mut body = FreeRigidBody::new(inertia, Rotor::IDENTITY, omega);
+        let energy = body.kinetic_energy();
+        let momentum = body.body_momentum().norm();
+        let (mut energy_drift, mut momentum_drift, mut unit_drift) = (0.0f64, 0.0f64, 0.0f64);
+        for _ in 0..steps {
+            body.step(method, h);
+            energy_drift = energy_drift.max((body.kinetic_energy() - energy).abs() / energy);
+            momentum_drift = momentum_drift.max((body.body_momentum().norm() - momentum).abs() / momentum);
+            unit_drift = unit_drift.max((body.attitude.norm() - 1.0).abs());
+        }
+        println!(
+            "{:?}: relative energy drift {:.2e}, |L| drift {:.2e}, |R| - 1 {:.2e}",
+            method, energy_drift, momentum_drift, unit_drift
+        );
+        assert!(momentum_drift < 1e-12 && unit_drift < 1e-12);
+        if method != LieMethod::Euler {
+            assert!(energy_drift < 1e-6, "{:?} energy drift {}", method, energy_drift);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::{Add, Mul, Sub};
+
+use rayon::prelude::*;
+
+use crate::grade::Rotor;
+use crate::multivector::{geometric_product, MultiVector};
+
+/// Number of elements processed together by the SIMD kernels (4 x f64 = one AVX register).
+const LANES: usize = 4;
+
+/// Elements per rayon task in the `par_*` variants.
+const PAR_CHUNK: usize = 4096;
+
+/// A batch of Cl(3,0) multivectors in structure-of-arrays layout.
+///
+/// `coeff(k)[i]` is the k-th coefficient (same basis order as `MultiVector`) of element `i`,
+/// so each coefficient is contiguous in memory and the kernels can work on several elements
+/// per instruction. The eight arrays always have the same length.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MultiVectorBatch {
+    coeffs: [Vec<f64>; 8],
+}
+
+/// A group of SIMD lanes. Elementwise ops over a fixed-size, aligned array are reliably
+/// lowered to vector instructions, which lets the scalar product expansion run unchanged.
+#[derive(Clone, Copy)]
+#[repr(align(32))]
+struct Lanes([f64; LANES]);
+
+impl Lanes {
+    #[inline(always)]
+    fn splat(v: f64) -> Self {
+        Self([v; LANES])
+    }
+}
+
+impl Add for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        let mut r = self.0;
+        for (x, y) in r.iter_mut().zip(rhs.0) {
+            *x += y;
+        }
+        Self(r)
+    }
+}
+
+impl Sub for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        let mut r = self.0;
+        for (x, y) in r.iter_mut().zip(rhs.0) {
+            *x -= y;
+        }
+        Self(r)
+    }
+}
+
+impl Mul for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        let mut r = self.0;
+        for (x, y) in r.iter_mut().zip(rhs.0) {
+            *x *= y;
+        }
+        Self(r)
+    }
+}
+
+/// The sandwich `R x R~` of an even rotor is linear and grade-preserving, so it reduces
+/// to a scale on the scalar and pseudoscalar parts and a 3x3 matrix on each of the
+/// vector and bivector parts.
+struct RotorMap {
+    scale: f64,
+    vector: [[f64; 3]; 3],
+    bivector: [[f64; 3]; 3],
+}
+
+impl RotorMap {
+    fn new(rotor: Rotor) -> Self {
+        let r = MultiVector::from(rotor);
+        let mut vector = [[0.0; 3]; 3];
+        let mut bivector = [[0.0; 3]; 3];
+        for j in 0..3 {
+            let mut v = MultiVector::ZERO;
+            v.coeffs[1 + j] = 1.0;
+            let mut b = MultiVector::ZERO;
+            b.coeffs[4 + j] = 1.0;
+            let (v, b) = (v.rotate(r), b.rotate(r));
+            for i in 0..3 {
+                vector[i][j] = v.coeffs[1 + i];
+                bivector[i][j] = b.coeffs[4 + i];
+            }
+        }
+        Self { scale: rotor.norm_squared(), vector, bivector }
+    }
+
+    #[inline(always)]
+    fn apply<T>(&self, x: &[T; 8], splat: impl Fn(f64) -> T) -> [T; 8]
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T>,
+    {
+        let m = |row: &[f64; 3], a: T, b: T, c: T| splat(row[0]) * a + splat(row[1]) * b + splat(row[2]) * c;
+        let (v, b) = (&self.vector, &self.bivector);
+        [
+            splat(self.scale) * x[0],
+            m(&v[0], x[1], x[2], x[3]),
+            m(&v[1], x[1], x[2], x[3]),
+            m(&v[2], x[1], x[2], x[3]),
+            m(&b[0], x[4], x[5], x[6]),
+            m(&b[1], x[4], x[5], x[6]),
+            m(&b[2], x[4], x[5], x[6]),
+            splat(self.scale) * x[7],
+        ]
+    }
+}
+
+impl MultiVectorBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Batch of `len` zero multivectors.
+    pub fn zeros(len: usize) -> Self {
+        Self { coeffs: std::array::from_fn(|_| vec![0.0; len]) }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { coeffs: std::array::from_fn(|_| Vec::with_capacity(capacity)) }
+    }
+
+    /// Batch from its coefficient arrays, `coeffs[k][i]` being coefficient `k` of element `i`.
+    ///
+    /// Panics if the arrays have different lengths.
+    pub fn from_coeffs(coeffs: [Vec<f64>; 8]) -> Self {
+        let len = coeffs[0].len();
+        assert!(coeffs.iter().all(|c| c.len() == len), "coefficient arrays differ in length");
+        Self { coeffs }
+    }
+
+    /// The k-th coefficient of every element. Panics if `k >= 8`.
+    pub fn coeff(&self, k: usize) -> &[f64] {
+        &self.coeffs[k]
+    }
+
+    /// All eight coefficient arrays.
+    pub fn coeffs(&self) -> [&[f64]; 8] {
+        self.slices(0, self.len())
+    }
+
+    /// Take the coefficient arrays back out.
+    pub fn into_coeffs(self) -> [Vec<f64>; 8] {
+        self.coeffs
+    }
+
+    pub fn len(&self) -> usize {
+        self.coeffs[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, mv: MultiVector) {
+        for (k, c) in self.coeffs.iter_mut().enumerate() {
+            c.push(mv.coeffs[k]);
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<MultiVector> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(MultiVector::new(std::array::from_fn(|k| self.coeffs[k][index])))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = MultiVector> + '_ {
+        (0..self.len()).map(move |i| MultiVector::new(std::array::from_fn(|k| self.coeffs[k][i])))
+    }
+
+    /// Convert back to array-of-structs layout.
+    pub fn to_vec(&self) -> Vec<MultiVector> {
+        self.iter().collect()
+    }
+
+    /// Elementwise geometric product `self[i] * rhs[i]`.
+    ///
+    /// Panics if the batches have different lengths.
+    pub fn geometric_product(&self, rhs: &Self) -> Self {
+        assert_eq!(self.len(), rhs.len(), "batch length mismatch");
+        let mut out = Self::zeros(self.len());
+        product_kernel(self.slices(0, self.len()), rhs.slices(0, rhs.len()), out.slices_mut(self.len()).remove(0));
+        out
+    }
+
+    /// Rayon-parallel variant of [`geometric_product`](Self::geometric_product).
+    pub fn par_geometric_product(&self, rhs: &Self) -> Self {
+        assert_eq!(self.len(), rhs.len(), "batch length mismatch");
+        let mut out = Self::zeros(self.len());
+        out.slices_mut(PAR_CHUNK)
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(n, dst)| {
+                let start = n * PAR_CHUNK;
+                let len = dst[0].len();
+                product_kernel(self.slices(start, len), rhs.slices(start, len), dst);
+            });
+        out
+    }
+
+    /// Apply `R x R~` to every element.
+    pub fn rotate(&self, rotor: Rotor) -> Self {
+        let map = RotorMap::new(rotor);
+        let mut out = Self::zeros(self.len());
+        rotor_kernel(&map, self.slices(0, self.len()), out.slices_mut(self.len()).remove(0));
+        out
+    }
+
+    /// Rayon-parallel variant of [`rotate`](Self::rotate).
+    pub fn par_rotate(&self, rotor: Rotor) -> Self {
+        let map = RotorMap::new(rotor);
+        let mut out = Self::zeros(self.len());
+        out.slices_mut(PAR_CHUNK)
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(n, dst)| {
+                let len = dst[0].len();
+                rotor_kernel(&map, self.slices(n * PAR_CHUNK, len), dst);
+            });
+        out
+    }
+
+    fn slices(&self, start: usize, len: usize) -> [&[f64]; 8] {
+        std::array::from_fn(|k| &self.coeffs[k][start..start + len])
+    }
+
+    /// Split all coefficient arrays into aligned chunks of at most `chunk` elements.
+    fn slices_mut(&mut self, chunk: usize) -> Vec<[&mut [f64]; 8]> {
+        let chunk = chunk.max(1);
+        let mut chunks = Vec::with_capacity(self.len() / chunk + 1);
+        let mut rest: [&mut [f64]; 8] = self.coeffs.each_mut().map(|c| c.as_mut_slice());
+        loop {
+            let n = rest[0].len().min(chunk);
+            let mut head: [&mut [f64]; 8] = Default::default();
+            for (h, r) in head.iter_mut().zip(rest.iter_mut()) {
+                let (a, b) = std::mem::take(r).split_at_mut(n);
+                *h = a;
+                *r = b;
+            }
+            chunks.push(head);
+            if rest[0].is_empty() {
+                return chunks;
+            }
+        }
+    }
+}
+
+/// Gather lane group `i` of every coefficient.
+#[inline(always)]
+fn load(src: &[&[f64]; 8], i: usize) -> [Lanes; 8] {
+    std::array::from_fn(|k| Lanes(src[k][i..i + LANES].try_into().unwrap()))
+}
+
+#[inline(always)]
+fn store(dst: &mut [&mut [f64]; 8], i: usize, v: [Lanes; 8]) {
+    for k in 0..8 {
+        dst[k][i..i + LANES].copy_from_slice(&v[k].0);
+    }
+}
+
+fn product_kernel(a: [&[f64]; 8], b: [&[f64]; 8], mut dst: [&mut [f64]; 8]) {
+    let len = dst[0].len();
+    let simd_len = len - len % LANES;
+    for i in (0..simd_len).step_by(LANES) {
+        let c = geometric_product(&load(&a, i), &load(&b, i));
+        store(&mut dst, i, c);
+    }
+    for i in simd_len..len {
+        let c = geometric_product(&std::array::from_fn(|k| a[k][i]), &std::array::from_fn(|k| b[k][i]));
+        for k in 0..8 {
+            dst[k][i] = c[k];
+        }
+    }
+}
+
+fn rotor_kernel(map: &RotorMap, src: [&[f64]; 8], mut dst: [&mut [f64]; 8]) {
+    let len = dst[0].len();
+    let simd_len = len - len % LANES;
+    for i in (0..simd_len).step_by(LANES) {
+        let c = map.apply(&load(&src, i), Lanes::splat);
+        store(&mut dst, i, c);
+    }
+    for i in simd_len..len {
+        let c = map.apply(&std::array::from_fn(|k| src[k][i]), |v| v);
+        for k in 0..8 {
+            dst[k][i] = c[k];
+        }
+    }
+}
+
+impl From<&[MultiVector]> for MultiVectorBatch {
+    fn from(mvs: &[MultiVector]) -> Self {
+        Self { coeffs: std::array::from_fn(|k| mvs.iter().map(|m| m.coeffs[k]).collect()) }
+    }
+}
+
+impl FromIterator<MultiVector> for MultiVectorBatch {
+    fn from_iter<I: IntoIterator<Item = MultiVector>>(iter: I) -> Self {
+        let mut batch = Self::new();
+        for mv in iter {
+            batch.push(mv);
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(n: usize) -> Vec<MultiVector> {
+        (0..n).map(|i| MultiVector::new(std::array::from_fn(|k| ((i * 8 + k) as f64 * 0.37).sin()))).collect()
+    }
+
+    #[test]
+    fn coefficient_round_trip() {
+        let batch = MultiVectorBatch::from(&sample(5)[..]);
+        assert_eq!(batch.coeff(3).len(), 5);
+        let rebuilt = MultiVectorBatch::from_coeffs(batch.clone().into_coeffs());
+        assert_eq!(rebuilt, batch);
+        assert_eq!(rebuilt.to_vec(), sample(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn from_coeffs_rejects_ragged_arrays() {
+        let mut coeffs: [Vec<f64>; 8] = std::array::from_fn(|_| vec![0.0; 3]);
+        coeffs[5].pop();
+        MultiVectorBatch::from_coeffs(coeffs);
+    }
+
+    #[test]
+    fn kernels_match_scalar_products() {
+        // 4099 elements: several SIMD groups, a scalar tail and more than one rayon chunk
+        let (a, b) = (sample(4099), sample(4100)[1..].to_vec());
+        let (ba, bb) = (MultiVectorBatch::from(&a[..]), MultiVectorBatch::from(&b[..]));
+        let rotor = Rotor::from_plane_angle(crate::grade::Bivector::new(0.3, -0.2, 0.9), 1.1);
+        let close = |x: MultiVector, y: MultiVector| (0..8).all(|k| (x.coeffs[k] - y.coeffs[k]).abs() < 1e-12);
+        for (i, (p, q)) in ba.geometric_product(&bb).iter().zip(ba.par_geometric_product(&bb).iter()).enumerate() {
+            assert!(close(p, a[i] * b[i]) && close(q, p));
+        }
+        for (i, (p, q)) in ba.rotate(rotor).iter().zip(ba.par_rotate(rotor).iter()).enumerate() {
+            assert!(close(p, a[i].rotate(MultiVector::from(rotor))) && close(q, p));
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Basis-blade engine: derives geometric product tables from a metric signature.
+//!
+//! Basis blades are encoded as bitmasks: bit `i` set means the `i`-th basis vector is a
+//! factor, always taken in increasing index order (e.g. `0b101` is `e1 e3` when basis
+//! vectors are numbered from 1). The hand-written Cl(3,0) product in `multivector.rs`
+//! can be checked against these tables with `verify_product`, and the CGA and PGA
+//! products are evaluated from them directly.
+
+use std::fmt;
+
+/// Grade (number of basis vector factors) of a basis blade.
+pub fn grade(blade: usize) -> usize {
+    blade.count_ones() as usize
+}
+
+/// Sign picked up when reordering the product `a * b` into canonical (increasing) order.
+pub fn reorder_sign(a: usize, b: usize) -> f64 {
+    let mut a = a >> 1;
+    let mut swaps = 0;
+    while a != 0 {
+        swaps += (a & b).count_ones();
+        a >>= 1;
+    }
+    if swaps % 2 == 0 { 1.0 } else { -1.0 }
+}
+
+/// Geometric product of two basis blades for a diagonal `metric` (the square of each
+/// basis vector). Returns the sign (0 for degenerate metrics) and the resulting blade.
+pub fn blade_product(a: usize, b: usize, metric: &[f64]) -> (f64, usize) {
+    let mut sign = reorder_sign(a, b);
+    let common = a & b;
+    for (i, m) in metric.iter().enumerate() {
+        if common & (1 << i) != 0 {
+            sign *= m;
+        }
+    }
+    (sign, a ^ b)
+}
+
+/// Full Cayley table on canonical bitmask order, `table[a * n + b] = blade_product(a, b)`
+/// with `n = 2^metric.len()`.
+pub fn cayley_table(metric: &[f64]) -> Vec<(f64, usize)> {
+    let n = 1 << metric.len();
+    (0..n * n).map(|k| blade_product(k / n, k % n, metric)).collect()
+}
+
+/// Product of two coefficient arrays indexed by blade bitmask, keeping only the terms
+/// `blade i * blade j -> blade k` accepted by `keep`. `table` is `cayley_table` of a metric
+/// with `N` blades; zero coefficients are skipped, which makes sparse operands cheap.
+pub(crate) fn product_where<const N: usize>(
+    table: &[(f64, usize)],
+    a: &[f64; N],
+    b: &[f64; N],
+    keep: impl Fn(usize, usize, usize) -> bool,
+) -> [f64; N] {
+    let mut c = [0.0; N];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0.0 {
+            continue;
+        }
+        for (j, &y) in b.iter().enumerate() {
+            if y == 0.0 {
+                continue;
+            }
+            let (sign, k) = table[i * N + j];
+            if sign != 0.0 && keep(i, j, k) {
+                c[k] += sign * x * y;
+            }
+        }
+    }
+    c
+}
+
+/// Apply `f(grade, coefficient)` to every coefficient of a bitmask-indexed array.
+pub(crate) fn map_grades<const N: usize>(c: &[f64; N], f: impl Fn(usize, f64) -> f64) -> [f64; N] {
+    std::array::from_fn(|m| f(grade(m), c[m]))
+}
+
+/// Grade projection, reversion and the arithmetic operators for a `Copy` type with a
+/// bitmask-indexed `coeffs` array, whose geometric product comes from the Cayley table
+/// returned by `$table()`.
+macro_rules! blade_algebra {
+    ($ty:ident, $table:path) => {
+        impl $ty {
+            fn product_where(&self, rhs: &Self, keep: impl Fn(usize, usize, usize) -> bool) -> Self {
+                Self { coeffs: $crate::blade::product_where($table(), &self.coeffs, &rhs.coeffs, keep) }
+            }
+
+            pub fn grade(&self, k: usize) -> Self {
+                Self { coeffs: $crate::blade::map_grades(&self.coeffs, |g, v| if g == k { v } else { 0.0 }) }
+            }
+
+            /// Reversion (~): blades of grade 2 and 3 (mod 4) change sign.
+            pub fn reverse(&self) -> Self {
+                Self { coeffs: $crate::blade::map_grades(&self.coeffs, |g, v| if g % 4 >= 2 { -v } else { v }) }
+            }
+        }
+
+        impl std::ops::Add for $ty {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                let mut c = self.coeffs;
+                for (a, b) in c.iter_mut().zip(rhs.coeffs) {
+                    *a += b;
+                }
+                Self { coeffs: c }
+            }
+        }
+
+        impl std::ops::Sub for $ty {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                let mut c = self.coeffs;
+                for (a, b) in c.iter_mut().zip(rhs.coeffs) {
+                    *a -= b;
+                }
+                Self { coeffs: c }
+            }
+        }
+
+        impl std::ops::Neg for $ty {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self { coeffs: self.coeffs.map(|c| -c) }
+            }
+        }
+
+        impl std::ops::Mul<f64> for $ty {
+            type Output = Self;
+            fn mul(self, rhs: f64) -> Self {
+                Self { coeffs: self.coeffs.map(|c| c * rhs) }
+            }
+        }
+
+        /// Geometric product.
+        impl std::ops::Mul for $ty {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                self.product_where(&rhs, |_, _, _| true)
+            }
+        }
+    };
+}
+
+pub(crate) use blade_algebra;
+
+/// Metric signature Cl(p, q, r): `p` basis vectors square to +1, `q` to -1 and `r` to 0.
+///
+/// Basis vectors are ordered degenerate first, then positive, then negative, matching
+/// `e0` in PGA and `e+`, `e-` in CGA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub p: usize,
+    pub q: usize,
+    pub r: usize,
+}
+
+impl Signature {
+    pub fn new(p: usize, q: usize, r: usize) -> Self {
+        Self { p, q, r }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.p + self.q + self.r
+    }
+
+    /// Square of each basis vector.
+    pub fn metric(&self) -> Vec<f64> {
+        let mut m = vec![0.0; self.r];
+        m.extend(std::iter::repeat_n(1.0, self.p));
+        m.extend(std::iter::repeat_n(-1.0, self.q));
+        m
+    }
+}
+
+/// How a coefficient array stores basis blades: `blades[k] = (mask, sign)` means
+/// coefficient `k` multiplies `sign * blade(mask)`, so non-canonical blades such as
+/// `e31 = -e13` can be represented.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasisLayout {
+    pub blades: Vec<(usize, f64)>,
+    pub names: Vec<String>,
+}
+
+impl BasisLayout {
+    /// All blades in bitmask order, with basis vectors numbered from `first_index`.
+    pub fn canonical(dimension: usize, first_index: usize) -> Self {
+        let blades: Vec<(usize, f64)> = (0..1usize << dimension).map(|m| (m, 1.0)).collect();
+        let names = blades.iter().map(|&(m, _)| blade_name(m, first_index)).collect();
+        Self { blades, names }
+    }
+
+    /// The coefficient layout of `MultiVector`: `1, e1, e2, e3, e12, e23, e31, e123`.
+    pub fn cl3() -> Self {
+        let blades = vec![
+            (0b000, 1.0),
+            (0b001, 1.0),
+            (0b010, 1.0),
+            (0b100, 1.0),
+            (0b011, 1.0),
+            (0b110, 1.0),
+            (0b101, -1.0),
+            (0b111, 1.0),
+        ];
+        let names = ["1", "e1", "e2", "e3", "e12", "e23", "e31", "e123"].map(String::from).to_vec();
+        Self { blades, names }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blades.is_empty()
+    }
+
+    /// Coefficient index and orientation sign of a canonical blade.
+    pub fn index_of(&self, mask: usize) -> Option<(usize, f64)> {
+        self.blades.iter().position(|&(m, _)| m == mask).map(|k| (k, self.blades[k].1))
+    }
+}
+
+/// Name of a canonical blade, e.g. `e13` for `0b101` with `first_index = 1`.
+pub fn blade_name(mask: usize, first_index: usize) -> String {
+    if mask == 0 {
+        return "1".to_string();
+    }
+    let digits: String = (0..usize::BITS as usize)
+        .filter(|i| mask & (1 << i) != 0)
+        .map(|i| (i + first_index).to_string())
+        .collect();
+    format!("e{}", digits)
+}
+
+/// Cayley table of the geometric product expressed in a `BasisLayout`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CayleyTable {
+    pub layout: BasisLayout,
+    /// `entries[i * n + j] = (sign, k)`: basis element `i` times `j` is `sign` times element `k`.
+    entries: Vec<(f64, usize)>,
+}
+
+impl CayleyTable {
+    /// Derive the table for `metric` (see `Signature::metric`). Panics if the layout does
+    /// not contain every blade of the algebra.
+    pub fn new(metric: &[f64], layout: BasisLayout) -> Self {
+        let n = layout.len();
+        assert_eq!(n, 1 << metric.len(), "layout must list every basis blade");
+        let mut entries = Vec::with_capacity(n * n);
+        for &(a, sa) in &layout.blades {
+            for &(b, sb) in &layout.blades {
+                let (s, m) = blade_product(a, b, metric);
+                let (k, sk) = layout.index_of(m).expect("layout must list every basis blade");
+                // sa*A * sb*B = sa*sb*s * M = sa*sb*s*sk * (sk*M)
+                entries.push((sa * sb * s * sk, k));
+            }
+        }
+        Self { layout, entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.layout.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layout.is_empty()
+    }
+
+    /// Product of basis elements `i` and `j` as `(sign, k)`.
+    pub fn get(&self, i: usize, j: usize) -> (f64, usize) {
+        self.entries[i * self.len() + j]
+    }
+
+    /// Geometric product of two coefficient arrays in this layout.
+    pub fn product(&self, a: &[f64], b: &[f64]) -> Vec<f64> {
+        let n = self.len();
+        let mut c = vec![0.0; n];
+        for (i, &ai) in a.iter().enumerate().take(n) {
+            for (j, &bj) in b.iter().enumerate().take(n) {
+                let (s, k) = self.get(i, j);
+                c[k] += s * ai * bj;
+            }
+        }
+        c
+    }
+
+    /// Symbolic expansion of the product, one Rust expression per output coefficient
+    /// (e.g. `a[0]*b[1] + a[1]*b[0] - ...`), grouped by left operand index.
+    pub fn product_code(&self, lhs: &str, rhs: &str) -> Vec<String> {
+        let n = self.len();
+        let mut terms: Vec<Vec<(f64, usize, usize)>> = vec![Vec::new(); n];
+        for i in 0..n {
+            for j in 0..n {
+                let (s, k) = self.get(i, j);
+                if s != 0.0 {
+                    terms[k].push((s, i, j));
+                }
+            }
+        }
+        terms
+            .into_iter()
+            .map(|ts| {
+                let mut out = String::new();
+                for (t, (s, i, j)) in ts.into_iter().enumerate() {
+                    let op = match (t, s < 0.0) {
+                        (0, false) => "",
+                        (0, true) => "-",
+                        (_, false) => " + ",
+                        (_, true) => " - ",
+                    };
+                    out.push_str(&format!("{}{}[{}]*{}[{}]", op, lhs, i, rhs, j));
+                }
+                if out.is_empty() { "0.0".to_string() } else { out }
+            })
+            .collect()
+    }
+}
+
+/// Renders the table with blade names, one row per left operand.
+impl fmt::Display for CayleyTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = &self.layout.names;
+        let width = names.iter().map(|n| n.len()).max().unwrap_or(1) + 2;
+        for i in 0..self.len() {
+            for j in 0..self.len() {
+                let (s, k) = self.get(i, j);
+                let cell = if s == 0.0 {
+                    "0".to_string()
+                } else if s < 0.0 {
+                    format!("-{}", names[k])
+                } else {
+                    names[k].clone()
+                };
+                write!(f, "{:>width$}", cell, width = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A basis pair on which a specialized product disagrees with the derived table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductMismatch {
+    pub lhs: String,
+    pub rhs: String,
+    pub expected: Vec<f64>,
+    pub actual: Vec<f64>,
+}
+
+impl fmt::Display for ProductMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} * {}: expected {:?}, got {:?}", self.lhs, self.rhs, self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for ProductMismatch {}
+
+/// Check a specialized product implementation against the table on every pair of
+/// basis elements (the product is bilinear, so this verifies it completely).
+pub fn verify_product(table: &CayleyTable, product: impl Fn(&[f64], &[f64]) -> Vec<f64>) -> Result<(), ProductMismatch> {
+    let n = table.len();
+    for i in 0..n {
+        for j in 0..n {
+            let mut a = vec![0.0; n];
+            let mut b = vec![0.0; n];
+            a[i] = 1.0;
+            b[j] = 1.0;
+            let expected = table.product(&a, &b);
+            let actual = product(&a, &b);
+            if expected != actual {
+                return Err(ProductMismatch {
+                    lhs: table.layout.names[i].clone(),
+                    rhs: table.layout.names[j].clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_where_filters_terms() {
+        let table = cayley_table(&[1.0, 1.0, 1.0]);
+        let (e1, e2) = ([0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(product_where(&table, &e2, &e1, |_, _, _| true)[0b011], -1.0);
+        assert_eq!(product_where(&table, &e1, &e1, |_, _, _| true)[0], 1.0);
+        assert_eq!(product_where(&table, &e1, &e1, |i, j, _| i & j == 0), [0.0; 8]);
+    }
+
+    #[test]
+    fn map_grades_sees_blade_grades() {
+        let c: [f64; 8] = std::array::from_fn(|m| m as f64);
+        assert_eq!(map_grades(&c, |g, v| if g == 2 { v } else { 0.0 }), [0.0, 0.0, 0.0, 3.0, 0.0, 5.0, 6.0, 0.0]);
+    }
+}
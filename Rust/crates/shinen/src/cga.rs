@@ -0,0 +1,509 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::blade::{self, blade_algebra, cayley_table};
+use crate::notation;
+use crate::grade::{Bivector, Rotor, Vector};
+use crate::multivector::MultiVector;
+
+/// Number of basis blades in Cl(4,1).
+const BLADES: usize = 32;
+
+/// Squares of the basis vectors `e1, e2, e3, e+ (e4), e- (e5)`.
+const METRIC: [f64; 5] = [1.0, 1.0, 1.0, 1.0, -1.0];
+
+const E4: usize = 0b01000;
+const E5: usize = 0b10000;
+const PSEUDOSCALAR: usize = 0b11111;
+
+fn table() -> &'static [(f64, usize)] {
+    static TABLE: OnceLock<Vec<(f64, usize)>> = OnceLock::new();
+    TABLE.get_or_init(|| cayley_table(&METRIC))
+}
+
+/// MultiVector in the conformal model Cl(4,1) of 3D Euclidean space.
+///
+/// `coeffs` is indexed by basis blade bitmask: bit 0..=2 are `e1, e2, e3`, bit 3 is `e+`
+/// (squares to +1) and bit 4 is `e-` (squares to -1). The null vectors are
+/// `n_inf = e+ + e-` (point at infinity) and `n_o = (e- - e+) / 2` (origin).
+///
+/// Constructors return blades in direct (outer product null space) form, so that
+/// e.g. `line_from_points(a, b) == point(a) ^ point(b) ^ n_inf`; `dual()` converts to
+/// the inner product form used by `dual_sphere`/`dual_plane`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CgaMultiVector {
+    pub coeffs: [f64; BLADES],
+}
+
+blade_algebra!(CgaMultiVector, table);
+
+impl CgaMultiVector {
+    pub const ZERO: Self = Self { coeffs: [0.0; BLADES] };
+
+    pub fn new(coeffs: [f64; BLADES]) -> Self {
+        Self { coeffs }
+    }
+
+    pub fn scalar(s: f64) -> Self {
+        Self::blade(0, s)
+    }
+
+    /// Single basis blade (by bitmask) with coefficient `value`.
+    pub fn blade(mask: usize, value: f64) -> Self {
+        let mut c = [0.0; BLADES];
+        c[mask] = value;
+        Self { coeffs: c }
+    }
+
+    /// Point at infinity `n_inf = e+ + e-`.
+    pub fn n_inf() -> Self {
+        Self::blade(E4, 1.0) + Self::blade(E5, 1.0)
+    }
+
+    /// Origin `n_o = (e- - e+) / 2`.
+    pub fn n_o() -> Self {
+        Self::blade(E5, 0.5) - Self::blade(E4, 0.5)
+    }
+
+    /// Unit pseudoscalar `e1 e2 e3 e+ e-`.
+    pub fn pseudoscalar() -> Self {
+        Self::blade(PSEUDOSCALAR, 1.0)
+    }
+
+    /// Embed a Euclidean vector as a (non-null) grade-1 element.
+    pub fn vector(v: Vector) -> Self {
+        let mut c = [0.0; BLADES];
+        (c[0b001], c[0b010], c[0b100]) = (v.e1, v.e2, v.e3);
+        Self { coeffs: c }
+    }
+
+    /// Conformal point `x + |x|^2 / 2 n_inf + n_o`.
+    pub fn point(x: Vector) -> Self {
+        Self::vector(x) + Self::n_inf() * (0.5 * x.norm_squared()) + Self::n_o()
+    }
+
+    /// Dual (inner product null space) sphere `C - r^2 / 2 n_inf`.
+    pub fn dual_sphere(center: Vector, radius: f64) -> Self {
+        Self::point(center) - Self::n_inf() * (0.5 * radius * radius)
+    }
+
+    /// Dual plane `n + d n_inf` with normal `n` and signed distance `d` from the origin.
+    pub fn dual_plane(normal: Vector, distance: f64) -> Self {
+        let n = normal.normalize();
+        Self::vector(n) + Self::n_inf() * distance
+    }
+
+    pub fn sphere(center: Vector, radius: f64) -> Self {
+        Self::dual_sphere(center, radius).undual()
+    }
+
+    pub fn plane(normal: Vector, distance: f64) -> Self {
+        Self::dual_plane(normal, distance).undual()
+    }
+
+    /// Circle with the given center, plane normal and radius.
+    pub fn circle(center: Vector, normal: Vector, radius: f64) -> Self {
+        let n = normal.normalize();
+        Self::sphere(center, radius).meet(&Self::plane(n, n.dot(center)))
+    }
+
+    /// Line through `point` along `direction`.
+    pub fn line(point: Vector, direction: Vector) -> Self {
+        Self::line_from_points(point, point + direction)
+    }
+
+    pub fn point_pair(a: Vector, b: Vector) -> Self {
+        Self::point(a).outer(&Self::point(b))
+    }
+
+    pub fn line_from_points(a: Vector, b: Vector) -> Self {
+        Self::point_pair(a, b).outer(&Self::n_inf())
+    }
+
+    pub fn circle_from_points(a: Vector, b: Vector, c: Vector) -> Self {
+        Self::point_pair(a, b).outer(&Self::point(c))
+    }
+
+    pub fn plane_from_points(a: Vector, b: Vector, c: Vector) -> Self {
+        Self::circle_from_points(a, b, c).outer(&Self::n_inf())
+    }
+
+    pub fn sphere_from_points(a: Vector, b: Vector, c: Vector, d: Vector) -> Self {
+        Self::circle_from_points(a, b, c).outer(&Self::point(d))
+    }
+
+    // Versors
+
+    /// Translator `1 - t n_inf / 2`, moving points by `t`.
+    pub fn translator(t: Vector) -> Self {
+        Self::scalar(1.0) - Self::vector(t) * Self::n_inf() * 0.5
+    }
+
+    /// Embed a Euclidean rotor (rotation about the origin).
+    pub fn rotor(r: Rotor) -> Self {
+        let mut c = [0.0; BLADES];
+        c[0] = r.s;
+        c[0b011] = r.e12;
+        c[0b110] = r.e23;
+        // e31 = -e1 e3
+        c[0b101] = -r.e31;
+        Self { coeffs: c }
+    }
+
+    /// Dilator scaling about the origin by `scale > 0`.
+    pub fn dilator(scale: f64) -> Self {
+        let half = 0.5 * scale.ln();
+        let e = Self::n_o().outer(&Self::n_inf());
+        Self::scalar(half.cosh()) + e * half.sinh()
+    }
+
+    /// Motor applying `dilation` about the origin, then `rotation`, then `translation`.
+    pub fn motor(rotation: Rotor, translation: Vector, dilation: f64) -> Self {
+        Self::translator(translation) * Self::rotor(rotation) * Self::dilator(dilation)
+    }
+
+    /// Versor sandwich `V x V^-1`. Returns `None` if `self` is not invertible.
+    pub fn apply(&self, x: &Self) -> Option<Self> {
+        Some(*self * *x * self.inverse()?)
+    }
+
+    /// Reflect a point in a dual plane (or invert it in a dual sphere): `-m x m^-1`.
+    pub fn reflect(&self, mirror: &Self) -> Option<Self> {
+        Some(-(*mirror * *self * mirror.inverse()?))
+    }
+
+    // Products
+
+    /// Outer (wedge) product.
+    pub fn outer(&self, rhs: &Self) -> Self {
+        self.product_where(rhs, |i, j, _| i & j == 0)
+    }
+
+    /// Left contraction `self ⌋ rhs`.
+    pub fn left_contraction(&self, rhs: &Self) -> Self {
+        self.product_where(rhs, |i, j, k| blade::grade(j) >= blade::grade(i) && blade::grade(k) == blade::grade(j) - blade::grade(i))
+    }
+
+    /// Scalar part of the geometric product; for vectors this is the inner product.
+    pub fn scalar_product(&self, rhs: &Self) -> f64 {
+        self.product_where(rhs, |_, _, k| k == 0).coeffs[0]
+    }
+
+    /// Meet (intersection) of two direct blades, computed as `(A* ^ B*)` undualized.
+    pub fn meet(&self, rhs: &Self) -> Self {
+        self.dual().outer(&rhs.dual()).undual()
+    }
+
+    /// Dual `X I^-1`.
+    pub fn dual(&self) -> Self {
+        // I^2 = -1 in Cl(4,1), so I^-1 = -I
+        *self * -Self::pseudoscalar()
+    }
+
+    /// Inverse of `dual`: `X I`.
+    pub fn undual(&self) -> Self {
+        *self * Self::pseudoscalar()
+    }
+
+    // Unary operations
+
+    /// Grade involution: odd grades change sign.
+    pub fn involute(&self) -> Self {
+        Self { coeffs: blade::map_grades(&self.coeffs, |g, v| if g % 2 == 1 { -v } else { v }) }
+    }
+
+    /// Inverse of a versor or blade, `X~ / (X X~)`. Returns `None` if `X X~` is not an
+    /// invertible scalar.
+    pub fn inverse(&self) -> Option<Self> {
+        let rev = self.reverse();
+        let norm = *self * rev;
+        let s = norm.coeffs[0];
+        let rest = norm.coeffs[1..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        if s.abs() <= f64::EPSILON || rest > 1e-9 * s.abs().max(1.0) {
+            return None;
+        }
+        Some(rev * (1.0 / s))
+    }
+
+    /// Euclidean part `e1, e2, e3` of the grade-1 coefficients.
+    fn euclidean(&self) -> Vector {
+        Vector::new(self.coeffs[0b001], self.coeffs[0b010], self.coeffs[0b100])
+    }
+
+    // Euclidean extraction
+
+    /// Euclidean location of a conformal point (any non-zero multiple of one).
+    pub fn to_point(&self) -> Option<Vector> {
+        let w = -self.scalar_product(&Self::n_inf());
+        if w.abs() <= f64::EPSILON {
+            return None;
+        }
+        Some(self.euclidean() * (1.0 / w))
+    }
+
+    /// Euclidean distance between two conformal points.
+    pub fn distance(&self, other: &Self) -> Option<f64> {
+        Some((self.to_point()? - other.to_point()?).norm())
+    }
+
+    /// Signed distance of a conformal point from a dual plane.
+    pub fn plane_distance(&self, dual_plane: &Self) -> Option<f64> {
+        let p = Self::point(self.to_point()?);
+        Some(p.scalar_product(dual_plane) / dual_plane.euclidean().norm())
+    }
+
+    /// Center and squared radius of a direct round (point pair, circle or sphere).
+    ///
+    /// The squared radius is negative for imaginary rounds, e.g. the meet of two
+    /// spheres that do not intersect.
+    pub fn round_params(&self) -> Option<(Vector, f64)> {
+        let n_inf = Self::n_inf();
+        let center = (*self * n_inf * *self).grade(1).to_point()?;
+        let d = n_inf.left_contraction(self);
+        let dd = (d * d).coeffs[0];
+        if dd.abs() <= f64::EPSILON {
+            return None;
+        }
+        let r2 = (*self * self.involute()).coeffs[0] / dd;
+        Some((center, r2))
+    }
+
+    /// Unit normal and signed distance from the origin of a direct plane.
+    pub fn plane_params(&self) -> Option<(Vector, f64)> {
+        let dual = self.dual();
+        let n = dual.euclidean();
+        let len = n.norm();
+        if len <= f64::EPSILON {
+            return None;
+        }
+        Some((n * (1.0 / len), dual.coeffs[E4] / len))
+    }
+
+    /// Point closest to the origin and unit direction of a direct line.
+    pub fn line_params(&self) -> Option<(Vector, Vector)> {
+        // L = X_p ^ d ^ n_inf = d ^ (n_inf ^ n_o) + (p ^ d) ^ n_inf
+        let c = &self.coeffs;
+        let d = Vector::new(c[0b11001], c[0b11010], c[0b11100]);
+        let d2 = d.norm_squared();
+        if d2 <= f64::EPSILON {
+            return None;
+        }
+        // moment p ^ d, read from its e+ component (e31 = -e1 e3)
+        let m = Bivector::new(c[0b01011], c[0b01110], -c[0b01101]);
+        // (p ^ d) d = |d|^2 p_perp
+        let p = Vector::from(MultiVector::from(m) * MultiVector::from(d)) * (1.0 / d2);
+        Some((p, d * (1.0 / d2.sqrt())))
+    }
+
+    /// Location of a flat point `X ^ n_inf`, e.g. the meet of a line and a plane.
+    pub fn flat_point(&self) -> Option<Vector> {
+        // X ^ n_inf = x ^ n_inf + n_o ^ n_inf, and n_o ^ n_inf = -e+ e-
+        let c = &self.coeffs;
+        let w = -c[E4 | E5];
+        if w.abs() <= f64::EPSILON {
+            return None;
+        }
+        Some(Vector::new(c[0b01001], c[0b01010], c[0b01100]) * (1.0 / w))
+    }
+
+    /// The two points of a direct point pair `A ^ B`.
+    pub fn point_pair_points(&self) -> Option<(Vector, Vector)> {
+        let t2 = (*self * *self).coeffs[0];
+        if t2 < 0.0 {
+            return None;
+        }
+        let root = Self::scalar(t2.sqrt());
+        let d = Self::n_inf().left_contraction(self);
+        let a = ((*self + root) * d).grade(1).to_point()?;
+        let b = ((*self - root) * d).grade(1).to_point()?;
+        Some((a, b))
+    }
+}
+
+impl Default for CgaMultiVector {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+// Display
+impl fmt::Display for CgaMultiVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = (0..BLADES).map(|m| blade::blade_name(m, 1)).collect();
+        notation::write_blades(f, &self.coeffs, &names, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Cga = CgaMultiVector;
+
+    fn assert_near(a: Vector, b: Vector) {
+        assert!((a - b).norm() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    /// Compare two point sets up to order.
+    fn assert_pair(got: (Vector, Vector), a: Vector, b: Vector) {
+        let (p, q) = if (got.0 - a).norm() <= (got.1 - a).norm() { got } else { (got.1, got.0) };
+        assert_near(p, a);
+        assert_near(q, b);
+    }
+
+    #[test]
+    fn points_and_lines() {
+        let (a, b) = (Vector::new(1.0, 2.0, 3.0), Vector::new(-1.0, 0.5, 2.0));
+        let (pa, pb) = (CgaMultiVector::point(a), CgaMultiVector::point(b));
+        assert!((pa.scalar_product(&pb) + (a - b).norm_squared() / 2.0).abs() < 1e-12);
+        let line = CgaMultiVector::line_from_points(a, b);
+        let on = CgaMultiVector::point(a * 3.0 - b * 2.0).outer(&line);
+        assert!(on.coeffs.iter().all(|c| c.abs() < 1e-9));
+        assert_eq!((-pa).reverse(), -pa);
+        assert_eq!(line.grade(3), line);
+    }
+
+    #[test]
+    fn round_params_of_spheres_circles_and_point_pairs() {
+        let c = Vector::new(1.0, -2.0, 0.5);
+        let (center, r2) = Cga::sphere(c, 3.0).round_params().unwrap();
+        assert_near(center, c);
+        assert!((r2 - 9.0).abs() < 1e-9);
+
+        let (center, r2) = Cga::circle(c, Vector::new(0.0, 0.0, 2.0), 1.5).round_params().unwrap();
+        assert_near(center, c);
+        assert!((r2 - 2.25).abs() < 1e-9);
+
+        let (a, b) = (Vector::new(1.0, 0.0, 2.0), Vector::new(-3.0, 4.0, 2.0));
+        let (center, r2) = Cga::point_pair(a, b).round_params().unwrap();
+        assert_near(center, (a + b) * 0.5);
+        assert!((r2 - (a - b).norm_squared() / 4.0).abs() < 1e-9);
+        assert_pair(Cga::point_pair(a, b).point_pair_points().unwrap(), a, b);
+
+        // four points on a known sphere
+        let on = |v: Vector| c + v * 2.0;
+        let sphere = Cga::sphere_from_points(
+            on(Vector::new(1.0, 0.0, 0.0)),
+            on(Vector::new(0.0, 1.0, 0.0)),
+            on(Vector::new(0.0, 0.0, -1.0)),
+            on(Vector::new(0.6, 0.0, 0.8)),
+        );
+        let (center, r2) = sphere.round_params().unwrap();
+        assert_near(center, c);
+        assert!((r2 - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn meet_of_two_spheres_is_their_intersection_circle() {
+        // unit spheres at x = 0 and x = 1 meet in the circle x = 1/2, r^2 = 3/4
+        let s1 = Cga::sphere(Vector::new(0.0, 0.0, 0.0), 1.0);
+        let s2 = Cga::sphere(Vector::new(1.0, 0.0, 0.0), 1.0);
+        let (center, r2) = s1.meet(&s2).round_params().unwrap();
+        assert_near(center, Vector::new(0.5, 0.0, 0.0));
+        assert!((r2 - 0.75).abs() < 1e-9);
+
+        // spheres too far apart meet in an imaginary circle
+        let far = Cga::sphere(Vector::new(3.0, 0.0, 0.0), 1.0);
+        let (center, r2) = s1.meet(&far).round_params().unwrap();
+        assert_near(center, Vector::new(1.5, 0.0, 0.0));
+        assert!(r2 < 0.0);
+    }
+
+    #[test]
+    fn meet_of_a_line_and_a_plane_is_a_flat_point() {
+        let plane = Cga::plane(Vector::new(0.0, 0.0, 1.0), 2.0);
+        let line = Cga::line(Vector::new(1.0, -1.0, 0.0), Vector::new(1.0, 2.0, 1.0));
+        let hit = Vector::new(3.0, 3.0, 2.0);
+        assert_near(plane.meet(&line).flat_point().unwrap(), hit);
+        assert_near(line.meet(&plane).flat_point().unwrap(), hit);
+
+        // a line parallel to the plane only meets it at infinity
+        let parallel = Cga::line(Vector::new(0.0, 0.0, 0.0), Vector::new(1.0, 1.0, 0.0));
+        assert_eq!(plane.meet(&parallel).flat_point(), None);
+    }
+
+    #[test]
+    fn meet_of_a_line_and_a_sphere_is_a_point_pair() {
+        let sphere = Cga::sphere(Vector::new(0.0, 0.0, 1.0), 2.0);
+        let line = Cga::line(Vector::new(-5.0, 0.0, 1.0), Vector::new(1.0, 0.0, 0.0));
+        let pair = sphere.meet(&line).point_pair_points().unwrap();
+        assert_pair(pair, Vector::new(-2.0, 0.0, 1.0), Vector::new(2.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn plane_and_line_params_recover_the_construction() {
+        let (n, d) = Cga::plane(Vector::new(0.0, 3.0, 4.0), -1.5).plane_params().unwrap();
+        assert_near(n, Vector::new(0.0, 0.6, 0.8));
+        assert!((d + 1.5).abs() < 1e-9);
+
+        let (a, b, c) = (Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        let (n, d) = Cga::plane_from_points(a, b, c).plane_params().unwrap();
+        let unit = Vector::new(1.0, 1.0, 1.0).normalize();
+        // the orientation follows the point order, so compare up to sign
+        let sign = n.dot(unit).signum();
+        assert_near(n * sign, unit);
+        assert!((d * sign - 1.0 / 3f64.sqrt()).abs() < 1e-9);
+
+        let (p, dir) = Cga::line(Vector::new(2.0, 1.0, 5.0), Vector::new(0.0, 0.0, 3.0)).line_params().unwrap();
+        assert_near(p, Vector::new(2.0, 1.0, 0.0));
+        assert_near(dir, Vector::new(0.0, 0.0, 1.0));
+
+        let (a, b) = (Vector::new(1.0, 1.0, 0.0), Vector::new(3.0, 1.0, 2.0));
+        let (p, dir) = Cga::line_from_points(a, b).line_params().unwrap();
+        assert_near(dir, (b - a).normalize());
+        assert!(p.dot(dir).abs() < 1e-9);
+        assert!((p - a).cross(dir).norm() < 1e-9);
+        assert_near(p, Vector::new(0.5, 1.0, -0.5));
+    }
+
+    #[test]
+    fn motor_dilates_rotates_then_translates() {
+        let r = Rotor::from_plane_angle(Bivector::new(1.0, 0.0, 0.0), std::f64::consts::FRAC_PI_2);
+        let (t, scale) = (Vector::new(1.0, -2.0, 0.5), 3.0);
+        let motor = Cga::motor(r, t, scale);
+        for x in [Vector::new(1.0, 0.0, 0.0), Vector::new(0.3, -1.2, 2.0), Vector::new(0.0, 0.0, 0.0)] {
+            let moved = motor.apply(&Cga::point(x)).unwrap().to_point().unwrap();
+            assert_near(moved, r.apply(x * scale) + t);
+        }
+        // e1 turns to e2
+        assert_near(Cga::rotor(r).apply(&Cga::point(Vector::new(1.0, 0.0, 0.0))).unwrap().to_point().unwrap(),
+            Vector::new(0.0, 1.0, 0.0));
+
+        // distances scale with the dilation, so the image sphere has radius 3r
+        let sphere = Cga::sphere(Vector::new(1.0, 1.0, 0.0), 0.5);
+        let (center, r2) = motor.apply(&sphere).unwrap().round_params().unwrap();
+        assert_near(center, r.apply(Vector::new(3.0, 3.0, 0.0)) + t);
+        assert!((r2 - 2.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reflect_in_planes_and_invert_in_spheres() {
+        let x = Vector::new(1.0, 2.0, 3.0);
+        let mirror = Cga::dual_plane(Vector::new(0.0, 0.0, 1.0), 1.0);
+        let image = Cga::point(x).reflect(&mirror).unwrap().to_point().unwrap();
+        assert_near(image, Vector::new(1.0, 2.0, -1.0));
+
+        let oblique = Cga::dual_plane(Vector::new(1.0, 1.0, 0.0), 0.0);
+        let image = Cga::point(x).reflect(&oblique).unwrap().to_point().unwrap();
+        assert_near(image, Vector::new(-2.0, -1.0, 3.0));
+
+        // inversion in a sphere of radius 2 about c maps c + v to c + 4 v / |v|^2
+        let c = Vector::new(0.0, 1.0, 0.0);
+        let v = Vector::new(1.0, 0.0, 1.0);
+        let inverted = Cga::point(c + v).reflect(&Cga::dual_sphere(c, 2.0)).unwrap().to_point().unwrap();
+        assert_near(inverted, c + v * (4.0 / v.norm_squared()));
+    }
+
+    #[test]
+    fn distances_between_points_and_planes() {
+        let (a, b) = (Cga::point(Vector::new(1.0, 2.0, 2.0)), Cga::point(Vector::new(4.0, 6.0, 2.0)));
+        assert!((a.distance(&b).unwrap() - 5.0).abs() < 1e-9);
+        assert!(((a * 7.0).distance(&(b * -0.5)).unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(Cga::n_inf().distance(&a), None);
+
+        let plane = Cga::dual_plane(Vector::new(0.0, 0.0, 2.0), 1.0);
+        assert!((a.plane_distance(&plane).unwrap() - 1.0).abs() < 1e-9);
+        let below = Cga::point(Vector::new(5.0, -3.0, -0.5));
+        assert!((below.plane_distance(&plane).unwrap() + 1.5).abs() < 1e-9);
+    }
+}
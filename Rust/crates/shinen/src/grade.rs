@@ -0,0 +1,608 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use math_universe_zigen::Scalar;
+
+use crate::multivector::MultiVector;
+
+/// Grade-1 element of Cl(3,0): `e1*e1 + e2*e2 + e3*e3`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector<T: Scalar = f64> {
+    pub e1: T,
+    pub e2: T,
+    pub e3: T,
+}
+
+/// Grade-2 element of Cl(3,0), using the same `e12`, `e23`, `e31` basis as `MultiVector`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bivector<T: Scalar = f64> {
+    pub e12: T,
+    pub e23: T,
+    pub e31: T,
+}
+
+/// Grade-3 element of Cl(3,0): a multiple of the pseudoscalar `e123`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trivector<T: Scalar = f64> {
+    pub e123: T,
+}
+
+/// Even-grade element of Cl(3,0) (scalar + bivector).
+///
+/// Unit rotors encode 3D rotations and are applied with the sandwich `R v R~`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotor<T: Scalar = f64> {
+    pub s: T,
+    pub e12: T,
+    pub e23: T,
+    pub e31: T,
+}
+
+impl Vector<f64> {
+    pub const ZERO: Self = Self { e1: 0.0, e2: 0.0, e3: 0.0 };
+}
+
+impl<T: Scalar> Vector<T> {
+    pub fn new(e1: T, e2: T, e3: T) -> Self {
+        Self { e1, e2, e3 }
+    }
+
+    /// Inner (dot) product.
+    pub fn dot(self, rhs: Self) -> T {
+        self.e1 * rhs.e1 + self.e2 * rhs.e2 + self.e3 * rhs.e3
+    }
+
+    /// Outer (wedge) product.
+    pub fn wedge(self, rhs: Self) -> Bivector<T> {
+        Bivector {
+            e12: self.e1 * rhs.e2 - self.e2 * rhs.e1,
+            e23: self.e2 * rhs.e3 - self.e3 * rhs.e2,
+            e31: self.e3 * rhs.e1 - self.e1 * rhs.e3,
+        }
+    }
+
+    /// Cross product, the vector dual to `self ^ rhs`.
+    pub fn cross(self, rhs: Self) -> Self {
+        let b = self.wedge(rhs);
+        Self { e1: b.e23, e2: b.e31, e3: b.e12 }
+    }
+
+    pub fn norm_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn norm(self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        self * (T::one() / self.norm())
+    }
+}
+
+impl Bivector<f64> {
+    pub const ZERO: Self = Self { e12: 0.0, e23: 0.0, e31: 0.0 };
+}
+
+impl<T: Scalar> Bivector<T> {
+    pub fn new(e12: T, e23: T, e31: T) -> Self {
+        Self { e12, e23, e31 }
+    }
+
+    /// Reversion (~): bivectors change sign.
+    pub fn reverse(self) -> Self {
+        -self
+    }
+
+    pub fn norm_squared(self) -> T {
+        self.e12 * self.e12 + self.e23 * self.e23 + self.e31 * self.e31
+    }
+
+    pub fn norm(self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        self * (T::one() / self.norm())
+    }
+
+    /// Commutator product `(self * rhs - rhs * self) / 2`, which is again a bivector.
+    pub fn commutator(self, rhs: Self) -> Self {
+        (self * rhs).bivector()
+    }
+
+    /// Exponential `cos|B| + sin|B| B/|B|`; `exp(-B * angle / 2)` rotates by `angle` in the unit plane `B`.
+    ///
+    /// For small `|B|` both factors come from their Taylor series in `|B|^2`, which avoids the
+    /// square root at zero and keeps the derivative finite when `T` is a dual number.
+    pub fn exp(self) -> Rotor<T> {
+        let c = |x: f64| T::from_f64(x).unwrap();
+        let theta2 = self.norm_squared();
+        let (cos, k) = if theta2 < c(1e-6) {
+            // truncation error below |B|^6 / 720
+            let cos = T::one() - theta2 / c(2.0) + theta2 * theta2 / c(24.0);
+            (cos, T::one() - theta2 / c(6.0) + theta2 * theta2 / c(120.0))
+        } else {
+            let theta = theta2.sqrt();
+            (theta.cos(), theta.sin() / theta)
+        };
+        Rotor { s: cos, e12: self.e12 * k, e23: self.e23 * k, e31: self.e31 * k }
+    }
+}
+
+impl Trivector<f64> {
+    pub const ZERO: Self = Self { e123: 0.0 };
+    /// The unit pseudoscalar `e123`.
+    pub const I: Self = Self { e123: 1.0 };
+}
+
+impl<T: Scalar> Trivector<T> {
+    pub fn new(e123: T) -> Self {
+        Self { e123 }
+    }
+
+    /// Reversion (~): the pseudoscalar changes sign.
+    pub fn reverse(self) -> Self {
+        Self { e123: -self.e123 }
+    }
+}
+
+impl Rotor<f64> {
+    pub const IDENTITY: Self = Self { s: 1.0, e12: 0.0, e23: 0.0, e31: 0.0 };
+
+    /// Principal logarithm of a unit rotor: the bivector `B` with `B.exp() == self` and `|B| <= pi`.
+    ///
+    /// `-1` is a half-turn in every plane, so no plane is principal; it gets `pi e12`.
+    pub fn log(self) -> Bivector {
+        let b = self.bivector();
+        let sin = b.norm();
+        if sin == 0.0 && self.s < 0.0 {
+            return Bivector::new(std::f64::consts::PI, 0.0, 0.0);
+        }
+        if sin <= f64::EPSILON && self.s >= 0.0 {
+            return b;
+        }
+        b * (sin.atan2(self.s) / sin)
+    }
+}
+
+impl<T: Scalar> Rotor<T> {
+    pub fn new(s: T, e12: T, e23: T, e31: T) -> Self {
+        Self { s, e12, e23, e31 }
+    }
+
+    /// Rotor rotating by `angle` radians in the oriented `plane`: `exp(-plane * angle / 2)`.
+    ///
+    /// A positive angle in the `e12` plane turns `e1` towards `e2`. `plane` need not be normalized;
+    /// a zero `plane` names no rotation and gives the identity.
+    pub fn from_plane_angle(plane: Bivector<T>, angle: T) -> Self {
+        if plane.norm_squared().is_zero() {
+            return Self::new(T::one(), T::zero(), T::zero(), T::zero());
+        }
+        let half = angle / (T::one() + T::one());
+        let (sin, cos) = (half.sin(), half.cos());
+        let b = plane.normalize();
+        Self {
+            s: cos,
+            e12: -sin * b.e12,
+            e23: -sin * b.e23,
+            e31: -sin * b.e31,
+        }
+    }
+
+    pub fn scalar(self) -> T {
+        self.s
+    }
+
+    pub fn bivector(self) -> Bivector<T> {
+        Bivector { e12: self.e12, e23: self.e23, e31: self.e31 }
+    }
+
+    /// Reversion (~): negates the bivector part.
+    pub fn reverse(self) -> Self {
+        Self { s: self.s, e12: -self.e12, e23: -self.e23, e31: -self.e31 }
+    }
+
+    pub fn norm_squared(self) -> T {
+        self.s * self.s + self.e12 * self.e12 + self.e23 * self.e23 + self.e31 * self.e31
+    }
+
+    pub fn norm(self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let inv = T::one() / self.norm();
+        Self { s: self.s * inv, e12: self.e12 * inv, e23: self.e23 * inv, e31: self.e31 * inv }
+    }
+
+    /// Apply the sandwich product `R v R~` to a vector.
+    ///
+    /// Expanded by grade so only 24 multiplications are needed, against 128 for
+    /// the two full products in `MultiVector::rotate`.
+    pub fn apply(self, v: Vector<T>) -> Vector<T> {
+        let Self { s, e12, e23, e31 } = self;
+        // u + t*e123 = R v
+        let u1 = s * v.e1 + e12 * v.e2 - e31 * v.e3;
+        let u2 = s * v.e2 - e12 * v.e1 + e23 * v.e3;
+        let u3 = s * v.e3 + e31 * v.e1 - e23 * v.e2;
+        let t = e23 * v.e1 + e31 * v.e2 + e12 * v.e3;
+        // vector part of (u + t*e123) R~
+        Vector {
+            e1: s * u1 + e12 * u2 - e31 * u3 + t * e23,
+            e2: s * u2 - e12 * u1 + e23 * u3 + t * e31,
+            e3: s * u3 + e31 * u1 - e23 * u2 + t * e12,
+        }
+    }
+
+    /// Apply the sandwich `R B R~` to a bivector, by rotating its dual vector.
+    pub fn apply_bivector(self, b: Bivector<T>) -> Bivector<T> {
+        let v = self.apply(Vector { e1: b.e23, e2: b.e31, e3: b.e12 });
+        Bivector { e12: v.e3, e23: v.e1, e31: v.e2 }
+    }
+}
+
+impl<T: Scalar> Default for Vector<T> {
+    fn default() -> Self {
+        Self::new(T::zero(), T::zero(), T::zero())
+    }
+}
+
+impl<T: Scalar> Default for Bivector<T> {
+    fn default() -> Self {
+        Self::new(T::zero(), T::zero(), T::zero())
+    }
+}
+
+impl<T: Scalar> Default for Trivector<T> {
+    fn default() -> Self {
+        Self::new(T::zero())
+    }
+}
+
+impl<T: Scalar> Default for Rotor<T> {
+    fn default() -> Self {
+        Self::new(T::one(), T::zero(), T::zero(), T::zero())
+    }
+}
+
+// Ops impl
+impl<T: Scalar> Add for Vector<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self { e1: self.e1 + rhs.e1, e2: self.e2 + rhs.e2, e3: self.e3 + rhs.e3 }
+    }
+}
+
+impl<T: Scalar> Sub for Vector<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self { e1: self.e1 - rhs.e1, e2: self.e2 - rhs.e2, e3: self.e3 - rhs.e3 }
+    }
+}
+
+impl<T: Scalar> Neg for Vector<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { e1: -self.e1, e2: -self.e2, e3: -self.e3 }
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vector<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Self { e1: self.e1 * rhs, e2: self.e2 * rhs, e3: self.e3 * rhs }
+    }
+}
+
+/// Geometric product of two vectors: `a.b + a^b`.
+impl<T: Scalar> Mul for Vector<T> {
+    type Output = Rotor<T>;
+    fn mul(self, rhs: Self) -> Rotor<T> {
+        let b = self.wedge(rhs);
+        Rotor { s: self.dot(rhs), e12: b.e12, e23: b.e23, e31: b.e31 }
+    }
+}
+
+/// Vector times pseudoscalar is the dual bivector.
+impl<T: Scalar> Mul<Trivector<T>> for Vector<T> {
+    type Output = Bivector<T>;
+    fn mul(self, rhs: Trivector<T>) -> Bivector<T> {
+        Bivector { e12: self.e3 * rhs.e123, e23: self.e1 * rhs.e123, e31: self.e2 * rhs.e123 }
+    }
+}
+
+impl<T: Scalar> Add for Bivector<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self { e12: self.e12 + rhs.e12, e23: self.e23 + rhs.e23, e31: self.e31 + rhs.e31 }
+    }
+}
+
+impl<T: Scalar> Sub for Bivector<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self { e12: self.e12 - rhs.e12, e23: self.e23 - rhs.e23, e31: self.e31 - rhs.e31 }
+    }
+}
+
+impl<T: Scalar> Neg for Bivector<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { e12: -self.e12, e23: -self.e23, e31: -self.e31 }
+    }
+}
+
+impl<T: Scalar> Mul<T> for Bivector<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Self { e12: self.e12 * rhs, e23: self.e23 * rhs, e31: self.e31 * rhs }
+    }
+}
+
+/// Geometric product of two bivectors: scalar + bivector (there is no grade 4 in 3D).
+impl<T: Scalar> Mul for Bivector<T> {
+    type Output = Rotor<T>;
+    fn mul(self, rhs: Self) -> Rotor<T> {
+        let (a, b) = (self, rhs);
+        Rotor {
+            s: -(a.e12 * b.e12 + a.e23 * b.e23 + a.e31 * b.e31),
+            e12: a.e31 * b.e23 - a.e23 * b.e31,
+            e23: a.e12 * b.e31 - a.e31 * b.e12,
+            e31: a.e23 * b.e12 - a.e12 * b.e23,
+        }
+    }
+}
+
+/// Bivector times pseudoscalar is the (negated) dual vector.
+impl<T: Scalar> Mul<Trivector<T>> for Bivector<T> {
+    type Output = Vector<T>;
+    fn mul(self, rhs: Trivector<T>) -> Vector<T> {
+        Vector { e1: -self.e23 * rhs.e123, e2: -self.e31 * rhs.e123, e3: -self.e12 * rhs.e123 }
+    }
+}
+
+impl<T: Scalar> Add for Trivector<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self { e123: self.e123 + rhs.e123 }
+    }
+}
+
+impl<T: Scalar> Sub for Trivector<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self { e123: self.e123 - rhs.e123 }
+    }
+}
+
+impl<T: Scalar> Neg for Trivector<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { e123: -self.e123 }
+    }
+}
+
+impl<T: Scalar> Mul<T> for Trivector<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Self { e123: self.e123 * rhs }
+    }
+}
+
+/// `e123 * e123 = -1`.
+impl<T: Scalar> Mul for Trivector<T> {
+    type Output = T;
+    fn mul(self, rhs: Self) -> T {
+        -self.e123 * rhs.e123
+    }
+}
+
+// The pseudoscalar commutes with everything in Cl(3,0).
+impl<T: Scalar> Mul<Vector<T>> for Trivector<T> {
+    type Output = Bivector<T>;
+    fn mul(self, rhs: Vector<T>) -> Bivector<T> {
+        rhs * self
+    }
+}
+
+impl<T: Scalar> Mul<Bivector<T>> for Trivector<T> {
+    type Output = Vector<T>;
+    fn mul(self, rhs: Bivector<T>) -> Vector<T> {
+        rhs * self
+    }
+}
+
+impl<T: Scalar> Add for Rotor<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self { s: self.s + rhs.s, e12: self.e12 + rhs.e12, e23: self.e23 + rhs.e23, e31: self.e31 + rhs.e31 }
+    }
+}
+
+impl<T: Scalar> Sub for Rotor<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self { s: self.s - rhs.s, e12: self.e12 - rhs.e12, e23: self.e23 - rhs.e23, e31: self.e31 - rhs.e31 }
+    }
+}
+
+impl<T: Scalar> Neg for Rotor<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { s: -self.s, e12: -self.e12, e23: -self.e23, e31: -self.e31 }
+    }
+}
+
+impl<T: Scalar> Mul<T> for Rotor<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Self { s: self.s * rhs, e12: self.e12 * rhs, e23: self.e23 * rhs, e31: self.e31 * rhs }
+    }
+}
+
+/// Composition of rotors: the even subalgebra is closed under the geometric product.
+impl<T: Scalar> Mul for Rotor<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let bb = self.bivector() * rhs.bivector();
+        Self {
+            s: self.s * rhs.s + bb.s,
+            e12: self.s * rhs.e12 + rhs.s * self.e12 + bb.e12,
+            e23: self.s * rhs.e23 + rhs.s * self.e23 + bb.e23,
+            e31: self.s * rhs.e31 + rhs.s * self.e31 + bb.e31,
+        }
+    }
+}
+
+// Conversions to and from MultiVector.
+// Converting from a MultiVector projects onto the target grade(s) and drops the rest.
+impl<T: Scalar> From<Vector<T>> for MultiVector<T> {
+    fn from(v: Vector<T>) -> Self {
+        let mut c = [T::zero(); 8];
+        (c[1], c[2], c[3]) = (v.e1, v.e2, v.e3);
+        MultiVector::new(c)
+    }
+}
+
+impl<T: Scalar> From<Bivector<T>> for MultiVector<T> {
+    fn from(b: Bivector<T>) -> Self {
+        let mut c = [T::zero(); 8];
+        (c[4], c[5], c[6]) = (b.e12, b.e23, b.e31);
+        MultiVector::new(c)
+    }
+}
+
+impl<T: Scalar> From<Trivector<T>> for MultiVector<T> {
+    fn from(t: Trivector<T>) -> Self {
+        let mut c = [T::zero(); 8];
+        c[7] = t.e123;
+        MultiVector::new(c)
+    }
+}
+
+impl<T: Scalar> From<Rotor<T>> for MultiVector<T> {
+    fn from(r: Rotor<T>) -> Self {
+        let mut c = [T::zero(); 8];
+        (c[0], c[4], c[5], c[6]) = (r.s, r.e12, r.e23, r.e31);
+        MultiVector::new(c)
+    }
+}
+
+impl<T: Scalar> From<MultiVector<T>> for Vector<T> {
+    fn from(m: MultiVector<T>) -> Self {
+        let c = m.coeffs;
+        Self { e1: c[1], e2: c[2], e3: c[3] }
+    }
+}
+
+impl<T: Scalar> From<MultiVector<T>> for Bivector<T> {
+    fn from(m: MultiVector<T>) -> Self {
+        let c = m.coeffs;
+        Self { e12: c[4], e23: c[5], e31: c[6] }
+    }
+}
+
+impl<T: Scalar> From<MultiVector<T>> for Trivector<T> {
+    fn from(m: MultiVector<T>) -> Self {
+        Self { e123: m.coeffs[7] }
+    }
+}
+
+impl<T: Scalar> From<MultiVector<T>> for Rotor<T> {
+    fn from(m: MultiVector<T>) -> Self {
+        let c = m.coeffs;
+        Self { s: c[0], e12: c[4], e23: c[5], e31: c[6] }
+    }
+}
+
+impl<T: Scalar> From<Vector<T>> for [T; 3] {
+    fn from(v: Vector<T>) -> Self {
+        [v.e1, v.e2, v.e3]
+    }
+}
+
+impl<T: Scalar> From<[T; 3]> for Vector<T> {
+    fn from(a: [T; 3]) -> Self {
+        Self { e1: a[0], e2: a[1], e3: a[2] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_plane_gives_identity() {
+        assert_eq!(Rotor::from_plane_angle(Bivector::ZERO, 1.0), Rotor::IDENTITY);
+        let v = Vector::new(1.0, 2.0, 3.0);
+        assert_eq!(Rotor::from_plane_angle(Bivector::ZERO, 0.5).apply(v), v);
+    }
+
+    #[test]
+    fn plane_angle_turns_e1_towards_e2() {
+        let r = Rotor::from_plane_angle(Bivector::new(2.0, 0.0, 0.0), std::f64::consts::FRAC_PI_2);
+        let v = r.apply(Vector::new(1.0, 0.0, 0.0));
+        assert!((v - Vector::new(0.0, 1.0, 0.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn exp_matches_closed_form_across_the_series_threshold() {
+        for &t in &[0.0, 1e-9, 1e-4, 9.9e-4, 1.01e-3, 0.5, 3.0] {
+            let r = Bivector::new(0.0, t, 0.0).exp();
+            assert!((r.s - f64::cos(t)).abs() < 1e-15 && (r.e23 - f64::sin(t)).abs() < 1e-15, "{t}");
+        }
+    }
+
+    #[test]
+    fn log_inverts_exp() {
+        let tiny = Bivector::new(1e-20, 0.0, 0.0);
+        for b in [Bivector::ZERO, tiny, Bivector::new(0.3, -1.1, 0.4), Bivector::new(0.0, 3.0, 0.0)] {
+            let l = b.exp().log();
+            assert!((l - b).norm() < 1e-12, "{:?}", b);
+        }
+        let minus_one = Rotor::new(-1.0, 0.0, 0.0, 0.0);
+        let l = minus_one.log();
+        assert!((l.norm() - std::f64::consts::PI).abs() < 1e-15);
+        let back = l.exp();
+        assert!((back.s + 1.0).abs() < 1e-15 && back.bivector().norm() < 1e-15);
+    }
+
+    #[test]
+    fn exp_differentiates_through_zero() {
+        use math_universe_zigen::Dual;
+        // d/dt exp(t e12) at t = 0 is e12
+        let r = Bivector::new(Dual::variable(0.0), Dual::constant(0.0), Dual::constant(0.0)).exp();
+        assert_eq!((r.s.real, r.s.dual), (1.0, 0.0));
+        assert_eq!((r.e12.real, r.e12.dual), (0.0, 1.0));
+        assert_eq!(r.e23.dual, 0.0);
+    }
+
+    #[test]
+    fn pose_error_gradient_through_apply() {
+        use math_universe_zigen::Dual;
+        let c = Dual::constant;
+        // rotating e1 by t in the e12 plane gives (cos t, sin t, 0); against e2 the squared
+        // error is 2 - 2 sin t, with derivative -2 cos t
+        for t in [0.0, 0.4, 1.3, -2.0] {
+            let r = Rotor::from_plane_angle(Bivector::new(c(1.0), c(0.0), c(0.0)), Dual::variable(t));
+            let d = r.apply(Vector::new(c(1.0), c(0.0), c(0.0))) - Vector::new(c(0.0), c(1.0), c(0.0));
+            let e = d.norm_squared();
+            assert!((e.real - (2.0 - 2.0 * f64::sin(t))).abs() < 1e-12);
+            assert!((e.dual + 2.0 * f64::cos(t)).abs() < 1e-12);
+        }
+
+        // gradient of |exp(B) v exp(B)~ - w|^2 in each bivector component, by finite differences
+        let (b, v, w) = ([0.3, -0.7, 0.2], [0.5, 1.0, -2.0], [1.0, 0.0, 1.5]);
+        let error = |b: [Dual<f64>; 3]| {
+            let r = Bivector::new(b[0], b[1], b[2]).exp();
+            (r.apply(Vector::new(c(v[0]), c(v[1]), c(v[2]))) - Vector::new(c(w[0]), c(w[1]), c(w[2]))).norm_squared()
+        };
+        for k in 0..3 {
+            let seeded = std::array::from_fn(|i| if i == k { Dual::variable(b[i]) } else { c(b[i]) });
+            let shifted = |h: f64| error(std::array::from_fn(|i| c(b[i] + if i == k { h } else { 0.0 }))).real;
+            let h = 1e-6;
+            let numeric = (shifted(h) - shifted(-h)) / (2.0 * h);
+            assert!((error(seeded).dual - numeric).abs() < 1e-7, "component {}", k);
+        }
+    }
+}
@@ -1,5 +1,25 @@
 pub mod multivector;
+pub mod grade;
+pub mod batch;
+pub mod cga;
+pub mod pga;
+pub mod blade;
+pub mod register;
+pub mod integrate;
+pub mod notation;
+pub mod matrix;
+mod rng;
 pub use multivector::MultiVector;
+pub use notation::ParseMultiVectorError;
+pub use grade::{Bivector, Rotor, Trivector, Vector};
+pub use batch::MultiVectorBatch;
+pub use cga::CgaMultiVector;
+pub use pga::{Motor, PgaMultiVector, RigidBody};
+pub use integrate::{FreeRigidBody, LieMethod};
+pub use register::{
+    fit_rotor, register, register_ransac, register_weighted, PointSet, RansacOptions, RansacRegistration, Registration,
+    RegistrationError,
+};
 
 pub fn hello() {
     println!("Hello from Shinen!");
@@ -11,33 +31,47 @@ use pyo3::prelude::*;
 #[cfg(feature = "python")]
 pub fn register_module(_py: Python, m: &PyModule) -> PyResult<&PyModule> {
     let submodule = PyModule::new(_py, "shinen")?;
-    m.add_class::<MultiVector>()?;
+    m.add_class::<PyMultiVector>()?;
     m.add_submodule(submodule)?;
     Ok(submodule)
 }
 
+#[cfg(feature = "python")]
+#[pyclass(name = "MultiVector")]
+#[derive(Clone, Copy)]
+pub struct PyMultiVector {
+    pub inner: MultiVector,
+}
+
 #[cfg(feature = "python")]
 #[pymethods]
-impl MultiVector {
+impl PyMultiVector {
     #[new]
     fn py_new(coeffs: Vec<f64>) -> Self {
         let mut c = [0.0; 8];
         for (i, v) in coeffs.iter().enumerate().take(8) {
             c[i] = *v;
         }
-        Self { coeffs: c }
+        Self { inner: MultiVector::new(c) }
     }
     
+    /// Parse blade notation such as `"1 + 2e1 - 0.5e12"`.
+    #[staticmethod]
+    fn from_str(s: &str) -> PyResult<Self> {
+        s.parse()
+            .map(|inner| Self { inner })
+            .map_err(|e: ParseMultiVectorError| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn to_latex(&self) -> String {
+        format!("{}", self.inner.latex())
+    }
+
     fn __repr__(&self) -> String {
-        format!("{}", self)
+        format!("{}", self.inner)
     }
 
-    fn __add__(&self, rhs: MultiVector) -> MultiVector { *self + rhs }
-    fn __sub__(&self, rhs: MultiVector) -> MultiVector { *self - rhs }
-    fn __mul__(&self, rhs: MultiVector) -> MultiVector { *self * rhs }
+    fn __add__(&self, rhs: PyMultiVector) -> PyMultiVector { PyMultiVector { inner: self.inner + rhs.inner } }
+    fn __sub__(&self, rhs: PyMultiVector) -> PyMultiVector { PyMultiVector { inner: self.inner - rhs.inner } }
+    fn __mul__(&self, rhs: PyMultiVector) -> PyMultiVector { PyMultiVector { inner: self.inner * rhs.inner } }
 }
-
-// We need to add #[pyclass] to MultiVector in multivector.rs, OR wrap it here.
-// Since MultiVector is not generic, we can put #[pyclass] directly on it in multivector.rs if we add pyo3 dependency to shinen.
-// I will wrap it here if I didn't add pyo3 to shinen.
-// But I should add pyo3 to shinen as well.
@@ -0,0 +1,129 @@
+//! Matrix representations of Cl(3,0): the Pauli isomorphism with 2x2 complex matrices
+//! and 3x3 rotation matrices of rotors.
+
+use num_complex::Complex;
+
+use math_universe_zigen::Scalar;
+
+use crate::grade::{Rotor, Vector};
+use crate::multivector::MultiVector;
+
+/// A 2x2 complex matrix, row-major.
+pub type Matrix2<T = f64> = [[Complex<T>; 2]; 2];
+
+/// A 3x3 real matrix, row-major, acting on column vectors.
+pub type Matrix3<T = f64> = [[T; 3]; 3];
+
+/// The Pauli matrices `sigma_1, sigma_2, sigma_3`, images of `e1, e2, e3`.
+pub fn pauli_matrices<T: Scalar>() -> [Matrix2<T>; 3] {
+    let (o, l, i) = (Complex::new(T::zero(), T::zero()), Complex::new(T::one(), T::zero()), Complex::new(T::zero(), T::one()));
+    [[[o, l], [l, o]], [[o, -i], [i, o]], [[l, o], [o, -l]]]
+}
+
+impl<T: Scalar> MultiVector<T> {
+    /// Image under the algebra isomorphism Cl(3,0) -> M2(C) sending `e_k` to `sigma_k`.
+    ///
+    /// The pseudoscalar `e123` maps to `i` times the identity, so the geometric product
+    /// becomes the matrix product: `(a * b).to_pauli() == a.to_pauli() x b.to_pauli()`.
+    pub fn to_pauli(&self) -> Matrix2<T> {
+        let c = &self.coeffs;
+        // x_0 I + x_k sigma_k with x_0 = 1 + e123, x_1 = e1 + e23, x_2 = e2 + e31, x_3 = e3 + e12
+        let alpha = Complex::new(c[0], c[7]);
+        let x = Complex::new(c[1], c[5]);
+        let y = Complex::new(c[2], c[6]);
+        let z = Complex::new(c[3], c[4]);
+        let iy = Complex::new(-y.im, y.re);
+        [[alpha + z, x - iy], [x + iy, alpha - z]]
+    }
+
+    /// Inverse of `to_pauli`: every 2x2 complex matrix is the image of exactly one multivector.
+    pub fn from_pauli(m: &Matrix2<T>) -> Self {
+        let two = T::one() + T::one();
+        let alpha = (m[0][0] + m[1][1]) / two;
+        let z = (m[0][0] - m[1][1]) / two;
+        let x = (m[0][1] + m[1][0]) / two;
+        // i y = (m10 - m01) / 2
+        let iy = (m[1][0] - m[0][1]) / two;
+        let y = Complex::new(iy.im, -iy.re);
+        MultiVector::new([alpha.re, x.re, y.re, z.re, z.im, x.im, y.im, alpha.im])
+    }
+}
+
+impl<T: Scalar> Rotor<T> {
+    /// The 3x3 rotation matrix `m` with `m v == R v R~` for a unit rotor.
+    ///
+    /// Columns are the images of `e1, e2, e3`; a non-unit rotor gives the rotation
+    /// scaled by `|R|^2`.
+    pub fn to_rotation_matrix(self) -> Matrix3<T> {
+        let o = T::zero();
+        let cols = [
+            self.apply(Vector::new(T::one(), o, o)),
+            self.apply(Vector::new(o, T::one(), o)),
+            self.apply(Vector::new(o, o, T::one())),
+        ];
+        [
+            [cols[0].e1, cols[1].e1, cols[2].e1],
+            [cols[0].e2, cols[1].e2, cols[2].e2],
+            [cols[0].e3, cols[1].e3, cols[2].e3],
+        ]
+    }
+
+    /// Unit rotor of a proper rotation matrix (Shepperd's method, stable for all angles).
+    ///
+    /// The sign of the result is chosen with a non-negative scalar part; `R` and `-R`
+    /// encode the same rotation.
+    pub fn from_rotation_matrix(m: &Matrix3<T>) -> Self {
+        let one = T::one();
+        let quarter = one / (one + one + one + one);
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        // quaternion (w, x, y, z) about axis (x, y, z); the rotor's bivector is the negated dual of the axis
+        let candidates = [trace, m[0][0] - m[1][1] - m[2][2], m[1][1] - m[0][0] - m[2][2], m[2][2] - m[0][0] - m[1][1]];
+        let mut best = 0;
+        for k in 1..4 {
+            if candidates[k] > candidates[best] {
+                best = k;
+            }
+        }
+        let d = (one + candidates[best]).sqrt() * (one + one);
+        let (w, x, y, z) = match best {
+            0 => (d * quarter, (m[2][1] - m[1][2]) / d, (m[0][2] - m[2][0]) / d, (m[1][0] - m[0][1]) / d),
+            1 => ((m[2][1] - m[1][2]) / d, d * quarter, (m[0][1] + m[1][0]) / d, (m[0][2] + m[2][0]) / d),
+            2 => ((m[0][2] - m[2][0]) / d, (m[0][1] + m[1][0]) / d, d * quarter, (m[1][2] + m[2][1]) / d),
+            _ => ((m[1][0] - m[0][1]) / d, (m[0][2] + m[2][0]) / d, (m[1][2] + m[2][1]) / d, d * quarter),
+        };
+        let r = Rotor::new(w, -z, -x, -y);
+        if w < T::zero() { -r } else { r }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::Bivector;
+
+    #[test]
+    fn pauli_images_of_the_basis() {
+        let [s1, s2, s3] = pauli_matrices::<f64>();
+        assert_eq!(MultiVector::new([0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]).to_pauli(), s1);
+        assert_eq!(MultiVector::new([0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).to_pauli(), s2);
+        assert_eq!(MultiVector::new([0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]).to_pauli(), s3);
+        let i = MultiVector::new([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]).to_pauli();
+        let (o, j) = (Complex::new(0.0, 0.0), Complex::new(0.0, 1.0));
+        assert_eq!(i, [[j, o], [o, j]]);
+    }
+
+    #[test]
+    fn rotation_matrix_round_trip() {
+        // includes half-turns, where the trace branch of Shepperd's method is unusable
+        let planes = [Bivector::new(1.0, 0.0, 0.0), Bivector::new(0.0, 1.0, 0.0), Bivector::new(0.3, -0.4, 0.9)];
+        for plane in planes {
+            for angle in [0.0, 0.7, 2.0, std::f64::consts::PI, -3.0] {
+                let r = Rotor::from_plane_angle(plane, angle);
+                let back = Rotor::from_rotation_matrix(&r.to_rotation_matrix());
+                let same = |s: f64| (r.s - s * back.s).abs() + (r.bivector() - back.bivector() * s).norm() < 1e-12;
+                assert!(same(1.0) || same(-1.0), "{:?} {}", plane, angle);
+                assert!(back.s >= 0.0);
+            }
+        }
+    }
+}
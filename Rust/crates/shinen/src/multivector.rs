@@ -1,24 +1,43 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, Mul, Neg, Sub};
 use std::fmt;
 
-#[cfg(feature = "python")]
-use pyo3::prelude::*;
+use math_universe_zigen::Scalar;
+
+use crate::notation::{self, Latex};
 
 /// MultiVector in Cl(3,0) Geometric Algebra.
-#[cfg_attr(feature = "python", pyclass)]
+///
+/// Generic over the coefficient type: `f64` by default, `f32` for graphics work, or
+/// `zigen::Dual<f64>` to differentiate geometric computations.
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct MultiVector {
-    pub coeffs: [f64; 8],
+pub struct MultiVector<T: Scalar = f64> {
+    pub coeffs: [T; 8],
 }
 
-impl MultiVector {
+impl MultiVector<f64> {
     pub const ZERO: Self = Self { coeffs: [0.0; 8] };
     pub const ONE: Self = Self { coeffs: [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0] };
+}
 
-    pub fn new(coeffs: [f64; 8]) -> Self {
+impl<T: Scalar> MultiVector<T> {
+    pub fn new(coeffs: [T; 8]) -> Self {
         Self { coeffs }
     }
 
+    pub fn zero() -> Self {
+        Self { coeffs: [T::zero(); 8] }
+    }
+
+    pub fn one() -> Self {
+        Self::scalar(T::one())
+    }
+
+    pub fn scalar(s: T) -> Self {
+        let mut c = [T::zero(); 8];
+        c[0] = s;
+        Self { coeffs: c }
+    }
+
     /// Reversion (~): Reverses the order of vectors in the product.
     /// scalar, vector -> same
     /// bivector -> negative
@@ -40,13 +59,23 @@ impl MultiVector {
     pub fn rotate(self, rotor: Self) -> Self {
         rotor * self * rotor.reverse()
     }
+
+    /// LaTeX form for `Display`, e.g. `format!("{:.2}", mv.latex())`.
+    pub fn latex(&self) -> Latex<'_, T> {
+        Latex(self)
+    }
+
+    /// Convert the coefficients to another scalar type, e.g. lift `f64` into `Dual<f64>`.
+    pub fn map<U: Scalar>(self, f: impl Fn(T) -> U) -> MultiVector<U> {
+        MultiVector { coeffs: self.coeffs.map(f) }
+    }
 }
 
 // Ops impl
-impl Add for MultiVector {
+impl<T: Scalar> Add for MultiVector<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        let mut res = Self::ZERO;
+        let mut res = Self::zero();
         for i in 0..8 {
             res.coeffs[i] = self.coeffs[i] + rhs.coeffs[i];
         }
@@ -54,10 +83,10 @@ impl Add for MultiVector {
     }
 }
 
-impl Sub for MultiVector {
+impl<T: Scalar> Sub for MultiVector<T> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
-        let mut res = Self::ZERO;
+        let mut res = Self::zero();
         for i in 0..8 {
             res.coeffs[i] = self.coeffs[i] - rhs.coeffs[i];
         }
@@ -65,10 +94,10 @@ impl Sub for MultiVector {
     }
 }
 
-impl Neg for MultiVector {
+impl<T: Scalar> Neg for MultiVector<T> {
     type Output = Self;
     fn neg(self) -> Self {
-        let mut res = Self::ZERO;
+        let mut res = Self::zero();
         for i in 0..8 {
             res.coeffs[i] = -self.coeffs[i];
         }
@@ -76,27 +105,45 @@ impl Neg for MultiVector {
     }
 }
 
-impl Mul for MultiVector {
+impl<T: Scalar> Mul for MultiVector<T> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
-        let a = self.coeffs;
-        let b = rhs.coeffs;
-        let mut c = [0.0; 8];
-        c[0] = a[0]*b[0] + a[1]*b[1] + a[2]*b[2] + a[3]*b[3] - a[4]*b[4] - a[5]*b[5] - a[6]*b[6] - a[7]*b[7];
-        c[1] = a[0]*b[1] + a[1]*b[0] - a[2]*b[4] + a[3]*b[6] + a[4]*b[2] - a[6]*b[3] - a[7]*b[5] - a[5]*b[7];
-        c[2] = a[0]*b[2] + a[1]*b[4] + a[2]*b[0] - a[3]*b[5] - a[4]*b[1] + a[5]*b[3] - a[7]*b[6] - a[6]*b[7];
-        c[3] = a[0]*b[3] - a[1]*b[6] + a[2]*b[5] + a[3]*b[0] + a[6]*b[1] - a[5]*b[2] - a[7]*b[4] - a[4]*b[7];
-        c[4] = a[0]*b[4] + a[1]*b[2] - a[2]*b[1] + a[4]*b[0] - a[6]*b[5] + a[5]*b[6] + a[3]*b[7] + a[7]*b[3]; 
-        c[5] = a[0]*b[5] + a[2]*b[3] - a[3]*b[2] + a[5]*b[0] - a[4]*b[6] + a[6]*b[4] + a[1]*b[7] + a[7]*b[1];
-        c[6] = a[0]*b[6] + a[3]*b[1] - a[1]*b[3] + a[6]*b[0] - a[5]*b[4] + a[4]*b[5] + a[2]*b[7] + a[7]*b[2];
-        c[7] = a[0]*b[7] + a[7]*b[0] + a[1]*b[5] + a[2]*b[6] + a[3]*b[4] + a[5]*b[1] + a[6]*b[2] + a[4]*b[3];
-        Self { coeffs: c }
+        Self { coeffs: geometric_product(&self.coeffs, &rhs.coeffs) }
+    }
+}
+
+/// Geometric product on raw coefficient arrays.
+///
+/// Generic over the lane type so the batch kernels can run the same expansion on SIMD lanes.
+#[inline(always)]
+pub(crate) fn geometric_product<T>(a: &[T; 8], b: &[T; 8]) -> [T; 8]
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    [
+        a[0]*b[0] + a[1]*b[1] + a[2]*b[2] + a[3]*b[3] - a[4]*b[4] - a[5]*b[5] - a[6]*b[6] - a[7]*b[7],
+        a[0]*b[1] + a[1]*b[0] - a[2]*b[4] + a[3]*b[6] + a[4]*b[2] - a[6]*b[3] - a[7]*b[5] - a[5]*b[7],
+        a[0]*b[2] + a[1]*b[4] + a[2]*b[0] - a[3]*b[5] - a[4]*b[1] + a[5]*b[3] - a[7]*b[6] - a[6]*b[7],
+        a[0]*b[3] - a[1]*b[6] + a[2]*b[5] + a[3]*b[0] + a[6]*b[1] - a[5]*b[2] - a[7]*b[4] - a[4]*b[7],
+        a[0]*b[4] + a[1]*b[2] - a[2]*b[1] + a[4]*b[0] + a[6]*b[5] - a[5]*b[6] + a[3]*b[7] + a[7]*b[3],
+        a[0]*b[5] + a[2]*b[3] - a[3]*b[2] + a[5]*b[0] + a[4]*b[6] - a[6]*b[4] + a[1]*b[7] + a[7]*b[1],
+        a[0]*b[6] + a[3]*b[1] - a[1]*b[3] + a[6]*b[0] + a[5]*b[4] - a[4]*b[5] + a[2]*b[7] + a[7]*b[2],
+        a[0]*b[7] + a[7]*b[0] + a[1]*b[5] + a[2]*b[6] + a[3]*b[4] + a[5]*b[1] + a[6]*b[2] + a[4]*b[3],
+    ]
+}
+
+impl<T: Scalar> Mul<T> for MultiVector<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Self { coeffs: self.coeffs.map(|c| c * rhs) }
     }
 }
 
 // Display
-impl fmt::Display for MultiVector {
+/// Blade notation such as `1 + 2e1 - 0.5e12 + e123`; zero terms are omitted and the
+/// formatter precision applies to every coefficient.
+impl<T: Scalar> fmt::Display for MultiVector<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.coeffs)
+        notation::write_blades(f, &self.coeffs, &notation::CL3_NAMES, false)
     }
 }
@@ -0,0 +1,236 @@
+//! Human-readable basis-blade notation: `1 + 2e1 - 0.5e12 + e123`.
+//!
+//! `Display` for the multivector types writes this form, honouring the formatter
+//! precision (`{:.3}`); `MultiVector::latex` wraps a value for LaTeX output, and
+//! `MultiVector` implements `FromStr` for the same notation.
+
+use std::fmt;
+use std::str::FromStr;
+
+use math_universe_zigen::Scalar;
+
+use crate::blade::{blade_product, BasisLayout};
+use crate::multivector::MultiVector;
+
+/// Blade names of the `MultiVector` coefficient layout.
+pub(crate) const CL3_NAMES: [&str; 8] = ["1", "e1", "e2", "e3", "e12", "e23", "e31", "e123"];
+
+/// Write `coeffs` as a signed sum of named blades, skipping zero terms (including
+/// terms that round to zero at the formatter's precision).
+pub(crate) fn write_blades<T, N>(f: &mut fmt::Formatter<'_>, coeffs: &[T], names: &[N], latex: bool) -> fmt::Result
+where
+    T: Scalar,
+    N: AsRef<str>,
+{
+    let mut first = true;
+    for (&c, name) in coeffs.iter().zip(names) {
+        let name = name.as_ref();
+        let negative = c < T::zero();
+        let magnitude = if negative { -c } else { c };
+        let digits = match f.precision() {
+            Some(p) => format!("{:.*}", p, magnitude),
+            None => format!("{}", magnitude),
+        };
+        if c.is_zero() || digits.chars().all(|ch| ch == '0' || ch == '.') {
+            continue;
+        }
+        let sign = match (first, negative) {
+            (true, false) => "",
+            (true, true) => "-",
+            (false, false) => " + ",
+            (false, true) => " - ",
+        };
+        f.write_str(sign)?;
+        if name == "1" {
+            f.write_str(&digits)?;
+        } else {
+            if !magnitude.is_one() {
+                f.write_str(&digits)?;
+                if latex {
+                    f.write_str(" ")?;
+                }
+            }
+            match (latex, name.strip_prefix('e')) {
+                (true, Some(index)) => write!(f, "e_{{{}}}", index)?,
+                _ => f.write_str(name)?,
+            }
+        }
+        first = false;
+    }
+    if first {
+        f.write_str("0")?;
+    }
+    Ok(())
+}
+
+/// LaTeX rendering of a multivector, e.g. `1 + 2 e_{1} - 0.5 e_{12}`. Created by `MultiVector::latex`.
+#[derive(Debug, Clone, Copy)]
+pub struct Latex<'a, T: Scalar>(pub &'a MultiVector<T>);
+
+impl<T: Scalar> fmt::Display for Latex<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_blades(f, &self.0.coeffs, &CL3_NAMES, true)
+    }
+}
+
+/// Error returned when parsing a `MultiVector` from blade notation fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMultiVectorError {
+    /// Byte offset of the offending input.
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseMultiVectorError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self { position, message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseMultiVectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseMultiVectorError {}
+
+/// Parses sums of terms like `1 + 2e1 - 0.5e12 + e123`.
+///
+/// A term is an optional coefficient (a decimal number, optionally followed by `*`)
+/// and an optional blade `e` followed by basis indices in any order: `e31`, `e13` and
+/// `e_{13}` are all accepted, with reordering signs applied and repeated indices
+/// contracted (`e11 = 1`).
+/// Exponent notation is not supported since `2e1` means `2 e1`.
+impl<T: Scalar + FromStr> FromStr for MultiVector<T> {
+    type Err = ParseMultiVectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let layout = BasisLayout::cl3();
+        let bytes = s.as_bytes();
+        let mut pos = 0;
+        let skip_ws = |pos: &mut usize| {
+            while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+                *pos += 1;
+            }
+        };
+        let mut coeffs = [T::zero(); 8];
+        let mut terms = 0;
+        skip_ws(&mut pos);
+        while pos < bytes.len() || terms == 0 {
+            // sign: required between terms, optional before the first
+            let mut negative = false;
+            let mut signs = 0;
+            while pos < bytes.len() && (bytes[pos] == b'+' || bytes[pos] == b'-') {
+                negative ^= bytes[pos] == b'-';
+                signs += 1;
+                pos += 1;
+                skip_ws(&mut pos);
+            }
+            if terms > 0 && signs == 0 {
+                return Err(ParseMultiVectorError::new(pos, "expected '+' or '-'"));
+            }
+            let start = pos;
+
+            // coefficient
+            while pos < bytes.len() && (bytes[pos].is_ascii_digit() || bytes[pos] == b'.') {
+                pos += 1;
+            }
+            let coefficient = if pos > start {
+                let c = s[start..pos]
+                    .parse::<T>()
+                    .map_err(|_| ParseMultiVectorError::new(start, format!("invalid number '{}'", &s[start..pos])))?;
+                skip_ws(&mut pos);
+                if pos < bytes.len() && bytes[pos] == b'*' {
+                    pos += 1;
+                    skip_ws(&mut pos);
+                    if pos >= bytes.len() || bytes[pos] != b'e' {
+                        return Err(ParseMultiVectorError::new(pos, "expected a blade after '*'"));
+                    }
+                }
+                Some(c)
+            } else {
+                None
+            };
+
+            // blade
+            let (sign, index) = if pos < bytes.len() && bytes[pos] == b'e' {
+                let blade_start = pos;
+                pos += 1;
+                let braced = s[pos..].starts_with("_{");
+                if braced {
+                    pos += 2;
+                }
+                let mut sign = 1.0;
+                let mut mask = 0;
+                let digits_start = pos;
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    let i = (bytes[pos] - b'0') as usize;
+                    if !(1..=3).contains(&i) {
+                        return Err(ParseMultiVectorError::new(pos, format!("no basis vector e{} in Cl(3,0)", i)));
+                    }
+                    let (s, m) = blade_product(mask, 1 << (i - 1), &[1.0; 3]);
+                    sign *= s;
+                    mask = m;
+                    pos += 1;
+                }
+                if pos == digits_start {
+                    return Err(ParseMultiVectorError::new(blade_start, "expected basis indices after 'e'"));
+                }
+                if braced {
+                    if pos >= bytes.len() || bytes[pos] != b'}' {
+                        return Err(ParseMultiVectorError::new(pos, "expected '}'"));
+                    }
+                    pos += 1;
+                }
+                let (k, orientation) = layout.index_of(mask).expect("Cl(3,0) layout lists every blade");
+                (sign * orientation, k)
+            } else if coefficient.is_some() {
+                (1.0, 0)
+            } else {
+                return Err(ParseMultiVectorError::new(pos, "expected a number or a blade"));
+            };
+
+            let mut value = coefficient.unwrap_or_else(T::one);
+            if (sign < 0.0) != negative {
+                value = -value;
+            }
+            coeffs[index] += value;
+            terms += 1;
+            skip_ws(&mut pos);
+        }
+        Ok(MultiVector::new(coeffs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_latex() {
+        let mv = MultiVector::new([1.0, 2.0, 0.0, 0.0, -0.5, 0.0, 0.0, 1.0]);
+        assert_eq!(mv.to_string(), "1 + 2e1 - 0.5e12 + e123");
+        assert_eq!(mv.latex().to_string(), "1 + 2 e_{1} - 0.5 e_{12} + e_{123}");
+        assert_eq!(format!("{:.2}", MultiVector::new([0.001, -1.0 / 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])), "-0.33e1");
+        assert_eq!(MultiVector::<f64>::new([0.0; 8]).to_string(), "0");
+    }
+
+    #[test]
+    fn parses_and_round_trips() {
+        let mv = MultiVector::new([1.0, 2.0, 0.0, 0.0, -0.5, 0.0, 0.25, 1.0]);
+        assert_eq!(mv.to_string().parse::<MultiVector>(), Ok(mv));
+        // reordered and repeated indices, braces and '*'
+        let parsed: MultiVector = "e13 - 2 * e_{21} + e11".parse().unwrap();
+        assert_eq!(parsed, MultiVector::new([1.0, 0.0, 0.0, 0.0, 2.0, 0.0, -1.0, 0.0]));
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let err = |s: &str| s.parse::<MultiVector>().unwrap_err().position;
+        assert_eq!(err("1 + e4"), 5);
+        assert_eq!(err("1 2"), 2);
+        assert_eq!(err("e"), 0);
+        assert_eq!(err(""), 0);
+    }
+}
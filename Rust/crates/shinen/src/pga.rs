@@ -0,0 +1,640 @@
+use std::ops::Mul;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::blade::{self, blade_algebra, cayley_table, reorder_sign};
+use crate::notation;
+use crate::grade::{Rotor, Vector};
+
+/// Number of basis blades in Cl(3,0,1).
+const BLADES: usize = 16;
+
+/// Squares of the basis vectors `e0, e1, e2, e3`.
+const METRIC: [f64; 4] = [0.0, 1.0, 1.0, 1.0];
+
+const E0: usize = 0b0001;
+const E1: usize = 0b0010;
+const E2: usize = 0b0100;
+const E3: usize = 0b1000;
+const E01: usize = E0 | E1;
+const E02: usize = E0 | E2;
+const E03: usize = E0 | E3;
+const E12: usize = E1 | E2;
+const E13: usize = E1 | E3;
+const E23: usize = E2 | E3;
+const E012: usize = E0 | E1 | E2;
+const E013: usize = E0 | E1 | E3;
+const E023: usize = E0 | E2 | E3;
+const E123: usize = E1 | E2 | E3;
+const E0123: usize = 0b1111;
+
+fn table() -> &'static [(f64, usize)] {
+    static TABLE: OnceLock<Vec<(f64, usize)>> = OnceLock::new();
+    TABLE.get_or_init(|| cayley_table(&METRIC))
+}
+
+/// MultiVector in the projective (plane-based) model Cl(3,0,1) of 3D Euclidean space.
+///
+/// `coeffs` is indexed by basis blade bitmask: bit 0 is the degenerate `e0` (squares to 0),
+/// bits 1..=3 are `e1, e2, e3`. Vectors are planes, bivectors are lines and trivectors
+/// are points; the outer product is the meet and the regressive product is the join.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PgaMultiVector {
+    pub coeffs: [f64; BLADES],
+}
+
+blade_algebra!(PgaMultiVector, table);
+
+impl PgaMultiVector {
+    pub const ZERO: Self = Self { coeffs: [0.0; BLADES] };
+
+    pub fn new(coeffs: [f64; BLADES]) -> Self {
+        Self { coeffs }
+    }
+
+    pub fn scalar(s: f64) -> Self {
+        Self::blade(0, s)
+    }
+
+    /// Single basis blade (by bitmask) with coefficient `value`.
+    pub fn blade(mask: usize, value: f64) -> Self {
+        let mut c = [0.0; BLADES];
+        c[mask] = value;
+        Self { coeffs: c }
+    }
+
+    /// Plane `a x + b y + c z + d = 0`, i.e. `a e1 + b e2 + c e3 + d e0`.
+    pub fn plane(a: f64, b: f64, c: f64, d: f64) -> Self {
+        let mut m = [0.0; BLADES];
+        (m[E1], m[E2], m[E3], m[E0]) = (a, b, c, d);
+        Self { coeffs: m }
+    }
+
+    /// Euclidean point `x e032 + y e013 + z e021 + e123`.
+    pub fn point(p: Vector) -> Self {
+        Self::point_weighted(p, 1.0)
+    }
+
+    /// Direction (ideal point at infinity) `x e032 + y e013 + z e021`.
+    pub fn direction(d: Vector) -> Self {
+        Self::point_weighted(d, 0.0)
+    }
+
+    fn point_weighted(p: Vector, w: f64) -> Self {
+        let mut m = [0.0; BLADES];
+        // e032 = -e023, e021 = -e012
+        (m[E023], m[E013], m[E012], m[E123]) = (-p.e1, p.e2, -p.e3, w);
+        Self { coeffs: m }
+    }
+
+    /// Line through `point` along `direction`.
+    pub fn line(point: Vector, direction: Vector) -> Self {
+        Self::point(point).join(&Self::direction(direction))
+    }
+
+    pub fn line_from_points(a: Vector, b: Vector) -> Self {
+        Self::point(a).join(&Self::point(b))
+    }
+
+    pub fn plane_from_points(a: Vector, b: Vector, c: Vector) -> Self {
+        Self::line_from_points(a, b).join(&Self::point(c))
+    }
+
+    // Products
+
+    /// Outer product; the meet (intersection) of planes, lines and points.
+    pub fn meet(&self, rhs: &Self) -> Self {
+        self.product_where(rhs, |i, j, _| i & j == 0)
+    }
+
+    /// Regressive product; the join of points and lines.
+    pub fn join(&self, rhs: &Self) -> Self {
+        self.dual().meet(&rhs.dual()).undual()
+    }
+
+    /// Symmetric inner product (grade `|r - s|` part of the geometric product).
+    pub fn inner(&self, rhs: &Self) -> Self {
+        self.product_where(rhs, |i, j, k| blade::grade(k) == blade::grade(i).abs_diff(blade::grade(j)))
+    }
+
+    /// Commutator product `(AB - BA) / 2`.
+    pub fn commutator(&self, rhs: &Self) -> Self {
+        (*self * *rhs - *rhs * *self) * 0.5
+    }
+
+    /// Poincaré dual (right complement): maps each blade `b` to the blade `c` with
+    /// `b ^ c = e0123`. Unlike `X I^-1` this is well defined although `e0` is null.
+    pub fn dual(&self) -> Self {
+        let mut c = [0.0; BLADES];
+        for (m, v) in self.coeffs.iter().enumerate() {
+            let comp = E0123 ^ m;
+            c[comp] = reorder_sign(m, comp) * v;
+        }
+        Self { coeffs: c }
+    }
+
+    /// Inverse of `dual`.
+    pub fn undual(&self) -> Self {
+        let mut c = [0.0; BLADES];
+        for (comp, v) in self.coeffs.iter().enumerate() {
+            let m = E0123 ^ comp;
+            c[m] = reorder_sign(m, comp) * v;
+        }
+        Self { coeffs: c }
+    }
+
+    // Unary operations
+
+    /// Euclidean norm, ignoring all components containing `e0`.
+    pub fn norm(&self) -> f64 {
+        self.coeffs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i & E0 == 0)
+            .map(|(_, v)| v * v)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Ideal norm: the norm of the `e0` components, used for ideal (infinite) elements.
+    pub fn ideal_norm(&self) -> f64 {
+        self.coeffs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i & E0 != 0)
+            .map(|(_, v)| v * v)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        *self * (1.0 / self.norm())
+    }
+
+    // Euclidean extraction
+
+    /// Euclidean location of a (finite) point.
+    pub fn to_point(&self) -> Option<Vector> {
+        let c = &self.coeffs;
+        let w = c[E123];
+        if w.abs() <= f64::EPSILON {
+            return None;
+        }
+        Some(Vector::new(-c[E023], c[E013], -c[E012]) * (1.0 / w))
+    }
+
+    /// Unit normal `(a, b, c)` and offset `d` of a plane `a x + b y + c z + d = 0`.
+    pub fn plane_params(&self) -> Option<(Vector, f64)> {
+        let c = &self.coeffs;
+        let n = Vector::new(c[E1], c[E2], c[E3]);
+        let len = n.norm();
+        if len <= f64::EPSILON {
+            return None;
+        }
+        Some((n * (1.0 / len), c[E0] / len))
+    }
+
+    /// Line (bivector) from its Plücker coordinates: Euclidean `direction` and `moment`
+    /// (`p x direction` for a line through `p`).
+    ///
+    /// The same layout encodes velocity lines (angular velocity, linear velocity),
+    /// forques (force, torque) and momentum lines (linear momentum, angular momentum).
+    pub fn from_line_parts(direction: Vector, moment: Vector) -> Self {
+        let mut c = [0.0; BLADES];
+        (c[E23], c[E13], c[E12]) = (direction.e1, -direction.e2, direction.e3);
+        (c[E01], c[E02], c[E03]) = (moment.e1, moment.e2, moment.e3);
+        Self { coeffs: c }
+    }
+
+    /// Plücker coordinates `(direction, moment)` of the bivector part; inverse of `from_line_parts`.
+    pub fn line_parts(&self) -> (Vector, Vector) {
+        let c = &self.coeffs;
+        (Vector::new(c[E23], -c[E13], c[E12]), Vector::new(c[E01], c[E02], c[E03]))
+    }
+
+    /// Point closest to the origin and unit direction of a (finite) line.
+    pub fn line_params(&self) -> Option<(Vector, Vector)> {
+        let (d, _) = self.line_parts();
+        let len = d.norm();
+        if len <= f64::EPSILON {
+            return None;
+        }
+        // The plane through the origin orthogonal to the line meets it at the closest point.
+        let orth = Self::plane(d.e1, d.e2, d.e3, 0.0);
+        let p = self.meet(&orth).to_point()?;
+        Some((p, d * (1.0 / len)))
+    }
+
+    /// Euclidean distance between two finite points.
+    pub fn distance(&self, other: &Self) -> Option<f64> {
+        Some((self.to_point()? - other.to_point()?).norm())
+    }
+
+    /// Signed distance from a point to a plane.
+    pub fn plane_distance(&self, plane: &Self) -> Option<f64> {
+        let p = self.to_point()?;
+        let (n, d) = plane.plane_params()?;
+        Some(n.dot(p) + d)
+    }
+
+    /// Orthogonal projection of `self` onto `target`: `(self . target) target`.
+    pub fn project_onto(&self, target: &Self) -> Self {
+        self.inner(target) * *target
+    }
+}
+
+impl Default for PgaMultiVector {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Even element of Cl(3,0,1) encoding a rigid-body transform (screw motion).
+///
+/// Applied with the sandwich `M x M~`; composition is the geometric product, with
+/// `a * b` applying `b` first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motor {
+    pub mv: PgaMultiVector,
+}
+
+impl Motor {
+    pub fn identity() -> Self {
+        Self { mv: PgaMultiVector::scalar(1.0) }
+    }
+
+    /// Motor translating by `t`.
+    pub fn from_translation(t: Vector) -> Self {
+        let mut c = [0.0; BLADES];
+        (c[0], c[E01], c[E02], c[E03]) = (1.0, -0.5 * t.e1, -0.5 * t.e2, -0.5 * t.e3);
+        Self { mv: PgaMultiVector::new(c) }
+    }
+
+    /// Motor rotating about the origin, from a Cl(3,0) rotor.
+    pub fn from_rotor(r: Rotor) -> Self {
+        let mut c = [0.0; BLADES];
+        // e31 = -e13
+        (c[0], c[E12], c[E23], c[E13]) = (r.s, r.e12, r.e23, -r.e31);
+        Self { mv: PgaMultiVector::new(c) }
+    }
+
+    /// Motor rotating by `angle` about the (finite) `axis` line, right-handed about its direction.
+    pub fn from_axis_angle(axis: &PgaMultiVector, angle: f64) -> Self {
+        Self::exp(&(axis.normalize() * (-0.5 * angle)))
+    }
+
+    /// Exponential of a bivector (line). `exp(-t/2 L)` rotates by `t` about a normalized line `L`.
+    pub fn exp(b: &PgaMultiVector) -> Self {
+        let c = &b.coeffs;
+        let (b01, b02, b03) = (c[E01], c[E02], c[E03]);
+        let (b12, b13, b23) = (c[E12], c[E13], c[E23]);
+        let l = b12 * b12 + b13 * b13 + b23 * b23;
+        let mut m = [0.0; BLADES];
+        if l <= f64::EPSILON * f64::EPSILON {
+            // pure translation: e0i square to zero
+            (m[0], m[E01], m[E02], m[E03]) = (1.0, b01, b02, b03);
+            return Self { mv: PgaMultiVector::new(m) };
+        }
+        // B ^ B = 2 w e0123 couples the rotational and translational parts
+        let w = b01 * b23 - b02 * b13 + b03 * b12;
+        let a = l.sqrt();
+        let (sin, cos) = a.sin_cos();
+        let s = sin / a;
+        let t = w / l * (cos - s);
+        m[0] = cos;
+        m[E12] = s * b12;
+        m[E13] = s * b13;
+        m[E23] = s * b23;
+        m[E01] = s * b01 + t * b23;
+        m[E02] = s * b02 - t * b13;
+        m[E03] = s * b03 + t * b12;
+        m[E0123] = w * s;
+        Self { mv: PgaMultiVector::new(m) }
+    }
+
+    /// Logarithm of a normalized motor: the bivector `B` with `exp(B) == self`.
+    pub fn log(&self) -> PgaMultiVector {
+        let c = &self.mv.coeffs;
+        let mut out = [0.0; BLADES];
+        let s = c[0].clamp(-1.0, 1.0);
+        let sin2 = 1.0 - s * s;
+        if sin2 <= 1e-12 {
+            (out[E01], out[E02], out[E03]) = (c[E01], c[E02], c[E03]);
+            return PgaMultiVector::new(out);
+        }
+        let a = 1.0 / sin2;
+        let b = s.acos() * a.sqrt();
+        let k = a * c[E0123] * (1.0 - s * b);
+        out[E12] = b * c[E12];
+        out[E13] = b * c[E13];
+        out[E23] = b * c[E23];
+        out[E01] = b * c[E01] + k * c[E23];
+        out[E02] = b * c[E02] - k * c[E13];
+        out[E03] = b * c[E03] + k * c[E12];
+        PgaMultiVector::new(out)
+    }
+
+    /// Screw linear interpolation: `a (a~ b)^t`, moving at constant screw velocity.
+    pub fn interpolate(a: &Self, b: &Self, t: f64) -> Self {
+        let rel = (a.reverse() * *b).normalize();
+        *a * Self::exp(&(rel.log() * t))
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self { mv: self.mv.reverse() }
+    }
+
+    /// Normalize so that `M M~ = 1`.
+    pub fn normalize(&self) -> Self {
+        let c = &self.mv.coeffs;
+        let s = self.mv.norm();
+        // remove the e0123 part of M M~ (Study number normalization)
+        let d = (c[0] * c[E0123] - c[E01] * c[E23] + c[E02] * c[E13] - c[E03] * c[E12]) / (s * s);
+        let mut corr = [0.0; BLADES];
+        (corr[0], corr[E0123]) = (1.0 / s, -d / s);
+        Self { mv: self.mv * PgaMultiVector::new(corr) }
+    }
+
+    /// Apply the sandwich `M x M~` to any element (point, line or plane).
+    pub fn apply(&self, x: &PgaMultiVector) -> PgaMultiVector {
+        self.mv * *x * self.mv.reverse()
+    }
+
+    pub fn transform_point(&self, p: Vector) -> Vector {
+        self.apply(&PgaMultiVector::point(p)).to_point().unwrap_or(p)
+    }
+
+    /// Rotation part as a Cl(3,0) rotor.
+    pub fn rotor(&self) -> Rotor {
+        let c = &self.mv.coeffs;
+        Rotor::new(c[0], c[E12], c[E23], -c[E13])
+    }
+
+    /// Translation part: where the motor moves the origin.
+    pub fn translation(&self) -> Vector {
+        self.transform_point(Vector::ZERO)
+    }
+}
+
+impl Default for Motor {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Forque (force line) of `force` applied at `point`: direction is the force, moment the torque.
+pub fn forque(point: Vector, force: Vector) -> PgaMultiVector {
+    PgaMultiVector::from_line_parts(force, point.cross(force))
+}
+
+/// Rigid body with its center of mass at the body origin and principal axes along `e1, e2, e3`.
+///
+/// The state is the body-to-world `motor` and the body-frame velocity line `rate`
+/// (angular velocity as direction, linear velocity of the center of mass as moment),
+/// evolving as `dM/dt = -M B / 2` under Newton-Euler dynamics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidBody {
+    pub motor: Motor,
+    pub rate: PgaMultiVector,
+    pub mass: f64,
+    /// Principal moments of inertia.
+    pub inertia: Vector,
+}
+
+impl RigidBody {
+    pub fn new(mass: f64, inertia: Vector) -> Self {
+        Self { motor: Motor::identity(), rate: PgaMultiVector::ZERO, mass, inertia }
+    }
+
+    /// Velocity line from body-frame angular and linear velocity.
+    pub fn velocity_line(angular: Vector, linear: Vector) -> PgaMultiVector {
+        PgaMultiVector::from_line_parts(angular, linear)
+    }
+
+    pub fn angular_velocity(&self) -> Vector {
+        self.rate.line_parts().0
+    }
+
+    pub fn linear_velocity(&self) -> Vector {
+        self.rate.line_parts().1
+    }
+
+    /// Inertia map `I[B]`: body-frame momentum line (linear momentum, angular momentum).
+    pub fn momentum(&self) -> PgaMultiVector {
+        let (w, v) = self.rate.line_parts();
+        PgaMultiVector::from_line_parts(v * self.mass, self.apply_inertia(w))
+    }
+
+    /// Momentum line in the world frame, conserved when no forque acts.
+    pub fn world_momentum(&self) -> PgaMultiVector {
+        self.motor.apply(&self.momentum())
+    }
+
+    pub fn kinetic_energy(&self) -> f64 {
+        let (w, v) = self.rate.line_parts();
+        0.5 * (self.mass * v.norm_squared() + w.dot(self.apply_inertia(w)))
+    }
+
+    fn apply_inertia(&self, w: Vector) -> Vector {
+        Vector::new(self.inertia.e1 * w.e1, self.inertia.e2 * w.e2, self.inertia.e3 * w.e3)
+    }
+
+    /// Time derivatives `(dM/dt, dB/dt)` under a body-frame forque.
+    fn derivative(&self, motor: &Motor, rate: &PgaMultiVector, body_forque: &PgaMultiVector) -> (PgaMultiVector, PgaMultiVector) {
+        let (w, v) = rate.line_parts();
+        let (f, tau) = body_forque.line_parts();
+        let iw = self.apply_inertia(w);
+        let t = tau - w.cross(iw);
+        let dw = Vector::new(t.e1 / self.inertia.e1, t.e2 / self.inertia.e2, t.e3 / self.inertia.e3);
+        let dv = f * (1.0 / self.mass) - w.cross(v);
+        (motor.mv * *rate * -0.5, PgaMultiVector::from_line_parts(dw, dv))
+    }
+
+    /// Advance by `dt` with classical RK4 under a constant body-frame forque,
+    /// renormalizing the motor afterwards.
+    pub fn step(&mut self, dt: f64, body_forque: &PgaMultiVector) {
+        let (m0, b0) = (self.motor, self.rate);
+        let offset = |dm: &PgaMultiVector, db: &PgaMultiVector, h: f64| {
+            (Motor { mv: m0.mv + *dm * h }, b0 + *db * h)
+        };
+        let (k1m, k1b) = self.derivative(&m0, &b0, body_forque);
+        let (m, b) = offset(&k1m, &k1b, 0.5 * dt);
+        let (k2m, k2b) = self.derivative(&m, &b, body_forque);
+        let (m, b) = offset(&k2m, &k2b, 0.5 * dt);
+        let (k3m, k3b) = self.derivative(&m, &b, body_forque);
+        let (m, b) = offset(&k3m, &k3b, dt);
+        let (k4m, k4b) = self.derivative(&m, &b, body_forque);
+        let dm = (k1m + (k2m + k3m) * 2.0 + k4m) * (dt / 6.0);
+        let db = (k1b + (k2b + k3b) * 2.0 + k4b) * (dt / 6.0);
+        self.motor = Motor { mv: m0.mv + dm }.normalize();
+        self.rate = b0 + db;
+    }
+
+    /// Convert a world-frame forque into the body frame.
+    pub fn to_body(&self, world: &PgaMultiVector) -> PgaMultiVector {
+        self.motor.reverse().apply(world)
+    }
+}
+
+/// Motor composition: `(a * b).apply(x) == a.apply(&b.apply(x))`.
+impl Mul for Motor {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self { mv: self.mv * rhs.mv }
+    }
+}
+
+// Display
+impl fmt::Display for PgaMultiVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = (0..BLADES).map(|m| blade::blade_name(m, 0)).collect();
+        notation::write_blades(f, &self.coeffs, &names, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::Bivector;
+    use std::f64::consts::FRAC_PI_2;
+
+    type Pga = PgaMultiVector;
+
+    fn assert_near(a: Vector, b: Vector, tol: f64) {
+        assert!((a - b).norm() < tol, "{a:?} != {b:?}");
+    }
+
+    /// Two motors act alike on a spread of test points (a motor and its negation agree).
+    fn assert_same_motion(a: &Motor, b: &Motor) {
+        let points = [
+            Vector::ZERO,
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, -2.0, 1.0),
+            Vector::new(3.0, 1.0, -1.0),
+        ];
+        for p in points {
+            assert_near(a.transform_point(p), b.transform_point(p), 1e-9);
+        }
+    }
+
+    /// Rodrigues rotation of `p` by `angle` about the line through `origin` along unit `axis`.
+    fn rotate_about(p: Vector, origin: Vector, axis: Vector, angle: f64) -> Vector {
+        let v = p - origin;
+        let (sin, cos) = angle.sin_cos();
+        origin + v * cos + axis.cross(v) * sin + axis * (axis.dot(v) * (1.0 - cos))
+    }
+
+    fn screw() -> Motor {
+        let axis = Pga::line(Vector::new(1.0, 2.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        Motor::from_translation(Vector::new(0.0, 0.0, 4.0)) * Motor::from_axis_angle(&axis, 2.0)
+    }
+
+    #[test]
+    fn join_and_meet() {
+        let (a, b) = (Vector::new(1.0, 0.0, 0.0), Vector::new(1.0, 2.0, 0.0));
+        let line = PgaMultiVector::point(a).join(&PgaMultiVector::point(b));
+        let x = line.meet(&PgaMultiVector::plane(0.0, 1.0, 0.0, -1.0)).to_point().unwrap();
+        assert!((x - Vector::new(1.0, 1.0, 0.0)).norm() < 1e-12);
+        assert_eq!(line.grade(2), line);
+        assert_eq!(line.reverse(), -line);
+    }
+
+    #[test]
+    fn from_axis_angle_is_right_handed_about_the_axis() {
+        let (origin, axis) = (Vector::new(1.0, 2.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        let m = Motor::from_axis_angle(&Pga::line(origin, axis), FRAC_PI_2);
+        assert_near(m.transform_point(Vector::new(2.0, 2.0, 0.0)), Vector::new(1.0, 3.0, 0.0), 1e-12);
+
+        let axis = Vector::new(1.0, -1.0, 2.0).normalize();
+        let line = Pga::line(origin, axis * 3.0);
+        for angle in [0.3, -1.2, 2.5] {
+            let m = Motor::from_axis_angle(&line, angle);
+            for p in [Vector::ZERO, Vector::new(0.5, 4.0, -1.0), origin + axis] {
+                assert_near(m.transform_point(p), rotate_about(p, origin, axis, angle), 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn exp_and_log_round_trip() {
+        let lines = [
+            Pga::line(Vector::new(1.0, 2.0, 0.0), Vector::new(0.0, 0.0, 1.0)) * 0.7,
+            Pga::from_line_parts(Vector::new(0.3, -0.4, 1.1), Vector::new(2.0, 0.5, -1.0)),
+            Pga::from_line_parts(Vector::ZERO, Vector::new(1.0, -3.0, 0.5)),
+        ];
+        for b in lines {
+            let m = Motor::exp(&b);
+            assert!((m.mv * m.mv.reverse() - Pga::scalar(1.0)).coeffs.iter().all(|c| c.abs() < 1e-12));
+            let back = m.log();
+            assert!((back - b.grade(2)).coeffs.iter().all(|c| c.abs() < 1e-12), "{back} != {b}");
+        }
+        let m = screw();
+        assert_same_motion(&Motor::exp(&m.log()), &m);
+    }
+
+    #[test]
+    fn interpolate_hits_the_endpoints_and_the_screw_midpoint() {
+        let a = Motor::from_translation(Vector::new(-1.0, 0.0, 0.5));
+        let b = screw() * a;
+        assert_same_motion(&Motor::interpolate(&a, &b, 0.0), &a);
+        assert_same_motion(&Motor::interpolate(&a, &b, 1.0), &b);
+
+        // halfway along the screw: half the rotation about the axis and half the advance along it
+        let mid = Motor::interpolate(&Motor::identity(), &screw(), 0.5);
+        let axis = Pga::line(Vector::new(1.0, 2.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+        let half = Motor::from_translation(Vector::new(0.0, 0.0, 2.0)) * Motor::from_axis_angle(&axis, 1.0);
+        assert_same_motion(&mid, &half);
+        assert_same_motion(&(mid * mid), &screw());
+    }
+
+    #[test]
+    fn composition_applies_the_right_motor_first() {
+        let r = Motor::from_rotor(Rotor::from_plane_angle(Bivector::new(0.0, 1.0, 0.0), 0.8));
+        let t = Motor::from_translation(Vector::new(1.0, -2.0, 3.0));
+        let s = screw();
+        let composed = s * r * t;
+        for p in [Vector::ZERO, Vector::new(1.0, 1.0, 1.0), Vector::new(-2.0, 0.5, 4.0)] {
+            let sequential = s.transform_point(r.transform_point(t.transform_point(p)));
+            assert_near(composed.transform_point(p), sequential, 1e-12);
+        }
+        let plane = Pga::plane(1.0, 2.0, -1.0, 0.5);
+        let (n, d) = composed.apply(&plane).plane_params().unwrap();
+        let (n2, d2) = s.apply(&r.apply(&t.apply(&plane))).plane_params().unwrap();
+        assert_near(n, n2, 1e-12);
+        assert!((d - d2).abs() < 1e-12);
+        assert_near(t.translation(), Vector::new(1.0, -2.0, 3.0), 1e-12);
+    }
+
+    #[test]
+    fn torque_free_body_conserves_momentum_and_energy() {
+        let mut body = RigidBody::new(2.0, Vector::new(1.0, 2.0, 3.0));
+        body.rate = RigidBody::velocity_line(Vector::new(0.3, 1.5, -0.2), Vector::new(0.5, 0.0, -1.0));
+        let (p0, e0) = (body.world_momentum(), body.kinetic_energy());
+        for _ in 0..2000 {
+            body.step(1e-3, &Pga::ZERO);
+        }
+        let drift = (body.world_momentum() - p0).coeffs.iter().fold(0.0f64, |m, c| m.max(c.abs()));
+        assert!(drift < 1e-8, "momentum drifted by {drift}");
+        assert!((body.kinetic_energy() - e0).abs() < 1e-8);
+        // the intermediate axis is unstable, so the body must actually have tumbled
+        assert!((body.angular_velocity() - Vector::new(0.3, 1.5, -0.2)).norm() > 0.1);
+    }
+
+    #[test]
+    fn constant_force_through_the_center_accelerates_uniformly() {
+        let mut body = RigidBody::new(2.0, Vector::new(1.0, 1.0, 1.0));
+        let push = forque(Vector::ZERO, Vector::new(4.0, 0.0, -2.0));
+        for _ in 0..100 {
+            body.step(1e-2, &push);
+        }
+        // a = F / m = (2, 0, -1) for one second
+        assert_near(body.linear_velocity(), Vector::new(2.0, 0.0, -1.0), 1e-9);
+        assert_near(body.motor.translation(), Vector::new(1.0, 0.0, -0.5), 1e-9);
+        assert_near(body.angular_velocity(), Vector::ZERO, 1e-12);
+
+        // an off-center force also spins the body, by tau = r x F
+        let mut body = RigidBody::new(1.0, Vector::new(2.0, 2.0, 2.0));
+        body.step(1e-3, &forque(Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)));
+        assert_near(body.angular_velocity(), Vector::new(0.0, 0.0, 0.5e-3), 1e-12);
+    }
+}
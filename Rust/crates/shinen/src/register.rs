@@ -0,0 +1,422 @@
+//! Rigid registration of corresponding point sets: the rotor (and motor) that best maps
+//! a source set onto a target set in the least-squares sense.
+//!
+//! The closed form follows Horn: maximizing `sum w_i y_i . (R x_i R~)` over unit rotors is a
+//! quadratic form in the four rotor coefficients, so the optimum is the dominant
+//! eigenvector of a symmetric 4x4 matrix.
+
+use std::fmt;
+
+use math_universe_keirin::PointCloud;
+
+use crate::grade::{Rotor, Vector};
+use crate::pga::Motor;
+use crate::rng::SplitMix64;
+
+/// Why a registration could not be computed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistrationError {
+    /// Source and target have different lengths.
+    LengthMismatch { source: usize, target: usize },
+    /// The weights do not match the number of correspondences.
+    WeightsMismatch { points: usize, weights: usize },
+    /// Fewer than three correspondences (or positively weighted ones).
+    TooFewPoints(usize),
+    /// A point does not have exactly three coordinates.
+    NotThreeDimensional { index: usize, dimension: usize },
+    /// No RANSAC hypothesis had enough inliers to refit.
+    NoConsensus,
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { source, target } => {
+                write!(f, "point sets differ in length: {} source, {} target", source, target)
+            }
+            Self::WeightsMismatch { points, weights } => {
+                write!(f, "{} weights given for {} correspondences", weights, points)
+            }
+            Self::TooFewPoints(n) => write!(f, "need at least 3 correspondences, got {}", n),
+            Self::NotThreeDimensional { index, dimension } => {
+                write!(f, "point {} has {} coordinates, expected 3", index, dimension)
+            }
+            Self::NoConsensus => write!(f, "no RANSAC hypothesis reached the minimum inlier count"),
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+/// Anything that can be read as a list of 3-D points.
+pub trait PointSet {
+    fn to_vectors(&self) -> Result<Vec<Vector>, RegistrationError>;
+}
+
+impl PointSet for [Vector] {
+    fn to_vectors(&self) -> Result<Vec<Vector>, RegistrationError> {
+        Ok(self.to_vec())
+    }
+}
+
+impl PointSet for Vec<Vector> {
+    fn to_vectors(&self) -> Result<Vec<Vector>, RegistrationError> {
+        Ok(self.clone())
+    }
+}
+
+impl PointSet for [[f64; 3]] {
+    fn to_vectors(&self) -> Result<Vec<Vector>, RegistrationError> {
+        Ok(self.iter().map(|&p| Vector::from(p)).collect())
+    }
+}
+
+impl PointSet for PointCloud {
+    fn to_vectors(&self) -> Result<Vec<Vector>, RegistrationError> {
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(index, p)| match p.as_slice() {
+                &[x, y, z] => Ok(Vector::new(x, y, z)),
+                _ => Err(RegistrationError::NotThreeDimensional { index, dimension: p.len() }),
+            })
+            .collect()
+    }
+}
+
+/// Best rigid transform `p -> R p R~ + translation` between two point sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Registration {
+    pub rotor: Rotor,
+    pub translation: Vector,
+    /// Root-mean-square (weighted, if weights were given) residual distance.
+    pub rms_error: f64,
+}
+
+impl Registration {
+    pub fn apply(&self, p: Vector) -> Vector {
+        self.rotor.apply(p) + self.translation
+    }
+
+    /// The same transform as a PGA motor (rotation about the origin, then translation).
+    pub fn motor(&self) -> Motor {
+        Motor::from_translation(self.translation) * Motor::from_rotor(self.rotor)
+    }
+}
+
+/// Result of `register_ransac`: the refitted registration and the inlier indices it used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RansacRegistration {
+    pub registration: Registration,
+    pub inliers: Vec<usize>,
+}
+
+/// Parameters of `register_ransac`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RansacOptions {
+    pub iterations: usize,
+    /// A correspondence is an inlier when its residual distance is at most this.
+    pub inlier_threshold: f64,
+    /// Hypotheses with fewer inliers are rejected.
+    pub min_inliers: usize,
+    pub seed: u64,
+}
+
+impl Default for RansacOptions {
+    fn default() -> Self {
+        Self { iterations: 500, inlier_threshold: 1e-2, min_inliers: 3, seed: 0 }
+    }
+}
+
+/// Least-squares rotor about the origin mapping `source[i]` onto `target[i]` (no translation).
+pub fn fit_rotor<S, T>(source: &S, target: &T) -> Result<Registration, RegistrationError>
+where
+    S: PointSet + ?Sized,
+    T: PointSet + ?Sized,
+{
+    let (x, y) = load(source, target)?;
+    let w = vec![1.0; x.len()];
+    solve(&x, &y, &w, false)
+}
+
+/// Least-squares rigid transform (rotor plus translation) mapping `source[i]` onto `target[i]`.
+pub fn register<S, T>(source: &S, target: &T) -> Result<Registration, RegistrationError>
+where
+    S: PointSet + ?Sized,
+    T: PointSet + ?Sized,
+{
+    let (x, y) = load(source, target)?;
+    let w = vec![1.0; x.len()];
+    solve(&x, &y, &w, true)
+}
+
+/// Weighted variant of `register`: correspondence `i` contributes `weights[i]` to the error.
+pub fn register_weighted<S, T>(source: &S, target: &T, weights: &[f64]) -> Result<Registration, RegistrationError>
+where
+    S: PointSet + ?Sized,
+    T: PointSet + ?Sized,
+{
+    let (x, y) = load(source, target)?;
+    if weights.len() != x.len() {
+        return Err(RegistrationError::WeightsMismatch { points: x.len(), weights: weights.len() });
+    }
+    solve(&x, &y, weights, true)
+}
+
+/// Robust `register`: fits minimal 3-point samples, keeps the hypothesis with the most
+/// inliers and refits on those inliers.
+pub fn register_ransac<S, T>(source: &S, target: &T, options: RansacOptions) -> Result<RansacRegistration, RegistrationError>
+where
+    S: PointSet + ?Sized,
+    T: PointSet + ?Sized,
+{
+    let (x, y) = load(source, target)?;
+    let n = x.len();
+    let mut rng = SplitMix64::new(options.seed);
+    let ones = [1.0; 3];
+    let mut best: Vec<usize> = Vec::new();
+    for _ in 0..options.iterations {
+        let idx = rng.sample(n, 3);
+        let xs: Vec<Vector> = idx.iter().map(|&i| x[i]).collect();
+        let ys: Vec<Vector> = idx.iter().map(|&i| y[i]).collect();
+        let Ok(hypothesis) = solve(&xs, &ys, &ones, true) else { continue };
+        let inliers: Vec<usize> = (0..n)
+            .filter(|&i| (hypothesis.apply(x[i]) - y[i]).norm() <= options.inlier_threshold)
+            .collect();
+        if inliers.len() > best.len() {
+            best = inliers;
+            if best.len() == n {
+                break;
+            }
+        }
+    }
+    if best.len() < options.min_inliers.max(3) {
+        return Err(RegistrationError::NoConsensus);
+    }
+    let xs: Vec<Vector> = best.iter().map(|&i| x[i]).collect();
+    let ys: Vec<Vector> = best.iter().map(|&i| y[i]).collect();
+    let registration = solve(&xs, &ys, &vec![1.0; best.len()], true)?;
+    Ok(RansacRegistration { registration, inliers: best })
+}
+
+fn load<S, T>(source: &S, target: &T) -> Result<(Vec<Vector>, Vec<Vector>), RegistrationError>
+where
+    S: PointSet + ?Sized,
+    T: PointSet + ?Sized,
+{
+    let (x, y) = (source.to_vectors()?, target.to_vectors()?);
+    if x.len() != y.len() {
+        return Err(RegistrationError::LengthMismatch { source: x.len(), target: y.len() });
+    }
+    if x.len() < 3 {
+        return Err(RegistrationError::TooFewPoints(x.len()));
+    }
+    Ok((x, y))
+}
+
+fn solve(x: &[Vector], y: &[Vector], w: &[f64], translate: bool) -> Result<Registration, RegistrationError> {
+    let total: f64 = w.iter().filter(|&&wi| wi > 0.0).sum();
+    let used = w.iter().filter(|&&wi| wi > 0.0).count();
+    if used < 3 {
+        return Err(RegistrationError::TooFewPoints(used));
+    }
+    let centroid = |p: &[Vector]| {
+        p.iter().zip(w).fold(Vector::ZERO, |acc, (&pi, &wi)| acc + pi * wi.max(0.0)) * (1.0 / total)
+    };
+    let (cx, cy) = if translate { (centroid(x), centroid(y)) } else { (Vector::ZERO, Vector::ZERO) };
+
+    // cross-covariance h[j][k] = sum w (x - cx)_j (y - cy)_k
+    let mut h = [[0.0; 3]; 3];
+    for ((&xi, &yi), &wi) in x.iter().zip(y).zip(w) {
+        let (a, b): ([f64; 3], [f64; 3]) = ((xi - cx).into(), (yi - cy).into());
+        for j in 0..3 {
+            for k in 0..3 {
+                h[j][k] += wi.max(0.0) * a[j] * b[k];
+            }
+        }
+    }
+
+    // Polarize the quadratic form f(R) = sum_jk h_jk (R e_j R~)_k on the rotor basis.
+    let basis = |a: usize| {
+        let mut c = [0.0; 4];
+        c[a] = 1.0;
+        c
+    };
+    let f = |c: [f64; 4]| {
+        let r = Rotor::new(c[0], c[1], c[2], c[3]);
+        let mut acc = 0.0;
+        for (j, row) in h.iter().enumerate() {
+            let mut e = [0.0; 3];
+            e[j] = 1.0;
+            let image: [f64; 3] = r.apply(Vector::from(e)).into();
+            acc += row.iter().zip(image).map(|(hjk, v)| hjk * v).sum::<f64>();
+        }
+        acc
+    };
+    let diag: [f64; 4] = std::array::from_fn(|a| f(basis(a)));
+    let mut n = [[0.0; 4]; 4];
+    for a in 0..4 {
+        n[a][a] = diag[a];
+        for b in a + 1..4 {
+            let mut c = basis(a);
+            c[b] = 1.0;
+            n[a][b] = 0.5 * (f(c) - diag[a] - diag[b]);
+            n[b][a] = n[a][b];
+        }
+    }
+    let q = dominant_eigenvector(n);
+    let rotor = Rotor::new(q[0], q[1], q[2], q[3]).normalize();
+    let translation = cy - rotor.apply(cx);
+
+    let sq: f64 = x
+        .iter()
+        .zip(y)
+        .zip(w)
+        .map(|((&xi, &yi), &wi)| wi.max(0.0) * (rotor.apply(xi) + translation - yi).norm_squared())
+        .sum();
+    Ok(Registration { rotor, translation, rms_error: (sq / total).sqrt() })
+}
+
+/// Eigenvector of the largest eigenvalue of a symmetric 4x4 matrix (cyclic Jacobi).
+fn dominant_eigenvector(mut a: [[f64; 4]; 4]) -> [f64; 4] {
+    let mut v = [[0.0; 4]; 4];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    for _ in 0..50 {
+        let off: f64 = (0..4).flat_map(|i| (0..4).filter(move |&j| j != i).map(move |j| (i, j))).map(|(i, j)| a[i][j] * a[i][j]).sum();
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..3 {
+            for q in p + 1..4 {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                for (k, (apk, aqk)) in row_p.into_iter().zip(row_q).enumerate() {
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+                for row in v.iter_mut() {
+                    let (vp, vq) = (row[p], row[q]);
+                    row[p] = c * vp - s * vq;
+                    row[q] = s * vp + c * vq;
+                }
+            }
+        }
+    }
+    let best = (0..4).max_by(|&i, &j| a[i][i].total_cmp(&a[j][j])).unwrap_or(0);
+    std::array::from_fn(|k| v[k][best])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::Bivector;
+
+    fn cloud() -> Vec<Vector> {
+        (0..20).map(|i| Vector::new((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos(), i as f64 * 0.1 - 1.0)).collect()
+    }
+
+    fn transform() -> (Rotor, Vector) {
+        (Rotor::from_plane_angle(Bivector::new(0.2, -0.5, 0.8), 1.2), Vector::new(0.5, -2.0, 1.0))
+    }
+
+    #[test]
+    fn recovers_exact_transform() {
+        let (rotor, t) = transform();
+        let source = cloud();
+        let target: Vec<Vector> = source.iter().map(|&p| rotor.apply(p) + t).collect();
+        let reg = register(&source, &target).unwrap();
+        assert!(reg.rms_error < 1e-9);
+        for &p in &source {
+            assert!((reg.apply(p) - (rotor.apply(p) + t)).norm() < 1e-9);
+            assert!((reg.motor().transform_point(p) - reg.apply(p)).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn ransac_rejects_outliers() {
+        let (rotor, t) = transform();
+        let source = cloud();
+        let mut target: Vec<Vector> = source.iter().map(|&p| rotor.apply(p) + t).collect();
+        for i in [2, 7, 11] {
+            target[i] = target[i] + Vector::new(3.0, 1.0, -2.0);
+        }
+        let fit = register_ransac(&source, &target, RansacOptions::default()).unwrap();
+        assert_eq!(fit.inliers.len(), 17);
+        assert!(!fit.inliers.contains(&7));
+        assert!(fit.registration.rms_error < 1e-9);
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        let source = cloud();
+        let short = source[..5].to_vec();
+        assert_eq!(register(&source, &short), Err(RegistrationError::LengthMismatch { source: 20, target: 5 }));
+        let two = source[..2].to_vec();
+        assert_eq!(register(&two, &two), Err(RegistrationError::TooFewPoints(2)));
+        let weights = vec![1.0; 19];
+        assert_eq!(
+            register_weighted(&source, &source, &weights),
+            Err(RegistrationError::WeightsMismatch { points: 20, weights: 19 })
+        );
+    }
+
+    #[test]
+    fn zero_weights_drop_outliers() {
+        let (rotor, t) = transform();
+        let source = cloud();
+        let mut target: Vec<Vector> = source.iter().map(|&p| rotor.apply(p) + t).collect();
+        let mut weights = vec![1.0; source.len()];
+        for i in [0, 5, 13] {
+            target[i] = target[i] + Vector::new(-4.0, 2.0, 1.0);
+            weights[i] = 0.0;
+        }
+        let reg = register_weighted(&source, &target, &weights).unwrap();
+        assert!(reg.rms_error < 1e-9);
+        assert!((reg.apply(source[5]) - (rotor.apply(source[5]) + t)).norm() < 1e-9);
+        // unweighted, the outliers pull the fit away
+        assert!(register(&source, &target).unwrap().rms_error > 0.1);
+
+        // scaling all weights changes nothing; fewer than three positive ones is too few
+        let scaled: Vec<f64> = weights.iter().map(|w| w * 5.0).collect();
+        let again = register_weighted(&source, &target, &scaled).unwrap();
+        assert!((again.translation - reg.translation).norm() < 1e-9);
+        let mut sparse = vec![0.0; source.len()];
+        sparse[1] = 1.0;
+        sparse[2] = 2.0;
+        assert_eq!(register_weighted(&source, &target, &sparse), Err(RegistrationError::TooFewPoints(2)));
+    }
+
+    #[test]
+    fn point_clouds_register_like_vectors() {
+        let (rotor, t) = transform();
+        let source = cloud();
+        let to_cloud = |ps: &[Vector]| PointCloud::new(ps.iter().map(|&p| <[f64; 3]>::from(p).to_vec()).collect());
+        let target: Vec<Vector> = source.iter().map(|&p| rotor.apply(p) + t).collect();
+        let (xs, ys) = (to_cloud(&source), to_cloud(&target));
+        assert_eq!(xs.to_vectors().unwrap(), source);
+        let reg = register(&xs, &ys).unwrap();
+        assert!(reg.rms_error < 1e-9);
+        assert!((reg.translation - register(&source, &target).unwrap().translation).norm() < 1e-9);
+
+        let mut flat = xs.clone();
+        flat.points[4] = vec![1.0, 2.0];
+        let expected = RegistrationError::NotThreeDimensional { index: 4, dimension: 2 };
+        assert_eq!(register(&flat, &ys), Err(expected.clone()));
+        assert_eq!(register(&ys, &flat), Err(expected));
+        flat.points[4] = vec![1.0, 2.0, 3.0, 4.0];
+        assert_eq!(fit_rotor(&flat, &ys), Err(RegistrationError::NotThreeDimensional { index: 4, dimension: 4 }));
+    }
+}
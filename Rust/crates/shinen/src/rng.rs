@@ -0,0 +1,81 @@
+//! A small deterministic pseudo-random generator for algorithms that want reproducible
+//! randomness (hypothesis sampling in RANSAC registration) without a runtime dependency
+//! on `rand`.
+
+/// SplitMix64 (Steele, Lea and Flood): a Weyl sequence passed through a 64-bit mixing
+/// function. Fast and statistically sound for algorithmic use; not cryptographic.
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n` (Lemire's multiply-and-reject). Panics if `n == 0`.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "cannot sample from an empty range");
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let m = self.next_u64() as u128 * n as u128;
+            if m as u64 >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+
+    /// `k` distinct indices from `0..n`, each `k`-subset equally likely (Floyd's algorithm).
+    ///
+    /// Panics if `k > n`.
+    pub(crate) fn sample(&mut self, n: usize, k: usize) -> Vec<usize> {
+        assert!(k <= n, "cannot sample {} distinct indices from {}", k, n);
+        let mut chosen = Vec::with_capacity(k);
+        for j in n - k..n {
+            let t = self.below(j as u64 + 1) as usize;
+            chosen.push(if chosen.contains(&t) { j } else { t });
+        }
+        chosen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_sequence() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+    }
+
+    #[test]
+    fn samples_are_distinct_and_uniform() {
+        let mut rng = SplitMix64::new(7);
+        let mut counts = [0usize; 6];
+        for _ in 0..20_000 {
+            let idx = rng.sample(6, 3);
+            assert!(idx.iter().all(|&i| i < 6));
+            assert!(idx[0] != idx[1] && idx[0] != idx[2] && idx[1] != idx[2], "{:?}", idx);
+            for i in idx {
+                counts[i] += 1;
+            }
+        }
+        // each index lands in half of the 3-subsets
+        assert!(counts.iter().all(|&c| (9_000..11_000).contains(&c)), "{:?}", counts);
+        let mut all = rng.sample(5, 5);
+        all.sort_unstable();
+        assert_eq!(all, vec![0, 1, 2, 3, 4]);
+        assert!(rng.sample(4, 0).is_empty());
+    }
+}
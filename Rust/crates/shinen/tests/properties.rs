@@ -0,0 +1,135 @@
+//! Randomized checks of the algebraic laws the products must satisfy, on inputs drawn
+//! from a seeded generator so failures are reproducible.
+
+use math_universe_shinen::blade::{verify_product, BasisLayout, CayleyTable, Signature};
+use math_universe_shinen::{Bivector, CgaMultiVector, MultiVector, PgaMultiVector, Rotor, Trivector, Vector};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SAMPLES: usize = 500;
+const TOLERANCE: f64 = 1e-12;
+
+fn max_diff(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).fold(0.0, |m, (x, y)| m.max((x - y).abs()))
+}
+
+fn coeffs<const N: usize>(rng: &mut StdRng) -> [f64; N] {
+    std::array::from_fn(|_| rng.gen_range(-1.0..1.0))
+}
+
+fn random_mv(rng: &mut StdRng) -> MultiVector {
+    MultiVector::new(coeffs(rng))
+}
+
+fn random_vector(rng: &mut StdRng) -> Vector {
+    let [e1, e2, e3] = coeffs(rng);
+    Vector::new(e1, e2, e3)
+}
+
+fn random_rotor(rng: &mut StdRng) -> Rotor {
+    let [e12, e23, e31] = coeffs(rng);
+    Rotor::from_plane_angle(Bivector::new(e12, e23, e31), rng.gen_range(-std::f64::consts::PI..std::f64::consts::PI))
+}
+
+/// Run `check` on `SAMPLES` seeded draws and fail with the seed's sample index if it
+/// reports an error above `TOLERANCE`.
+fn for_samples(seed: u64, check: impl Fn(&mut StdRng) -> f64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    for i in 0..SAMPLES {
+        let err = check(&mut rng);
+        assert!(err <= TOLERANCE, "sample {} (seed {}): error {}", i, seed, err);
+    }
+}
+
+#[test]
+fn multivector_product_matches_cayley_table() {
+    let table = CayleyTable::new(&Signature::new(3, 0, 0).metric(), BasisLayout::cl3());
+    let checked = verify_product(&table, |a, b| {
+        (MultiVector::new(a.try_into().unwrap()) * MultiVector::new(b.try_into().unwrap())).coeffs.to_vec()
+    });
+    assert_eq!(checked, Ok(()));
+}
+
+#[test]
+fn associativity() {
+    for_samples(1, |rng| {
+        let (a, b, c) = (random_mv(rng), random_mv(rng), random_mv(rng));
+        max_diff(&((a * b) * c).coeffs, &(a * (b * c)).coeffs)
+    });
+}
+
+#[test]
+fn distributivity() {
+    for_samples(2, |rng| {
+        let (a, b, c) = (random_mv(rng), random_mv(rng), random_mv(rng));
+        let left = max_diff(&(a * (b + c)).coeffs, &(a * b + a * c).coeffs);
+        left.max(max_diff(&((a + b) * c).coeffs, &(a * c + b * c).coeffs))
+    });
+}
+
+#[test]
+fn reverse_is_an_antiautomorphism() {
+    for_samples(3, |rng| {
+        let (a, b) = (random_mv(rng), random_mv(rng));
+        max_diff(&(a * b).reverse().coeffs, &(b.reverse() * a.reverse()).coeffs)
+    });
+}
+
+#[test]
+fn rotors_preserve_norms() {
+    for_samples(4, |rng| {
+        let (rotor, v) = (random_rotor(rng), random_vector(rng));
+        let rotated = MultiVector::from(v).rotate(rotor.into());
+        let norm = (Vector::from(rotated).norm() - v.norm()).abs();
+        norm.max(max_diff(&rotated.coeffs, &MultiVector::from(rotor.apply(v)).coeffs))
+    });
+}
+
+#[test]
+fn graded_products_agree_with_multivector() {
+    for_samples(5, |rng| {
+        let (u, w) = (random_vector(rng), random_vector(rng));
+        let (bu, bw) = (Bivector::from(random_mv(rng)), Bivector::from(random_mv(rng)));
+        let t = Trivector::from(random_mv(rng));
+        let (r1, r2) = (random_rotor(rng), random_rotor(rng));
+        let (mu, mw) = (MultiVector::from(u), MultiVector::from(w));
+        let (mbu, mbw, mt) = (MultiVector::from(bu), MultiVector::from(bw), MultiVector::from(t));
+        [
+            max_diff(&MultiVector::from(u * w).coeffs, &(mu * mw).coeffs),
+            max_diff(&MultiVector::from(bu * bw).coeffs, &(mbu * mbw).coeffs),
+            max_diff(&MultiVector::from(u * t).coeffs, &(mu * mt).coeffs),
+            max_diff(&MultiVector::from(bu * t).coeffs, &(mbu * mt).coeffs),
+            max_diff(&[t * t], &[(mt * mt).coeffs[0]]),
+            max_diff(&MultiVector::from(r1 * r2).coeffs, &(MultiVector::from(r1) * MultiVector::from(r2)).coeffs),
+        ]
+        .into_iter()
+        .fold(0.0, f64::max)
+    });
+}
+
+#[test]
+fn cga_and_pga_products_are_associative() {
+    for_samples(6, |rng| {
+        let [a, b, c] = [(); 3].map(|_| CgaMultiVector::new(coeffs(rng)));
+        let [p, q, r] = [(); 3].map(|_| PgaMultiVector::new(coeffs(rng)));
+        let cga = max_diff(&((a * b) * c).coeffs, &(a * (b * c)).coeffs);
+        cga.max(max_diff(&((p * q) * r).coeffs, &(p * (q * r)).coeffs))
+    });
+}
+
+#[test]
+fn matrix_representations() {
+    for_samples(7, |rng| {
+        let (a, b) = (random_mv(rng), random_mv(rng));
+        let (rotor, v) = (random_rotor(rng), random_vector(rng));
+        let (sa, sb, sab) = (a.to_pauli(), b.to_pauli(), (a * b).to_pauli());
+        let m = rotor.to_rotation_matrix();
+        let image: [f64; 3] = rotor.apply(v).into();
+        (0..2)
+            .flat_map(|i| (0..2).map(move |j| (i, j)))
+            .map(|(i, j)| (sa[i][0] * sb[0][j] + sa[i][1] * sb[1][j] - sab[i][j]).norm())
+            .chain((0..3).map(|i| (m[i][0] * v.e1 + m[i][1] * v.e2 + m[i][2] * v.e3 - image[i]).abs()))
+            .fold(0.0, f64::max)
+            .max(max_diff(&MultiVector::from_pauli(&sa).coeffs, &a.coeffs))
+    });
+}
@@ -1,5 +1,8 @@
 use crate::scalar::Scalar;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use num_traits::{FromPrimitive, Num, One, Zero};
+use std::cmp::Ordering;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
 use std::fmt;
 
 /// Dual number implementation for Forward Mode Automatic Differentiation.
@@ -89,6 +92,111 @@ impl<T: Scalar> Neg for Dual<T> {
     }
 }
 
+impl<T: Scalar> Rem for Dual<T> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        // a % b = a - q*b with q = trunc(a/b) locally constant
+        let r = self.real % rhs.real;
+        let q = (self.real - r) / rhs.real;
+        Self {
+            real: r,
+            dual: self.dual - q * rhs.dual,
+        }
+    }
+}
+
+impl<T: Scalar> AddAssign for Dual<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Scalar> SubAssign for Dual<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Scalar> MulAssign for Dual<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Scalar> DivAssign for Dual<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: Scalar> RemAssign for Dual<T> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+// num-traits integration, so that Dual<T> is itself a Scalar
+// and can be nested or used as a coefficient type elsewhere.
+impl<T: Scalar> Zero for Dual<T> {
+    fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.real.is_zero() && self.dual.is_zero()
+    }
+}
+
+impl<T: Scalar> One for Dual<T> {
+    fn one() -> Self {
+        Self::constant(T::one())
+    }
+}
+
+impl<T: Scalar> Num for Dual<T> {
+    type FromStrRadixErr = T::FromStrRadixErr;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        T::from_str_radix(str, radix).map(Self::constant)
+    }
+}
+
+impl<T: Scalar> FromPrimitive for Dual<T> {
+    fn from_i64(n: i64) -> Option<Self> {
+        T::from_i64(n).map(Self::constant)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        T::from_u64(n).map(Self::constant)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        T::from_f64(n).map(Self::constant)
+    }
+}
+
+impl<T: Scalar> Sum for Dual<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<T: Scalar> Product for Dual<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+/// Ordered by the real part; ties are broken by the dual part to stay consistent with `PartialEq`.
+impl<T: Scalar> PartialOrd for Dual<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.real.partial_cmp(&other.real) {
+            Some(Ordering::Equal) => self.dual.partial_cmp(&other.dual),
+            ord => ord,
+        }
+    }
+}
+
 // Mathematical Functions (Sin, Cos, etc.) using chain rule
 impl<T: Scalar> Dual<T> {
     pub fn sin(self) -> Self {
@@ -128,6 +236,51 @@ impl<T: Scalar> Dual<T> {
     }
 }
 
+impl<T: Scalar> Scalar for Dual<T> {
+    fn abs(self) -> Self {
+        if self.real < T::zero() { -self } else { self }
+    }
+
+    fn sqrt(self) -> Self {
+        let sqrt_real = self.real.sqrt();
+        Self {
+            real: sqrt_real,
+            dual: self.dual / (sqrt_real + sqrt_real),
+        }
+    }
+
+    fn sin(self) -> Self {
+        Dual::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        Dual::cos(self)
+    }
+
+    fn exp(self) -> Self {
+        Dual::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        Self {
+            real: self.real.ln(),
+            dual: self.dual / self.real,
+        }
+    }
+
+    fn powf(self, n: Self) -> Self {
+        if n.dual.is_zero() {
+            return Dual::powf(self, n.real);
+        }
+        // x^n = exp(n ln x): d = x^n * (n' ln x + n x' / x)
+        let pow_real = self.real.powf(n.real);
+        Self {
+            real: pow_real,
+            dual: pow_real * (n.dual * self.real.ln() + n.real * self.dual / self.real),
+        }
+    }
+}
+
 // Python interop (impl via separate pyclass struct in wrapper usually, 
 // but if we want Dual to be pyclass, we need PyO3 dep in Zigen.
 // The plan said "Python feature flags" in main crate?
@@ -147,3 +300,126 @@ impl<T: Scalar + Into<f64> + From<f64>> Dual<T> {
     // PyO3 limitation: generics on pyclass are hard.
     // Usually we expose `DualF64` to Python.
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Derivative of `f` at `x` by forward-mode differentiation.
+    fn derivative(f: impl Fn(Dual<f64>) -> Dual<f64>, x: f64) -> f64 {
+        f(Dual::variable(x)).dual
+    }
+
+    /// Central difference `(f(x + h) - f(x - h)) / 2h`, evaluating `f` on constants.
+    fn central(f: impl Fn(Dual<f64>) -> Dual<f64>, x: f64) -> f64 {
+        let h = 1e-6;
+        (f(Dual::constant(x + h)).real - f(Dual::constant(x - h)).real) / (2.0 * h)
+    }
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() <= tol * (1.0 + b.abs()), "{} vs {}", a, b);
+    }
+
+    #[test]
+    fn arithmetic_follows_the_product_and_quotient_rules() {
+        let (x, y) = (Dual::new(3.0, 1.0), Dual::new(-2.0, 0.5));
+        assert_eq!(x + y, Dual::new(1.0, 1.5));
+        assert_eq!(x - y, Dual::new(5.0, 0.5));
+        assert_eq!(x * y, Dual::new(-6.0, -2.0 + 1.5));
+        assert_eq!(x / y, Dual::new(-1.5, (1.0 * -2.0 - 3.0 * 0.5) / 4.0));
+        assert_eq!(-x, Dual::new(-3.0, -1.0));
+        let mut z = x;
+        z += y;
+        z -= y;
+        assert_eq!(z, x);
+        z *= y;
+        assert_eq!(z, x * y);
+        z /= y;
+        assert_close(z.real, x.real, 1e-15);
+        assert_close(z.dual, x.dual, 1e-15);
+        z %= Dual::constant(2.0);
+        assert_eq!(z.real, 1.0);
+    }
+
+    #[test]
+    fn remainder_is_differentiable_away_from_jumps() {
+        // x mod 2.5 has slope 1 in x; (7 mod y) = 7 - 2 y near y = 3 has slope -2
+        for x in [0.3, 1.7, 4.1, -3.2] {
+            assert_eq!(derivative(|x| x % Dual::constant(2.5), x), 1.0);
+            assert_close(derivative(|x| x % Dual::constant(2.5), x), central(|x| x % Dual::constant(2.5), x), 1e-8);
+        }
+        let r = Dual::constant(7.0) % Dual::variable(3.0);
+        assert_eq!((r.real, r.dual), (1.0, -2.0));
+        let mut a = Dual::variable(7.0);
+        a %= Dual::constant(3.0);
+        assert_eq!((a.real, a.dual), (1.0, 1.0));
+    }
+
+    #[test]
+    fn elementary_functions_match_closed_forms() {
+        for x in [0.2, 0.9, 1.5, 3.7] {
+            assert_close(derivative(|x| x.sin(), x), x.cos(), 1e-15);
+            assert_close(derivative(|x| x.cos(), x), -x.sin(), 1e-15);
+            assert_close(derivative(|x| x.exp(), x), x.exp(), 1e-15);
+            assert_close(derivative(Scalar::ln, x), 1.0 / x, 1e-15);
+            assert_close(derivative(Scalar::sqrt, x), 0.5 / x.sqrt(), 1e-15);
+            assert_close(derivative(|x| x.powf(2.5), x), 2.5 * x.powf(1.5), 1e-14);
+            assert_close(derivative(|x| Scalar::powf(x, Dual::constant(-1.5)), x), -1.5 * x.powf(-2.5), 1e-14);
+            // d/dy x^y = x^y ln x, and d/dx x^x = x^x (ln x + 1)
+            let xy = Scalar::powf(Dual::constant(x), Dual::variable(1.3));
+            assert_close(xy.dual, x.powf(1.3) * x.ln(), 1e-14);
+            assert_close(derivative(|x| Scalar::powf(x, x), x), x.powf(x) * (x.ln() + 1.0), 1e-14);
+        }
+    }
+
+    #[test]
+    fn abs_flips_the_derivative_with_the_sign() {
+        assert_eq!(derivative(Scalar::abs, 2.0), 1.0);
+        assert_eq!(derivative(Scalar::abs, -2.0), -1.0);
+        assert_eq!(Scalar::abs(Dual::new(-2.0, 3.0)), Dual::new(2.0, -3.0));
+    }
+
+    #[test]
+    fn compositions_agree_with_finite_differences() {
+        let f = |x: Dual<f64>| {
+            let two = Dual::constant(2.0);
+            Scalar::sqrt(x * x + two).sin() * Scalar::ln(x.exp() + x) / (x.cos() + two)
+        };
+        for x in [0.1, 0.8, 2.0, 5.5] {
+            assert_close(derivative(f, x), central(f, x), 1e-8);
+        }
+    }
+
+    #[test]
+    fn sums_products_and_conversions() {
+        let xs = [Dual::new(1.0, 1.0), Dual::new(2.0, 0.0), Dual::new(3.0, -1.0)];
+        assert_eq!(xs.iter().copied().sum::<Dual<f64>>(), Dual::new(6.0, 0.0));
+        // (x y z)' = x' y z + x y' z + x y z'
+        assert_eq!(xs.iter().copied().product::<Dual<f64>>(), Dual::new(6.0, 6.0 - 2.0));
+        assert_eq!(Dual::<f64>::from_i64(-4), Some(Dual::constant(-4.0)));
+        assert_eq!(Dual::<f64>::from_u64(7), Some(Dual::constant(7.0)));
+        assert_eq!(Dual::<f64>::from_f64(0.25), Some(Dual::constant(0.25)));
+        assert_eq!(Dual::<f64>::from_str_radix("ff", 16).ok(), Some(Dual::constant(255.0)));
+        assert_eq!(Dual::<f64>::from_str_radix("1.5", 10).ok(), Some(Dual::constant(1.5)));
+        assert!(Dual::<f64>::zero().is_zero() && !Dual::new(0.0, 1.0).is_zero());
+        assert_eq!(Dual::<f64>::one(), Dual::constant(1.0));
+    }
+
+    #[test]
+    fn ordering_uses_the_real_part_first() {
+        assert!(Dual::new(1.0, 5.0) < Dual::new(2.0, -5.0));
+        assert!(Dual::new(1.0, -1.0) < Dual::new(1.0, 1.0));
+        assert_eq!(Dual::new(1.0, 1.0).partial_cmp(&Dual::new(1.0, 1.0)), Some(Ordering::Equal));
+        assert_eq!(Dual::new(f64::NAN, 0.0).partial_cmp(&Dual::new(1.0, 0.0)), None);
+    }
+
+    #[test]
+    fn nested_duals_give_second_derivatives() {
+        // f(x) = x^3 sin x; f'' = 6 x sin x + 6 x^2 cos x - x^3 sin x
+        let x = 1.2;
+        let v = Dual::new(Dual::new(x, 1.0), Dual::new(1.0, 0.0));
+        let f = v * v * v * v.sin();
+        let expected = 6.0 * x * x.sin() + 6.0 * x * x * x.cos() - x * x * x * x.sin();
+        assert_close(f.dual.dual, expected, 1e-13);
+    }
+}
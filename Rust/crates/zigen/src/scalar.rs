@@ -1,7 +1,7 @@
 use num_traits::{Float, FromPrimitive, Num, NumAssign, NumOps, One, Zero};
 use std::fmt::{Debug, Display};
 use std::iter::{Product, Sum};
-use std::ops::{AddAssign, DivAssign, MulAssign, Neg, SubAssign};
+use std::ops::Neg;
 
 /// A trait representing a scalar value that can be used in Zigen for dual numbers.
 ///